@@ -0,0 +1,313 @@
+//! End-to-end harness for the deployed artifact.
+//!
+//! Builds `xrpl_token_dao.wasm` for `wasm32-unknown-unknown`, loads it into
+//! wasmi and serves its host imports (`env`, plus the xrpl-wasm-stdlib
+//! `host_lib` functions it uses) from a scripted `Ledger`. Tests
+//! in `tests/` drive whole governance scenarios through the exported entry
+//! points, so they cover the exact bytes we deploy rather than the `rlib`
+//! the unit tests link against.
+//!
+//! Set `DAO_WASM` to test a prebuilt artifact instead of building one, and
+//! `DAO_FEATURES` to build with extra features (e.g. `compact-state`).
+
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use wasmi::{Caller, Engine, Extern, Instance, Linker, Module, Store, WasmParams, WasmResults};
+use xrpl_token_dao::foundation::codec;
+use xrpl_token_dao::foundation::host::{SF_MEMOS, SF_MEMO_DATA};
+
+/// Size of an XRPL AccountID
+pub const ACCOUNT_ID_SIZE: usize = 20;
+/// Size of the escrow Data field
+pub const FIELD_CAPACITY: usize = 4096;
+
+/// Host error code returned for failed imports
+const HOST_ERROR: i32 = -1;
+
+/// The ledger as seen by one escrow: its Data field, the clock, and the
+/// transaction currently being applied.
+#[derive(Debug, Clone, Default)]
+pub struct Ledger {
+    /// The escrow Data field, as stored by the contract
+    pub data: Vec<u8>,
+    /// Parent ledger close time, in seconds
+    pub time: u32,
+    /// Account submitting the current transaction
+    pub caller: [u8; ACCOUNT_ID_SIZE],
+    /// Account reported from the second `get_current_account` read on
+    pub spoofed_caller: Option<[u8; ACCOUNT_ID_SIZE]>,
+    /// `Memos[i].MemoData` of the current transaction
+    pub memos: Vec<Vec<u8>>,
+    /// Number of successful `set_data` calls
+    pub writes: u32,
+    /// Transactions submitted through `emit_transaction`, in order
+    pub emitted: Vec<Emitted>,
+    account_reads: u32,
+}
+
+/// One transaction the contract emitted from the DAO's account.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Emitted {
+    pub tx_type: i32,
+    pub destination: [u8; ACCOUNT_ID_SIZE],
+    /// XRP amount in drops
+    pub amount: i64,
+    /// Type-specific fields, as passed by the contract
+    pub payload: Vec<u8>,
+}
+
+impl Ledger {
+    /// Begin a new transaction from `caller` without memos.
+    pub fn submit(&mut self, caller: [u8; ACCOUNT_ID_SIZE]) {
+        self.caller = caller;
+        self.spoofed_caller = None;
+        self.memos.clear();
+        self.emitted.clear();
+        self.account_reads = 0;
+    }
+
+    /// The Data field as key=value text, decoding a `compact-state` blob.
+    pub fn text(&self) -> String {
+        let mut text = vec![0u8; 4 * FIELD_CAPACITY];
+        let len = codec::to_text(&self.data, &mut text).expect("decode Data field");
+        String::from_utf8_lossy(&text[..len]).into_owned()
+    }
+
+    /// Value of `key` in the Data field text.
+    pub fn value(&self, key: &str) -> Option<String> {
+        self.text()
+            .split(';')
+            .filter_map(|entry| entry.split_once('='))
+            .find(|(k, _)| *k == key)
+            .map(|(_, v)| v.to_string())
+    }
+}
+
+/// An instantiated contract bound to its ledger.
+pub struct Contract {
+    store: Store<Ledger>,
+    instance: Instance,
+}
+
+impl Contract {
+    /// Build (or locate) the artifact and instantiate it on an empty ledger.
+    pub fn deploy() -> Contract {
+        let wasm = std::fs::read(wasm_path()).expect("read contract wasm");
+        Contract::from_wasm(&wasm, Ledger::default())
+    }
+
+    /// Instantiate `wasm` on `ledger`.
+    pub fn from_wasm(wasm: &[u8], ledger: Ledger) -> Contract {
+        let engine = Engine::default();
+        let module = Module::new(&engine, wasm).expect("invalid contract wasm");
+        let mut store = Store::new(&engine, ledger);
+        let linker = linker(&engine);
+        let instance = linker
+            .instantiate(&mut store, &module)
+            .and_then(|pre| pre.start(&mut store))
+            .expect("instantiate contract");
+        Contract { store, instance }
+    }
+
+    pub fn ledger(&self) -> &Ledger {
+        self.store.data()
+    }
+
+    pub fn ledger_mut(&mut self) -> &mut Ledger {
+        self.store.data_mut()
+    }
+
+    /// Begin a transaction from `caller`, optionally with a memo 0 payload.
+    pub fn submit(&mut self, caller: [u8; ACCOUNT_ID_SIZE], memo: Option<&str>) -> &mut Self {
+        let ledger = self.ledger_mut();
+        ledger.submit(caller);
+        if let Some(memo) = memo {
+            ledger.memos.push(memo.as_bytes().to_vec());
+        }
+        self
+    }
+
+    /// Append the next memo to the pending transaction.
+    pub fn with_memo(&mut self, memo: &str) -> &mut Self {
+        self.ledger_mut().memos.push(memo.as_bytes().to_vec());
+        self
+    }
+
+    /// Move the ledger clock forward.
+    pub fn advance(&mut self, seconds: u32) {
+        self.ledger_mut().time += seconds;
+    }
+
+    /// Call export `name`. Traps (panics, out-of-bounds, unreachable) fail
+    /// the test with the export name.
+    pub fn call<P: WasmParams, R: WasmResults>(&mut self, name: &str, params: P) -> R {
+        let func = self
+            .instance
+            .get_typed_func::<P, R>(&self.store, name)
+            .unwrap_or_else(|e| panic!("export `{name}`: {e}"));
+        func.call(&mut self.store, params)
+            .unwrap_or_else(|e| panic!("`{name}` trapped: {e}"))
+    }
+}
+
+/// Path to the contract artifact: `DAO_WASM` if set, else a fresh release
+/// build into `target/harness` (a separate target dir, so the nested cargo
+/// does not wait on the lock held by `cargo test`).
+pub fn wasm_path() -> PathBuf {
+    if let Some(path) = std::env::var_os("DAO_WASM") {
+        return PathBuf::from(path);
+    }
+
+    let root = Path::new(env!("CARGO_MANIFEST_DIR")).parent().unwrap().to_path_buf();
+    let target_dir = root.join("target").join("harness");
+    let mut cargo = Command::new(std::env::var("CARGO").unwrap_or_else(|_| "cargo".into()));
+    cargo
+        .current_dir(&root)
+        .args(["build", "--release", "--target", "wasm32-unknown-unknown", "-p", "xrpl-token-dao"])
+        .env("CARGO_TARGET_DIR", &target_dir);
+    if let Ok(features) = std::env::var("DAO_FEATURES") {
+        cargo.args(["--features", &features]);
+    }
+    let status = cargo.status().expect("run cargo");
+    assert!(status.success(), "building the contract for wasm32-unknown-unknown failed");
+
+    target_dir.join("wasm32-unknown-unknown/release/xrpl_token_dao.wasm")
+}
+
+/// Hex-encode an AccountID the way the contract stores it (lowercase).
+pub fn hex(account: &[u8; ACCOUNT_ID_SIZE]) -> String {
+    account.iter().map(|b| format!("{b:02x}")).collect()
+}
+
+/// A recognisable AccountID built from one seed byte.
+pub fn account(seed: u8) -> [u8; ACCOUNT_ID_SIZE] {
+    let mut account = [0u8; ACCOUNT_ID_SIZE];
+    account[0] = seed;
+    account[ACCOUNT_ID_SIZE - 1] = seed;
+    account
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Host imports
+// ═══════════════════════════════════════════════════════════════════════
+
+fn memory_read(caller: &Caller<'_, Ledger>, ptr: i32, len: usize) -> Option<Vec<u8>> {
+    let memory = caller.get_export("memory").and_then(Extern::into_memory)?;
+    let mut buf = vec![0u8; len];
+    memory.read(caller, ptr as u32 as usize, &mut buf).ok()?;
+    Some(buf)
+}
+
+fn memory_write(caller: &mut Caller<'_, Ledger>, ptr: i32, bytes: &[u8]) -> bool {
+    match caller.get_export("memory").and_then(Extern::into_memory) {
+        Some(memory) => memory.write(caller, ptr as u32 as usize, bytes).is_ok(),
+        None => false,
+    }
+}
+
+fn linker(engine: &Engine) -> Linker<Ledger> {
+    let mut linker = Linker::new(engine);
+
+    linker
+        .func_wrap("env", "get_data", |mut caller: Caller<'_, Ledger>, ptr: i32, len: i32| -> i32 {
+            let data = caller.data().data.clone();
+            if data.len() > len as u32 as usize || !memory_write(&mut caller, ptr, &data) {
+                return HOST_ERROR;
+            }
+            data.len() as i32
+        })
+        .unwrap();
+
+    linker
+        .func_wrap("env", "set_data", |mut caller: Caller<'_, Ledger>, ptr: i32, len: i32| -> i32 {
+            let len = len as u32 as usize;
+            if len > FIELD_CAPACITY {
+                return HOST_ERROR;
+            }
+            match memory_read(&caller, ptr, len) {
+                Some(bytes) => {
+                    let ledger = caller.data_mut();
+                    ledger.data = bytes;
+                    ledger.writes += 1;
+                    0
+                }
+                None => HOST_ERROR,
+            }
+        })
+        .unwrap();
+
+    linker
+        .func_wrap("env", "get_current_account", |mut caller: Caller<'_, Ledger>, ptr: i32, len: i32| -> i32 {
+            if (len as u32 as usize) < ACCOUNT_ID_SIZE {
+                return HOST_ERROR;
+            }
+            let ledger = caller.data_mut();
+            ledger.account_reads += 1;
+            let account = match ledger.spoofed_caller {
+                Some(spoofed) if ledger.account_reads > 1 => spoofed,
+                _ => ledger.caller,
+            };
+            if !memory_write(&mut caller, ptr, &account) {
+                return HOST_ERROR;
+            }
+            ACCOUNT_ID_SIZE as i32
+        })
+        .unwrap();
+
+    linker
+        .func_wrap("env", "get_current_ledger_time", |caller: Caller<'_, Ledger>| -> i64 {
+            caller.data().time as i64
+        })
+        .unwrap();
+
+    linker
+        .func_wrap(
+            "env",
+            "emit_transaction",
+            |mut caller: Caller<'_, Ledger>, tx_type: i32, dest: i32, amount: i64, ptr: i32, len: i32| -> i32 {
+                let Some(destination) = memory_read(&caller, dest, ACCOUNT_ID_SIZE) else {
+                    return HOST_ERROR;
+                };
+                let Some(payload) = memory_read(&caller, ptr, len as u32 as usize) else {
+                    return HOST_ERROR;
+                };
+                caller.data_mut().emitted.push(Emitted {
+                    tx_type,
+                    destination: destination.try_into().unwrap(),
+                    amount,
+                    payload,
+                });
+                0
+            },
+        )
+        .unwrap();
+
+    linker
+        .func_wrap(
+            "host_lib",
+            "get_tx_nested_field",
+            |mut caller: Caller<'_, Ledger>, loc_ptr: i32, loc_len: i32, ptr: i32, len: i32| -> i32 {
+                let Some(locator) = memory_read(&caller, loc_ptr, loc_len as u32 as usize) else {
+                    return HOST_ERROR;
+                };
+                let codes: Vec<i32> = locator
+                    .chunks_exact(4)
+                    .map(|c| i32::from_le_bytes([c[0], c[1], c[2], c[3]]))
+                    .collect();
+                let memo = match codes.as_slice() {
+                    [SF_MEMOS, index, SF_MEMO_DATA] => caller.data().memos.get(*index as usize).cloned(),
+                    _ => None,
+                };
+                match memo {
+                    Some(memo) if memo.len() <= len as u32 as usize && memory_write(&mut caller, ptr, &memo) => {
+                        memo.len() as i32
+                    }
+                    _ => HOST_ERROR,
+                }
+            },
+        )
+        .unwrap();
+
+    linker
+}
@@ -0,0 +1,154 @@
+//! Full governance scenarios against the compiled contract.
+
+use xrpl_token_dao::foundation::config::{
+    ERR_ALREADY_INITIALIZED, ERR_BAD_PARAM, ERR_CALLER_VERIFICATION, ERR_DESCRIPTION_MISMATCH,
+    ERR_NOT_EXECUTOR, ERR_PROPOSAL_NOT_ACTIVE, SETTING_QUORUM_PERCENTAGE,
+    SUCCESS, TIMELOCK_MIN_DELAY, VOTE_AGAINST, VOTE_FOR, VOTING_DELAY, VOTING_PERIOD,
+};
+use xrpl_token_dao_harness::{account, hex, Contract, Emitted};
+
+fn alice() -> [u8; 20] { account(0xAA) }
+fn bob() -> [u8; 20] { account(0xBB) }
+fn dave() -> [u8; 20] { account(0xDD) }
+fn eve() -> [u8; 20] { account(0xEE) }
+
+/// Alice (admin + proposer, 1000 XRP), Bob (500 XRP), Dave (executor).
+fn deploy_dao() -> Contract {
+    let mut dao = Contract::deploy();
+    dao.ledger_mut().time = 1_000_000;
+    let spec = format!(
+        "admin={a};member_0={a}:1000000000:5;member_1={b}:500000000:0;member_2={d}:0:2",
+        a = hex(&alice()),
+        b = hex(&bob()),
+        d = hex(&dave()),
+    );
+    dao.submit(alice(), Some(&spec));
+    assert_eq!(dao.call::<(), i32>("initialize", ()), SUCCESS);
+    dao
+}
+
+const DESCRIPTION: &str = "Pay eve 1 XRP for the audit";
+
+/// Propose a 1 XRP payment to eve as alice; returns the hex proposal id.
+fn propose(dao: &mut Contract) -> String {
+    let memo = format!("actions={}:1000000:0:", hex(&eve()));
+    dao.submit(alice(), Some(&memo)).with_memo(DESCRIPTION);
+    assert_eq!(dao.call::<(), i32>("propose", ()), SUCCESS);
+    let index = dao.ledger().value("proposal_count").unwrap().parse::<u32>().unwrap() - 1;
+    dao.ledger().value(&format!("prop_{index}_id")).unwrap()
+}
+
+/// Submit a transaction from `caller` naming proposal `id`.
+fn submit_id(dao: &mut Contract, caller: [u8; 20], id: &str) {
+    dao.submit(caller, Some(&format!("id={id}")));
+}
+
+fn vote(dao: &mut Contract, voter: [u8; 20], id: &str, support: u8) -> i32 {
+    submit_id(dao, voter, id);
+    dao.call::<i32, i32>("cast_vote", i32::from(support))
+}
+
+#[test]
+fn propose_vote_queue_execute() {
+    let mut dao = deploy_dao();
+    let id = propose(&mut dao);
+
+    // Voting opens after the delay
+    assert_eq!(vote(&mut dao, bob(), &id, VOTE_FOR), ERR_PROPOSAL_NOT_ACTIVE);
+    dao.advance(VOTING_DELAY + 1);
+    assert_eq!(vote(&mut dao, alice(), &id, VOTE_FOR), SUCCESS);
+    assert_eq!(vote(&mut dao, bob(), &id, VOTE_AGAINST), SUCCESS);
+
+    // Queue only once voting has ended
+    submit_id(&mut dao, eve(), &id);
+    assert_eq!(dao.call::<(), i32>("queue", ()), ERR_PROPOSAL_NOT_ACTIVE);
+    dao.advance(VOTING_PERIOD);
+    assert_eq!(dao.call::<(), i32>("queue", ()), SUCCESS);
+    assert_eq!(dao.ledger().value("prop_0_state").as_deref(), Some("5"));
+    assert_eq!(dao.ledger().value("prop_0_act_count").as_deref(), Some("1"));
+
+    // Anyone can check the description against the stored hash
+    submit_id(&mut dao, eve(), &id);
+    dao.with_memo(DESCRIPTION);
+    assert_eq!(dao.call::<(), i32>("verify_description", ()), SUCCESS);
+    submit_id(&mut dao, eve(), &id);
+    dao.with_memo("Pay eve 100 XRP for the audit");
+    assert_eq!(dao.call::<(), i32>("verify_description", ()), ERR_DESCRIPTION_MISMATCH);
+
+    // Execute: executor only, after the timelock delay
+    submit_id(&mut dao, bob(), &id);
+    assert_eq!(dao.call::<(), i32>("execute", ()), ERR_NOT_EXECUTOR);
+    submit_id(&mut dao, dave(), &id);
+    assert!(dao.call::<(), i32>("execute", ()) < 0);
+    dao.advance(TIMELOCK_MIN_DELAY);
+    submit_id(&mut dao, dave(), &id);
+    assert_eq!(dao.call::<(), i32>("execute", ()), SUCCESS);
+
+    let ledger = dao.ledger();
+    assert_eq!(
+        ledger.emitted,
+        vec![Emitted { tx_type: 0, destination: eve(), amount: 1_000_000, payload: Vec::new() }]
+    );
+    assert_eq!(ledger.value("prop_0_state").as_deref(), Some("7"));
+    assert_eq!(ledger.value("prop_0_res_0").as_deref(), Some("1"));
+    assert_eq!(ledger.value("_exec"), None);
+    assert_eq!(dao.call::<(), i32>("audit", ()), SUCCESS);
+
+    // Executed proposals do not run twice
+    submit_id(&mut dao, dave(), &id);
+    assert!(dao.call::<(), i32>("execute", ()) < 0);
+    assert!(dao.ledger().emitted.is_empty());
+}
+
+#[test]
+fn defeated_proposal_cannot_be_queued() {
+    let mut dao = deploy_dao();
+    let id = propose(&mut dao);
+    dao.advance(VOTING_DELAY + 1);
+    assert_eq!(vote(&mut dao, alice(), &id, VOTE_AGAINST), SUCCESS);
+    dao.advance(VOTING_PERIOD);
+
+    submit_id(&mut dao, eve(), &id);
+    assert_eq!(dao.call::<(), i32>("queue", ()), ERR_PROPOSAL_NOT_ACTIVE);
+}
+
+#[test]
+fn spoofed_caller_is_rejected() {
+    let mut dao = deploy_dao();
+    let id = propose(&mut dao);
+    dao.advance(VOTING_DELAY + 1);
+
+    let writes = dao.ledger().writes;
+    submit_id(&mut dao, eve(), &id);
+    dao.ledger_mut().spoofed_caller = Some(alice());
+    assert_eq!(dao.call::<i32, i32>("cast_vote", i32::from(VOTE_FOR)), ERR_CALLER_VERIFICATION);
+    assert_eq!(dao.ledger().writes, writes);
+}
+
+#[test]
+fn initialize_runs_once() {
+    let mut dao = deploy_dao();
+    let spec = format!("admin={}", hex(&alice()));
+    dao.submit(alice(), Some(&spec));
+    assert_eq!(dao.call::<(), i32>("initialize", ()), ERR_ALREADY_INITIALIZED);
+}
+
+#[test]
+fn memo_parameters_and_queries() {
+    let mut dao = deploy_dao();
+
+    dao.submit(alice(), None);
+    assert_eq!(dao.call::<(), i32>("propose", ()), ERR_BAD_PARAM);
+
+    dao.submit(bob(), Some(&format!("delegatee={}", hex(&alice()))));
+    assert_eq!(dao.call::<(), i32>("delegate_votes", ()), SUCCESS);
+
+    assert_eq!(dao.call::<i32, i64>("get_setting", i32::from(SETTING_QUORUM_PERCENTAGE)), 4);
+    assert_eq!(dao.call::<i32, i64>("get_past_total_supply", 1_000_000), 1_500_000_000);
+    dao.submit(eve(), Some(&format!("account={}", hex(&alice()))));
+    assert_eq!(dao.call::<i32, i64>("get_past_votes", 1_000_000), 1_500_000_000);
+    assert_eq!(dao.call::<i32, i64>("get_past_votes", 999_999), 0);
+    let usage = dao.call::<(), i32>("data_usage", ());
+    assert_eq!((usage >> 16) as usize, dao.ledger().data.len());
+    assert_eq!(dao.call::<(), i32>("audit", ()), SUCCESS);
+}
@@ -0,0 +1,513 @@
+//! dao-sim — rehearse governance actions off-chain.
+//!
+//! Keeps one DAO's Data field and ledger clock in a local file and applies
+//! commands through the contract's own `exports` functions on a `MockHost`,
+//! so every check (caller roles, thresholds, quorum, timelock delays) is the
+//! one the deployed contract runs.
+//!
+//! ```text
+//! dao-sim init --as <admin> [--member <hex>:<power>:<roles>]... [--set cfg_quorum=10]...
+//! dao-sim propose --as <account> --desc <text>|--desc-file <path> --action <target>:<drops>:<tx type>:<payload hex>...
+//! dao-sim verify-desc --desc <text>|--desc-file <path> [--id <proposal>]
+//! dao-sim vote --as <account> --support for|against|abstain [--id <proposal>]
+//! dao-sim advance-time 3d
+//! dao-sim queue [--id <proposal>]
+//! dao-sim execute --as <executor> [--id <proposal>]
+//! dao-sim cancel --as <proposer> [--id <proposal>]
+//! dao-sim delegate --as <account> --to <account>
+//! dao-sim add-member --as <admin> --account <hex> --power <drops> --roles <bits>
+//! dao-sim remove-member --as <admin> --account <hex>
+//! dao-sim past-votes --at <ledger time> [--account <hex>]
+//! dao-sim show
+//! ```
+//!
+//! `--state <path>` (default `dao.state`) selects the file. `--id` takes a
+//! 64-digit hex proposal ID and defaults to the most recent proposal.
+//! Accounts are 40-digit hex AccountIDs.
+
+use std::fmt::Write as _;
+use std::process::ExitCode;
+
+use xrpl_token_dao::crypto::hex::{decode_hex, encode_hex};
+use xrpl_token_dao::exports;
+use xrpl_token_dao::foundation::config::*;
+use xrpl_token_dao::foundation::host::MockHost;
+use xrpl_token_dao::foundation::types::{Actions, Member, Proposal, TimelockOp};
+use xrpl_token_dao::governance::governor::{get_proposal_state, read_count};
+use xrpl_token_dao::governance::settings::{Settings, SETTING_KEYS};
+use xrpl_token_dao::governance::votes;
+use xrpl_token_dao::token::xrp_votes;
+
+const DEFAULT_STATE: &str = "dao.state";
+const TIME_PREFIX: &str = "ledger_time=";
+
+type Account = [u8; ACCOUNT_ID_SIZE];
+
+fn main() -> ExitCode {
+    match run(std::env::args().skip(1).collect()) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(message) => {
+            eprintln!("dao-sim: {message}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(argv: Vec<String>) -> Result<(), String> {
+    let args = Args::parse(argv)?;
+    let path = args.flag("state").unwrap_or(DEFAULT_STATE).to_string();
+
+    let mut host = if args.command == "init" {
+        if std::path::Path::new(&path).exists() {
+            return Err(format!("{path} already exists"));
+        }
+        MockHost::new()
+    } else {
+        load(&path)?
+    };
+
+    let rc = match args.command.as_str() {
+        "init" => {
+            let admin = args.account("as")?;
+            let mut spec = format!("admin={}", hex(&admin));
+            for (i, member) in args.all("member").enumerate() {
+                write!(spec, ";member_{i}={member}").unwrap();
+            }
+            for setting in args.all("set") {
+                write!(spec, ";{setting}").unwrap();
+            }
+            host.call_as(&admin).with_memo(spec.as_bytes());
+            exports::initialize(&mut host)
+        }
+        "propose" => {
+            let actions: Vec<&str> = args.all("action").collect();
+            if actions.is_empty() {
+                return Err("propose needs at least one --action".into());
+            }
+            let memo = format!("actions={}", actions.join(","));
+            let description = description(&args)?;
+            host.call_as(&args.account("as")?).with_memo(memo.as_bytes()).with_memo(&description);
+            exports::propose(&mut host)
+        }
+        "verify-desc" => {
+            let memo = format!("id={}", hex(&proposal_id(&host, &args)?));
+            let description = description(&args)?;
+            host.call_as(&[0u8; ACCOUNT_ID_SIZE]).with_memo(memo.as_bytes()).with_memo(&description);
+            return match exports::verify_description(&mut host) {
+                SUCCESS => {
+                    println!("description matches");
+                    Ok(())
+                }
+                ERR_DESCRIPTION_MISMATCH => Err("description does not match the proposal".into()),
+                rc => Err(format!("verify-desc failed with error {rc}")),
+            };
+        }
+        "past-votes" => {
+            let at: u32 = args.required("at")?.parse().map_err(|_| "--at takes a ledger time in seconds")?;
+            host.call_as(&[0u8; ACCOUNT_ID_SIZE]);
+            println!("total voting power at {at}: {}", exports::get_past_total_supply(&mut host, at));
+            if args.flag("account").is_some() {
+                let account = args.account("account")?;
+                let memo = format!("account={}", hex(&account));
+                host.call_as(&[0u8; ACCOUNT_ID_SIZE]).with_memo(memo.as_bytes());
+                println!("votes of {} at {at}: {}", hex(&account), exports::get_past_votes(&mut host, at));
+            }
+            return Ok(());
+        }
+        "vote" => {
+            let support = match args.required("support")? {
+                "against" => VOTE_AGAINST,
+                "for" => VOTE_FOR,
+                "abstain" => VOTE_ABSTAIN,
+                other => return Err(format!("unknown support `{other}`")),
+            };
+            let memo = format!("id={}", hex(&proposal_id(&host, &args)?));
+            host.call_as(&args.account("as")?).with_memo(memo.as_bytes());
+            exports::cast_vote(&mut host, support)
+        }
+        "queue" => {
+            let memo = format!("id={}", hex(&proposal_id(&host, &args)?));
+            let caller = if args.flag("as").is_some() { args.account("as")? } else { [0u8; ACCOUNT_ID_SIZE] };
+            host.call_as(&caller).with_memo(memo.as_bytes());
+            exports::queue(&mut host)
+        }
+        "execute" => {
+            let memo = format!("id={}", hex(&proposal_id(&host, &args)?));
+            host.call_as(&args.account("as")?).with_memo(memo.as_bytes());
+            exports::execute(&mut host)
+        }
+        "cancel" => {
+            let memo = format!("id={}", hex(&proposal_id(&host, &args)?));
+            host.call_as(&args.account("as")?).with_memo(memo.as_bytes());
+            exports::cancel(&mut host)
+        }
+        "delegate" => {
+            let memo = format!("delegatee={}", hex(&args.account("to")?));
+            host.call_as(&args.account("as")?).with_memo(memo.as_bytes());
+            exports::delegate_votes(&mut host)
+        }
+        "add-member" => {
+            let memo = format!(
+                "account={};power={};roles={}",
+                hex(&args.account("account")?),
+                args.required("power")?,
+                args.required("roles")?,
+            );
+            host.call_as(&args.account("as")?).with_memo(memo.as_bytes());
+            exports::add_member(&mut host)
+        }
+        "remove-member" => {
+            let memo = format!("account={}", hex(&args.account("account")?));
+            host.call_as(&args.account("as")?).with_memo(memo.as_bytes());
+            exports::remove_member(&mut host)
+        }
+        "advance-time" => {
+            let duration = args.positional.first().ok_or("advance-time needs a duration")?;
+            host.advance(parse_duration(duration)?);
+            SUCCESS
+        }
+        "show" => {
+            print!("{}", show(&host));
+            return Ok(());
+        }
+        other => return Err(format!("unknown command `{other}`")),
+    };
+
+    if rc < 0 {
+        return Err(format!("{} failed with error {rc}", args.command));
+    }
+    save(&path, &host)
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// State file
+// ═══════════════════════════════════════════════════════════════════════
+
+/// `ledger_time=<secs>\n` followed by the raw Data field.
+fn load(path: &str) -> Result<MockHost, String> {
+    let bytes = std::fs::read(path).map_err(|e| format!("{path}: {e} (run `dao-sim init` first)"))?;
+    let newline = bytes.iter().position(|&b| b == b'\n').ok_or(format!("{path}: missing header"))?;
+    let header = std::str::from_utf8(&bytes[..newline]).map_err(|_| format!("{path}: bad header"))?;
+    let time = header
+        .strip_prefix(TIME_PREFIX)
+        .and_then(|t| t.parse().ok())
+        .ok_or(format!("{path}: bad header"))?;
+
+    let data = &bytes[newline + 1..];
+    let mut host = MockHost::new();
+    if data.len() > host.data.len() {
+        return Err(format!("{path}: Data field larger than {} bytes", host.data.len()));
+    }
+    host.data[..data.len()].copy_from_slice(data);
+    host.data_len = data.len();
+    host.time = time;
+    Ok(host)
+}
+
+fn save(path: &str, host: &MockHost) -> Result<(), String> {
+    let mut bytes = format!("{TIME_PREFIX}{}\n", host.time).into_bytes();
+    bytes.extend_from_slice(&host.data[..host.data_len]);
+    std::fs::write(path, bytes).map_err(|e| format!("{path}: {e}"))
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Output
+// ═══════════════════════════════════════════════════════════════════════
+
+fn show(host: &MockHost) -> String {
+    let (text, len) = host.state();
+    let data = &text[..len];
+    let total = votes::get_total_voting_power(data);
+    let settings = Settings::load(data);
+    let mut out = String::new();
+
+    writeln!(out, "ledger time  {}", host.time).unwrap();
+    writeln!(out, "data field   {} / {} bytes", host.data_len, host.data.len()).unwrap();
+
+    writeln!(out, "\nsettings").unwrap();
+    for (param, key) in SETTING_KEYS.iter().enumerate() {
+        let value = settings.get(param as u8).unwrap_or_default();
+        writeln!(out, "  {:<18} {value}", String::from_utf8_lossy(key)).unwrap();
+    }
+
+    writeln!(out, "\nmembers (total voting power {total}, quorum for new proposals {})", settings.quorum(total)).unwrap();
+    for i in 0..votes::get_member_count(data) {
+        let Some(member) = Member::load(data, i) else { continue };
+        write!(
+            out,
+            "  {}  power {:>14}  effective {:>14}  roles {}",
+            hex(&member.account),
+            member.voting_power,
+            xrp_votes::get_effective_votes(data, &member.account),
+            role_names(member.roles),
+        )
+        .unwrap();
+        let delegate = xrp_votes::get_delegate(data, &member.account);
+        if delegate != member.account {
+            write!(out, "  -> {}", hex(&delegate)).unwrap();
+        }
+        out.push('\n');
+    }
+
+    writeln!(out, "\nproposals").unwrap();
+    for i in 0..read_count(data, b"proposal_count") {
+        let Some(p) = Proposal::load(data, i) else { continue };
+        let state = get_proposal_state(data, i, host.time);
+        write!(
+            out,
+            "  #{}  {:<9} votes {} for / {} against / {} abstain  window {}..{}",
+            hex(&p.id),
+            proposal_state_name(state),
+            p.for_votes,
+            p.against_votes,
+            p.abstain_votes,
+            p.vote_start,
+            p.vote_end,
+        )
+        .unwrap();
+        if p.vote_extension != 0 {
+            write!(out, "  (late quorum +{}s)", p.vote_extension).unwrap();
+        }
+        if p.eta != 0 {
+            write!(out, "  eta {}", p.eta).unwrap();
+        }
+        writeln!(
+            out,
+            "\n      quorum {} of supply {}  description {}",
+            p.quorum,
+            p.total_supply,
+            hex(&p.description_hash),
+        )
+        .unwrap();
+        for (k, action) in Actions::load(data, i).unwrap_or_default().as_slice().iter().enumerate() {
+            let payload: String = action.payload().iter().map(|b| format!("{b:02x}")).collect();
+            write!(
+                out,
+                "      tx type {:>3}  {} drops -> {}  payload {}",
+                action.tx_type,
+                action.amount,
+                hex(&action.target),
+                if payload.is_empty() { "-" } else { &payload },
+            )
+            .unwrap();
+            match Actions::result(data, i, k as u16) {
+                Some(SUCCESS) => out.push_str("  emitted"),
+                Some(code) => write!(out, "  failed ({code})").unwrap(),
+                None => {}
+            }
+            out.push('\n');
+        }
+    }
+
+    writeln!(out, "\ntimelock operations").unwrap();
+    for i in 0..read_count(data, b"op_count") {
+        let Some(op) = TimelockOp::load(data, i) else { continue };
+        writeln!(
+            out,
+            "  op {}  proposal #{}  {:<7} ready at {}",
+            hex(&op.id),
+            hex(&op.proposal_id),
+            op_state_name(op.state),
+            op.ready_at,
+        )
+        .unwrap();
+    }
+    out
+}
+
+fn proposal_state_name(state: u8) -> &'static str {
+    match state {
+        PROPOSAL_STATE_PENDING => "Pending",
+        PROPOSAL_STATE_ACTIVE => "Active",
+        PROPOSAL_STATE_CANCELED => "Canceled",
+        PROPOSAL_STATE_DEFEATED => "Defeated",
+        PROPOSAL_STATE_SUCCEEDED => "Succeeded",
+        PROPOSAL_STATE_QUEUED => "Queued",
+        PROPOSAL_STATE_EXPIRED => "Expired",
+        PROPOSAL_STATE_EXECUTED => "Executed",
+        _ => "?",
+    }
+}
+
+fn op_state_name(state: u8) -> &'static str {
+    match state {
+        OP_STATE_PENDING => "Pending",
+        OP_STATE_READY => "Ready",
+        OP_STATE_DONE => "Done",
+        OP_STATE_EXPIRED => "Expired",
+        _ => "Unset",
+    }
+}
+
+fn role_names(roles: u8) -> String {
+    let names: Vec<&str> = [
+        (ROLE_PROPOSER, "proposer"),
+        (ROLE_EXECUTOR, "executor"),
+        (ROLE_ADMIN, "admin"),
+        (ROLE_CANCELLER, "canceller"),
+    ]
+    .iter()
+    .filter(|(bit, _)| roles & bit != 0)
+    .map(|(_, name)| *name)
+    .collect();
+    if names.is_empty() { "-".into() } else { names.join(",") }
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Arguments
+// ═══════════════════════════════════════════════════════════════════════
+
+struct Args {
+    command: String,
+    positional: Vec<String>,
+    flags: Vec<(String, String)>,
+}
+
+impl Args {
+    fn parse(argv: Vec<String>) -> Result<Args, String> {
+        let mut iter = argv.into_iter();
+        let mut args = Args { command: String::new(), positional: Vec::new(), flags: Vec::new() };
+        while let Some(arg) = iter.next() {
+            if let Some(name) = arg.strip_prefix("--") {
+                let value = iter.next().ok_or(format!("--{name} needs a value"))?;
+                args.flags.push((name.to_string(), value));
+            } else if args.command.is_empty() {
+                args.command = arg;
+            } else {
+                args.positional.push(arg);
+            }
+        }
+        if args.command.is_empty() {
+            return Err("no command (try `show`)".into());
+        }
+        Ok(args)
+    }
+
+    fn flag(&self, name: &str) -> Option<&str> {
+        self.flags.iter().rev().find(|(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    fn all<'a>(&'a self, name: &'a str) -> impl Iterator<Item = &'a str> {
+        self.flags.iter().filter(move |(n, _)| n == name).map(|(_, v)| v.as_str())
+    }
+
+    fn required(&self, name: &str) -> Result<&str, String> {
+        self.flag(name).ok_or(format!("{} needs --{name}", self.command))
+    }
+
+    fn account(&self, name: &str) -> Result<Account, String> {
+        let value = self.required(name)?;
+        let mut account = [0u8; ACCOUNT_ID_SIZE];
+        if value.len() != 2 * ACCOUNT_ID_SIZE || !decode_hex(value.as_bytes(), &mut account) {
+            return Err(format!("--{name}: expected a 40-digit hex AccountID"));
+        }
+        Ok(account)
+    }
+}
+
+/// `--id`, or the most recent proposal.
+fn proposal_id(host: &MockHost, args: &Args) -> Result<[u8; HASH_SIZE], String> {
+    if let Some(id) = args.flag("id") {
+        let mut hash = [0u8; HASH_SIZE];
+        if id.len() != 2 * HASH_SIZE || !decode_hex(id.as_bytes(), &mut hash) {
+            return Err(format!("--id: expected a 64-digit hex proposal id, got `{id}`"));
+        }
+        return Ok(hash);
+    }
+    let (text, len) = host.state();
+    let count = read_count(&text[..len], b"proposal_count");
+    count
+        .checked_sub(1)
+        .and_then(|last| Proposal::load(&text[..len], last))
+        .map(|p| p.id)
+        .ok_or("no proposals yet".into())
+}
+
+/// `--desc <text>`, or the contents of `--desc-file <path>`.
+fn description(args: &Args) -> Result<Vec<u8>, String> {
+    let text = match (args.flag("desc"), args.flag("desc-file")) {
+        (Some(text), None) => text.as_bytes().to_vec(),
+        (None, Some(path)) => std::fs::read(path).map_err(|e| format!("{path}: {e}"))?,
+        _ => return Err("pass one of --desc or --desc-file".into()),
+    };
+    if text.is_empty() || text.len() > MEMO_CAPACITY {
+        return Err(format!("description must be 1 to {MEMO_CAPACITY} bytes"));
+    }
+    Ok(text)
+}
+
+/// `90`, `90s`, `15m`, `6h`, `3d` → seconds.
+fn parse_duration(text: &str) -> Result<u32, String> {
+    let (digits, unit) = match text.find(|c: char| !c.is_ascii_digit()) {
+        Some(at) => text.split_at(at),
+        None => (text, "s"),
+    };
+    let scale = match unit {
+        "s" => 1,
+        "m" => 60,
+        "h" => 3_600,
+        "d" => 86_400,
+        _ => return Err(format!("bad duration `{text}`")),
+    };
+    digits
+        .parse::<u32>()
+        .ok()
+        .and_then(|n| n.checked_mul(scale))
+        .ok_or(format!("bad duration `{text}`"))
+}
+
+/// Lowercase hex of an AccountID or 256-bit ID.
+fn hex(bytes: &[u8]) -> String {
+    let mut buf = [0u8; 2 * HASH_SIZE];
+    let buf = &mut buf[..2 * bytes.len()];
+    encode_hex(bytes, buf);
+    String::from_utf8_lossy(buf).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn run_in(path: &str, line: &str) -> Result<(), String> {
+        let mut argv: Vec<String> = line.split_whitespace().map(String::from).collect();
+        argv.extend(["--state".to_string(), path.to_string()]);
+        run(argv)
+    }
+
+    #[test]
+    fn test_parse_duration() {
+        assert_eq!(parse_duration("90"), Ok(90));
+        assert_eq!(parse_duration("15m"), Ok(900));
+        assert_eq!(parse_duration("3d"), Ok(259_200));
+        assert!(parse_duration("3w").is_err());
+        assert!(parse_duration("d").is_err());
+    }
+
+    #[test]
+    fn test_state_file_round_trip() {
+        let path = std::env::temp_dir().join(format!("dao-sim-{}.state", std::process::id()));
+        let path = path.to_str().unwrap();
+        let _ = std::fs::remove_file(path);
+        let admin = "AA000000000000000000000000000000000000AA";
+
+        run_in(path, &format!("init --as {admin} --member {admin}:1000000000:5")).unwrap();
+        assert!(run_in(path, &format!("init --as {admin}")).is_err());
+        run_in(path, "advance-time 1h").unwrap();
+        let action = format!("{admin}:1000000:0:");
+        assert!(run_in(path, &format!("propose --as {admin} --action {action}")).is_err());
+        run_in(path, &format!("propose --as {admin} --desc Rotate-the-signer-list --action {action}")).unwrap();
+        run_in(path, "verify-desc --desc Rotate-the-signer-list").unwrap();
+        assert!(run_in(path, "verify-desc --desc Rotate-the-signers").is_err());
+        run_in(path, &format!("past-votes --at 0 --account {admin}")).unwrap();
+        assert!(run_in(path, "past-votes --at yesterday").is_err());
+
+        let host = load(path).unwrap();
+        assert_eq!(host.time, 3_600);
+        let id = proposal_id(&host, &Args::parse(vec!["show".into()]).unwrap()).unwrap();
+        assert!(show(&host).contains(&format!("#{}  Pending", hex(&id))));
+        run_in(path, &format!("cancel --as {admin} --id {}", hex(&id))).unwrap();
+        assert!(show(&load(path).unwrap()).contains("Canceled"));
+        assert!(run_in(path, &format!("cancel --as {admin} --id 42")).is_err());
+        std::fs::remove_file(path).unwrap();
+    }
+}
@@ -1,92 +1,127 @@
-//! Deterministic hash function for proposal and operation IDs.
+//! Proposal and operation IDs, and the archive digest.
 //!
-//! Replaces the weak `description_hash ^ current_time` with a proper
-//! multi-input hash that mirrors OpenZeppelin's
+//! IDs are 256-bit and computed XRPL-style: SHA-512Half over a 4-byte hash
+//! prefix followed by the fields the ID commits to, mirroring OpenZeppelin's
 //! `keccak256(abi.encode(targets, values, calldatas, descriptionHash))`.
+//! The prefix keeps a proposal ID from ever equalling an operation ID or
+//! any XRPL ledger hash built from the same bytes.
 //!
-//! Uses a FNV-1a inspired mixing function with additional bit rotation.
-//! Not cryptographically secure (no SHA-256 available in no_std without
-//! extra dependencies), but collision-resistant for our domain of
-//! proposal IDs where inputs are unique (proposer + time + desc + nonce).
+//! Description hashes are plain SHA-512Half of the description bytes, with
+//! no prefix, so anyone can recompute one from the text they read.
+//!
+//! The archive digest left behind by `prune` is SHA-512Half under its own
+//! prefix, so holders of a proposal's final values can check them and
+//! cannot find other values that match.
 
-use crate::foundation::config::ACCOUNT_ID_SIZE;
+use crate::foundation::config::{ACCOUNT_ID_SIZE, HASH_SIZE};
+use crate::foundation::types::Action;
+use crate::crypto::sha512::{sha512_half, Sha512};
 
-/// Hash multiple inputs into a deterministic u32 proposal ID.
-///
-/// Inputs bound to the hash:
+/// Hash prefix of proposal IDs ("DPR\0", in the style of XRPL's HashPrefix).
+pub const HASH_PREFIX_PROPOSAL: [u8; 4] = *b"DPR\0";
+/// Hash prefix of timelock operation IDs ("DOP\0").
+pub const HASH_PREFIX_OPERATION: [u8; 4] = *b"DOP\0";
+/// Hash prefix of archive digests ("DAR\0").
+pub const HASH_PREFIX_ARCHIVE: [u8; 4] = *b"DAR\0";
+/// Hash prefix of the running archive root ("DAH\0").
+pub const HASH_PREFIX_ARCHIVE_ROOT: [u8; 4] = *b"DAH\0";
+
+/// 256-bit proposal ID: SHA-512Half of HASH_PREFIX_PROPOSAL followed by
 ///   - proposer AccountID (20 bytes) — who proposed
-///   - description_hash (4 bytes)    — what was proposed
+///   - description_hash (32 bytes)   — what was proposed
+///   - actions                       — what will run: count (4 bytes),
+///     then each target, amount, tx type and length-prefixed payload
 ///   - current_time (4 bytes)        — when proposed
-///   - proposal_count (1 byte)       — nonce to prevent collisions
+///   - proposal_nonce (2 bytes)      — slot index, so equal proposals
+///     made in one ledger still differ
 ///
-/// This mirrors OZ's approach of binding the ID to all proposal content.
+/// Integers are big-endian.
 pub fn hash_proposal(
     proposer: &[u8; ACCOUNT_ID_SIZE],
-    description_hash: u32,
+    description_hash: &[u8; HASH_SIZE],
+    actions: &[Action],
     current_time: u32,
-    proposal_nonce: u8,
-) -> u32 {
-    let mut h: u64 = 0xcbf29ce484222325; // FNV offset basis
-
-    // Mix proposer bytes
-    for &b in proposer.iter() {
-        h ^= b as u64;
-        h = h.wrapping_mul(0x100000001b3); // FNV prime
-    }
+    proposal_nonce: u16,
+) -> [u8; HASH_SIZE] {
+    let mut hasher = Sha512::new();
+    hasher.update(&HASH_PREFIX_PROPOSAL);
+    hasher.update(proposer);
+    hasher.update(description_hash);
 
-    // Mix description hash (4 bytes, big-endian)
-    for &b in &description_hash.to_be_bytes() {
-        h ^= b as u64;
-        h = h.wrapping_mul(0x100000001b3);
+    hasher.update(&(actions.len() as u32).to_be_bytes());
+    for action in actions {
+        hasher.update(&action.target);
+        hasher.update(&action.amount.to_be_bytes());
+        hasher.update(&action.tx_type.to_be_bytes());
+        hasher.update(&[action.payload_len]);
+        hasher.update(action.payload());
     }
 
-    // Mix current time (4 bytes, big-endian)
-    for &b in &current_time.to_be_bytes() {
-        h ^= b as u64;
-        h = h.wrapping_mul(0x100000001b3);
-    }
-
-    // Mix nonce
-    h ^= proposal_nonce as u64;
-    h = h.wrapping_mul(0x100000001b3);
-
-    // Final avalanche: fold 64-bit down to 32-bit with mixing
-    h ^= h >> 33;
-    h = h.wrapping_mul(0xff51afd7ed558ccd);
-    h ^= h >> 33;
-    h = h.wrapping_mul(0xc4ceb9fe1a85ec53);
-    h ^= h >> 33;
-
-    // Return lower 32 bits, ensure non-zero
-    let result = (h as u32) | 1;
-    result
+    hasher.update(&current_time.to_be_bytes());
+    hasher.update(&proposal_nonce.to_be_bytes());
+    hasher.finalize_half()
 }
 
-/// Hash inputs for a timelock operation ID.
-/// Binds: proposal_id + schedule_time + op_nonce.
+/// 256-bit timelock operation ID: SHA-512Half of HASH_PREFIX_OPERATION,
+/// proposal_id (32 bytes), schedule_time (4 bytes) and op_nonce (2 bytes).
 pub fn hash_operation(
-    proposal_id: u32,
+    proposal_id: &[u8; HASH_SIZE],
     schedule_time: u32,
-    op_nonce: u8,
-) -> u32 {
-    let mut h: u64 = 0xcbf29ce484222325;
-
-    for &b in &proposal_id.to_be_bytes() {
-        h ^= b as u64;
-        h = h.wrapping_mul(0x100000001b3);
-    }
+    op_nonce: u16,
+) -> [u8; HASH_SIZE] {
+    let mut hasher = Sha512::new();
+    hasher.update(&HASH_PREFIX_OPERATION);
+    hasher.update(proposal_id);
+    hasher.update(&schedule_time.to_be_bytes());
+    hasher.update(&op_nonce.to_be_bytes());
+    hasher.finalize_half()
+}
 
-    for &b in &schedule_time.to_be_bytes() {
-        h ^= b as u64;
-        h = h.wrapping_mul(0x100000001b3);
-    }
+/// Hash of a proposal description: SHA-512Half of the text as submitted
+/// (the full description, or a URI plus content hash such as an IPFS CID).
+pub fn hash_description(description: &[u8]) -> [u8; HASH_SIZE] {
+    sha512_half(description)
+}
 
-    h ^= op_nonce as u64;
-    h = h.wrapping_mul(0x100000001b3);
+/// Widen a 32-bit ID from before schema v3 (or description hash from
+/// before v4) to 256 bits: 28 zero bytes then the old value big-endian,
+/// so `0x...0000002a` is legacy ID 42.
+pub fn legacy_id(id: u32) -> [u8; HASH_SIZE] {
+    let mut wide = [0u8; HASH_SIZE];
+    wide[HASH_SIZE - 4..].copy_from_slice(&id.to_be_bytes());
+    wide
+}
 
-    h ^= h >> 33;
-    h = h.wrapping_mul(0xff51afd7ed558ccd);
-    h ^= h >> 33;
+/// Digest of a finished proposal's final result, kept after pruning:
+/// SHA-512Half of HASH_PREFIX_ARCHIVE followed by proposal_id (32 bytes),
+/// proposer (20 bytes), description_hash (32 bytes), final state (1 byte)
+/// and the For, Against and Abstain tallies (8 bytes each, big-endian).
+pub fn hash_archive(
+    proposal_id: &[u8; HASH_SIZE],
+    proposer: &[u8; ACCOUNT_ID_SIZE],
+    description_hash: &[u8; HASH_SIZE],
+    final_state: u8,
+    tallies: [u64; 3],
+) -> [u8; HASH_SIZE] {
+    let mut hasher = Sha512::new();
+    hasher.update(&HASH_PREFIX_ARCHIVE);
+    hasher.update(proposal_id);
+    hasher.update(proposer);
+    hasher.update(description_hash);
+    hasher.update(&[final_state]);
+    for tally in tallies {
+        hasher.update(&tally.to_be_bytes());
+    }
+    hasher.finalize_half()
+}
 
-    (h as u32) | 1
+/// Fold one archive digest into the running root: SHA-512Half of
+/// HASH_PREFIX_ARCHIVE_ROOT, the previous root (all zero at first) and the
+/// digest. Replaying the folded digests in order reproduces the root.
+pub fn hash_archive_root(root: &[u8; HASH_SIZE], digest: &[u8; HASH_SIZE]) -> [u8; HASH_SIZE] {
+    let mut hasher = Sha512::new();
+    hasher.update(&HASH_PREFIX_ARCHIVE_ROOT);
+    hasher.update(root);
+    hasher.update(digest);
+    hasher.finalize_half()
 }
@@ -6,7 +6,7 @@
 /// Writes into `out` and returns the number of bytes written.
 /// Returns None if the hex string is invalid or `out` is too small.
 pub fn decode_hex(hex: &[u8], out: &mut [u8]) -> bool {
-    if hex.len() % 2 != 0 {
+    if !hex.len().is_multiple_of(2) {
         return false;
     }
     let byte_len = hex.len() / 2;
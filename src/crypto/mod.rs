@@ -1,2 +1,3 @@
 pub mod hex;
 pub mod hash;
+pub mod sha512;
@@ -0,0 +1,876 @@
+//! WASM entry points, generic over `foundation::host::Host`.
+//!
+//! Each export in `lib.rs` is a thin `extern "C"` wrapper that calls the
+//! function of the same name here with `XrplHost`. Tests drive the same
+//! code with `MockHost`, so caller verification, locking and the full
+//! state rewrites are covered end to end.
+
+use crate::foundation;
+use crate::foundation::config::*;
+use crate::foundation::data::*;
+use crate::foundation::abi::Params;
+use crate::foundation::host::{locator_bytes, memo_locator, Host};
+use crate::foundation::types::{Actions, Proposal};
+use crate::crypto::hash::hash_description;
+use crate::governance;
+use crate::governance::governor;
+use crate::governance::counting;
+use crate::governance::votes;
+use crate::timelock::controller;
+use crate::token::xrp_votes;
+
+// ═══════════════════════════════════════════════════════════════════════
+// Host I/O
+// ═══════════════════════════════════════════════════════════════════════
+
+/// Read `Memos[index].MemoData` of the current transaction into `buf`.
+/// Returns the payload length, or ERR_HOST_CALL if there is no such memo.
+fn read_memo<H: Host>(host: &mut H, index: i32, buf: &mut [u8]) -> i32 {
+    let locator = memo_locator(index);
+    let len = host.get_tx_nested_field(locator_bytes(&locator), buf);
+    if len < 0 { return ERR_HOST_CALL; }
+    len
+}
+
+/// Read the call parameters (see `foundation::abi`) from memo 0 into
+/// `buf`. A transaction without memos has no parameters.
+fn read_params<'a, H: Host>(host: &mut H, buf: &'a mut [u8; MEMO_CAPACITY]) -> Params<'a> {
+    let len = read_memo(host, 0, buf);
+    Params::new(&buf[..len.max(0) as usize])
+}
+
+/// The `id=<hex64>` proposal ID from the call parameters.
+fn read_proposal_id<H: Host>(host: &mut H) -> Result<[u8; HASH_SIZE], i32> {
+    let mut params_buf = [0u8; MEMO_CAPACITY];
+    read_params(host, &mut params_buf).hash(b"id")
+}
+
+/// The SHA-512Half of memo 1, which carries a proposal description (the
+/// full text, or a URI plus content hash). A missing or empty description
+/// fails with ERR_BAD_PARAM.
+fn read_description_hash<H: Host>(host: &mut H) -> Result<[u8; HASH_SIZE], i32> {
+    let mut description = [0u8; MEMO_CAPACITY];
+    match read_memo(host, 1, &mut description) {
+        len if len > 0 => Ok(hash_description(&description[..len as usize])),
+        _ => Err(ERR_BAD_PARAM),
+    }
+}
+
+/// Read the Data field into `buf` as key=value text, rejecting layouts
+/// this build does not understand (call `migrate` first).
+/// Returns the text length, or a negative error code.
+fn read_state<H: Host>(host: &mut H, buf: &mut [u8; DATA_CAPACITY]) -> i32 {
+    let len = read_state_unchecked(host, buf);
+    if len < 0 { return len; }
+    if let Err(code) = foundation::migrate::check(&buf[..len as usize]) {
+        return code;
+    }
+    len
+}
+
+/// Read the Data field without checking `schema_version`.
+fn read_state_unchecked<H: Host>(host: &mut H, buf: &mut [u8; DATA_CAPACITY]) -> i32 {
+    #[cfg(not(feature = "compact-state"))]
+    {
+        let len = host.get_data(buf);
+        if len < 0 { return ERR_DATA_READ; }
+        len
+    }
+    #[cfg(feature = "compact-state")]
+    {
+        let mut raw = [0u8; FIELD_CAPACITY];
+        let len = host.get_data(&mut raw);
+        if len < 0 { return ERR_DATA_READ; }
+        match foundation::codec::to_text(&raw[..len as usize], buf) {
+            Ok(text_len) => text_len as i32,
+            Err(code) => code,
+        }
+    }
+}
+
+/// Write key=value text back to the Data field.
+/// Returns SUCCESS, or a negative error code.
+fn write_state<H: Host>(host: &mut H, text: &[u8]) -> i32 {
+    #[cfg(not(feature = "compact-state"))]
+    {
+        if host.set_data(text) < 0 {
+            return ERR_HOST_CALL;
+        }
+        SUCCESS
+    }
+    #[cfg(feature = "compact-state")]
+    {
+        let mut raw = [0u8; FIELD_CAPACITY];
+        let len = match foundation::codec::encode(text, &mut raw) {
+            Ok(len) => len,
+            Err(code) => return code,
+        };
+        if host.set_data(&raw[..len]) < 0 {
+            return ERR_HOST_CALL;
+        }
+        SUCCESS
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Entry Points — Governor Interface
+// ═══════════════════════════════════════════════════════════════════════
+
+/// Initialize a new DAO. Mirrors the Governor constructor.
+///
+/// Reads a `foundation::genesis` spec (admin, founding members, optional
+/// settings) from the first transaction memo. The caller must name itself
+/// admin. Runs once: any existing state fails with ERR_ALREADY_INITIALIZED.
+pub fn initialize<H: Host>(host: &mut H) -> i32 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    // A fresh escrow has no schema_version yet
+    let data_len = read_state_unchecked(host, &mut data_buf);
+    if data_len < 0 { return data_len; }
+    let data_len = data_len as usize;
+
+    let mut caller = [0u8; ACCOUNT_ID_SIZE];
+    if host.get_current_account(&mut caller) < 0 {
+        return ERR_HOST_CALL;
+    }
+    // Fix #3: Caller identity double-read verification
+    let mut caller_verify = [0u8; ACCOUNT_ID_SIZE];
+    if host.get_current_account(&mut caller_verify) < 0 {
+        return ERR_HOST_CALL;
+    }
+    if caller != caller_verify {
+        return ERR_CALLER_VERIFICATION;
+    }
+
+    let mut spec = [0u8; MEMO_CAPACITY];
+    let spec_len = read_memo(host, 0, &mut spec);
+    if spec_len < 0 { return spec_len; }
+
+    let genesis = match foundation::genesis::Genesis::parse(&spec[..spec_len as usize]) {
+        Ok(g) => g,
+        Err(code) => return code,
+    };
+    if genesis.admin != caller {
+        return ERR_NOT_ADMIN;
+    }
+
+    let current_time = host.get_current_ledger_time() as u32;
+    match foundation::genesis::initialize(&data_buf[..data_len], data_len, &genesis, current_time) {
+        Ok((new_data, new_len)) => {
+            let rc = write_state(host, &new_data[..new_len]);
+            if rc < 0 {
+                return rc;
+            }
+            SUCCESS
+        }
+        Err(code) => code,
+    }
+}
+
+/// Create a new governance proposal. Mirrors Governor.propose().
+///
+/// Memo 0 carries `actions=<action>,...`, the 1 to MAX_BATCH_OPS actions to
+/// run (see `foundation::abi`); like OZ, a proposal without actions is
+/// rejected. Memo 1 carries the description, stored as its SHA-512Half.
+/// Caller must hold tokens >= the proposal threshold setting.
+pub fn propose<H: Host>(host: &mut H) -> i32 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    let data_len = read_state(host, &mut data_buf);
+    if data_len < 0 { return data_len; }
+    let data_len = data_len as usize;
+
+    let mut caller = [0u8; ACCOUNT_ID_SIZE];
+    if host.get_current_account(&mut caller) < 0 {
+        return ERR_HOST_CALL;
+    }
+    // Fix #3: Caller identity double-read verification
+    let mut caller_verify = [0u8; ACCOUNT_ID_SIZE];
+    if host.get_current_account(&mut caller_verify) < 0 {
+        return ERR_HOST_CALL;
+    }
+    if caller != caller_verify {
+        return ERR_CALLER_VERIFICATION;
+    }
+
+    let current_time = host.get_current_ledger_time() as u32;
+    let proposer_votes = xrp_votes::get_effective_votes(&data_buf[..data_len], &caller);
+
+    let mut params_buf = [0u8; MEMO_CAPACITY];
+    let actions = match read_params(host, &mut params_buf).actions(b"actions") {
+        Ok(actions) => actions,
+        Err(code) => return code,
+    };
+    let description_hash = match read_description_hash(host) {
+        Ok(hash) => hash,
+        Err(code) => return code,
+    };
+
+    match governor::propose(
+        &data_buf[..data_len], data_len, &caller,
+        &description_hash, &actions, current_time, proposer_votes,
+    ) {
+        Ok((new_data, new_len, _prop_id)) => {
+            let rc = write_state(host, &new_data[..new_len]);
+            if rc < 0 {
+                return rc;
+            }
+            SUCCESS
+        }
+        Err(code) => code,
+    }
+}
+
+/// Cast a vote on an active proposal. Mirrors Governor.castVote().
+///
+/// Memo 0 carries `id=<hex64>`, the proposal ID.
+/// Vote support types: 0=Against, 1=For, 2=Abstain. The weight is the
+/// caller's votes at proposal creation (`xrp_votes::get_proposal_votes`).
+pub fn cast_vote<H: Host>(host: &mut H, support: u8) -> i32 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    let data_len = read_state(host, &mut data_buf);
+    if data_len < 0 { return data_len; }
+    let data_len = data_len as usize;
+
+    let mut caller = [0u8; ACCOUNT_ID_SIZE];
+    if host.get_current_account(&mut caller) < 0 {
+        return ERR_HOST_CALL;
+    }
+    // Fix #3: Caller identity double-read verification
+    let mut caller_verify = [0u8; ACCOUNT_ID_SIZE];
+    if host.get_current_account(&mut caller_verify) < 0 {
+        return ERR_HOST_CALL;
+    }
+    if caller != caller_verify {
+        return ERR_CALLER_VERIFICATION;
+    }
+
+    let current_time = host.get_current_ledger_time() as u32;
+
+    let proposal_id = match read_proposal_id(host) {
+        Ok(id) => id,
+        Err(code) => return code,
+    };
+    let proposal_index = match governor::find_proposal_by_id(&data_buf[..data_len], &proposal_id) {
+        Ok(idx) => idx,
+        Err(code) => return code,
+    };
+    // Weighed as of proposal creation, so re-delegating after voting cannot count twice
+    let weight = xrp_votes::get_proposal_votes(&data_buf[..data_len], &proposal_id, &caller);
+
+    match counting::cast_vote(
+        &data_buf[..data_len], data_len, proposal_index,
+        &caller, support, weight, current_time,
+    ) {
+        Ok((new_data, new_len)) => {
+            let rc = write_state(host, &new_data[..new_len]);
+            if rc < 0 {
+                return rc;
+            }
+            SUCCESS
+        }
+        Err(code) => code,
+    }
+}
+
+/// Queue a succeeded proposal into the timelock.
+/// Mirrors GovernorTimelockControl._queueOperations().
+/// Memo 0 carries `id=<hex64>`, the proposal ID.
+pub fn queue<H: Host>(host: &mut H) -> i32 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    let data_len = read_state(host, &mut data_buf);
+    if data_len < 0 { return data_len; }
+    let data_len = data_len as usize;
+
+    let current_time = host.get_current_ledger_time() as u32;
+
+    let proposal_id = match read_proposal_id(host) {
+        Ok(id) => id,
+        Err(code) => return code,
+    };
+    let prop_idx = match governor::find_proposal_by_id(&data_buf[..data_len], &proposal_id) {
+        Ok(idx) => idx,
+        Err(code) => return code,
+    };
+
+    // Proposal must be Succeeded
+    let state = governor::get_proposal_state(&data_buf[..data_len], prop_idx, current_time);
+    if state != PROPOSAL_STATE_SUCCEEDED {
+        return ERR_PROPOSAL_NOT_ACTIVE;
+    }
+
+    // Schedule in timelock
+    let delay = governance::settings::Settings::load(&data_buf[..data_len]).timelock_min_delay;
+    match controller::schedule(&data_buf[..data_len], data_len, &proposal_id, current_time, delay) {
+        Ok((new_data, new_len, _op_id)) => {
+            // Update proposal state to Queued, recording its eta
+            let eta = match current_time.checked_add(delay) {
+                Some(eta) => eta,
+                None => return ERR_OVERFLOW,
+            };
+            let (final_data, fpos) = match governor::mark_queued(&new_data[..new_len], new_len, prop_idx, eta) {
+                Ok(r) => r,
+                Err(code) => return code,
+            };
+
+            let rc = write_state(host, &final_data[..fpos]);
+            if rc < 0 {
+                return rc;
+            }
+            SUCCESS
+        }
+        Err(code) => code,
+    }
+}
+
+/// Execute a queued proposal after timelock delay.
+/// Mirrors GovernorTimelockControl._executeOperations().
+///
+/// `ACTION_SET_SETTING` actions are applied first, through
+/// `settings::set_setting` while `_lock`/`_exec` mark the proposal as
+/// executing. Every other action is emitted through
+/// `Host::emit_transaction` in order. Each outcome is recorded as
+/// `prop_N_res_K` (SUCCESS or the error code). While nothing has been
+/// emitted a failure returns that error and leaves the proposal Queued.
+/// Once an action has gone out a later failure no longer reverts the
+/// others (unlike OZ): the proposal is marked Executed and the failure
+/// stays in state. The final state is checked to fit before anything is
+/// emitted.
+/// Memo 0 carries `id=<hex64>`, the proposal ID.
+///
+/// Security fixes applied:
+/// - Fix #2: Reentrancy guard (lock before execute, unlock after)
+/// - Fix #3: Caller identity verification (double-read pattern)
+pub fn execute<H: Host>(host: &mut H) -> i32 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    let data_len = read_state(host, &mut data_buf);
+    if data_len < 0 { return data_len; }
+    let data_len = data_len as usize;
+
+    // Fix #3: Caller identity verification — double-read pattern
+    let mut caller = [0u8; ACCOUNT_ID_SIZE];
+    if host.get_current_account(&mut caller) < 0 {
+        return ERR_HOST_CALL;
+    }
+    let mut caller_verify = [0u8; ACCOUNT_ID_SIZE];
+    if host.get_current_account(&mut caller_verify) < 0 {
+        return ERR_HOST_CALL;
+    }
+    if caller != caller_verify {
+        return ERR_CALLER_VERIFICATION;
+    }
+
+    // Caller must be executor
+    if !votes::has_role(&data_buf[..data_len], &caller, ROLE_EXECUTOR) {
+        return ERR_NOT_EXECUTOR;
+    }
+
+    // Fix #2: Reentrancy guard — check lock
+    if governor::is_locked(&data_buf[..data_len]) {
+        return ERR_REENTRANT;
+    }
+
+    let proposal_id = match read_proposal_id(host) {
+        Ok(id) => id,
+        Err(code) => return code,
+    };
+
+    // Set lock, recording the proposal for settings::only_governance
+    let (locked_data, locked_len) = match governor::set_executing(&data_buf[..data_len], data_len, Some(&proposal_id)) {
+        Ok(r) => r,
+        Err(code) => return code,
+    };
+
+    let current_time = host.get_current_ledger_time() as u32;
+
+    let op_idx = match controller::find_operation_by_proposal(&locked_data[..locked_len], &proposal_id) {
+        Ok(idx) => idx,
+        Err(code) => {
+            // Unlock before returning error
+            let _ = governor::set_executing(&locked_data[..locked_len], locked_len, None);
+            return code;
+        }
+    };
+
+    match controller::execute(&locked_data[..locked_len], locked_len, op_idx, current_time) {
+        Ok((new_data, new_len)) => {
+            // Also update proposal state to Executed
+            let prop_idx = match governor::find_proposal_by_id(&new_data[..new_len], &proposal_id) {
+                Ok(idx) => idx,
+                Err(_) => {
+                    // Unlock and save
+                    let (unlocked, ulen) = match governor::set_executing(&new_data[..new_len], new_len, None) {
+                        Ok(r) => r,
+                        Err(_) => {
+                            let rc = write_state(host, &new_data[..new_len]);
+                            if rc < 0 {
+                                return rc;
+                            }
+                            return SUCCESS;
+                        }
+                    };
+                    let rc = write_state(host, &unlocked[..ulen]);
+                    if rc < 0 {
+                        return rc;
+                    }
+                    return SUCCESS;
+                }
+            };
+
+            let mut store = Store::load(&new_data[..new_len]);
+            let mut actions = Actions::new();
+            if let Some(mut proposal) = Proposal::load(&new_data[..new_len], prop_idx) {
+                actions = match Actions::load(&new_data[..new_len], prop_idx) {
+                    Some(actions) => actions,
+                    None => return ERR_INVALID_ENTRY,
+                };
+                // Reserve every result at its widest before emitting, so
+                // recording outcomes cannot run out of space afterwards
+                for k in 0..actions.as_slice().len() {
+                    if let Err(code) = Actions::store_result(&mut store, prop_idx, k as u16, i32::MIN) {
+                        return code;
+                    }
+                }
+                proposal.state = PROPOSAL_STATE_EXECUTED;
+                if let Err(code) = proposal.store(&mut store, prop_idx) {
+                    return code;
+                }
+            }
+            // Settings changes need the lock and `_exec` still in place
+            for (k, action) in actions.as_slice().iter().enumerate() {
+                if action.tx_type != ACTION_SET_SETTING {
+                    continue;
+                }
+                match governance::settings::apply_action(store.as_bytes(), store.len(), action) {
+                    Ok((changed, changed_len)) => store = Store::load(&changed[..changed_len]),
+                    Err(code) => return code,
+                }
+                // Cannot fail: no wider than the value reserved above
+                let _ = Actions::store_result(&mut store, prop_idx, k as u16, SUCCESS);
+            }
+            // Fix #2: Unlock reentrancy guard in final data
+            if let Err(code) = store.set(b"_lock", b"0") {
+                return code;
+            }
+            store.remove(b"_exec");
+            // Checked before anything is emitted, which cannot be undone
+            if let Err(code) = check_fits(store.as_bytes()) {
+                return code;
+            }
+
+            let mut sent = false;
+            for (k, action) in actions.as_slice().iter().enumerate() {
+                if action.tx_type == ACTION_SET_SETTING {
+                    continue;
+                }
+                let rc = host.emit_transaction(action);
+                // Fail closed while nothing has gone out: without a write
+                // the proposal stays Queued and its operation Ready
+                if rc < 0 && !sent {
+                    return rc;
+                }
+                sent = true;
+                let outcome = if rc < 0 { rc } else { SUCCESS };
+                // Cannot fail: no wider than the value reserved above
+                let _ = Actions::store_result(&mut store, prop_idx, k as u16, outcome);
+            }
+            let (unlocked, ulen) = store.commit();
+
+            let rc = write_state(host, &unlocked[..ulen]);
+            if rc < 0 {
+                return rc;
+            }
+            SUCCESS
+        }
+        Err(code) => {
+            // Unlock before returning error
+            if let Ok((unlocked, ulen)) = governor::set_executing(&locked_data[..locked_len], locked_len, None) {
+                let _ = write_state(host, &unlocked[..ulen]);
+            }
+            code
+        }
+    }
+}
+
+/// Cancel a proposal. Mirrors Governor.cancel().
+/// Memo 0 carries `id=<hex64>`, the proposal ID. The proposer may cancel
+/// while Pending; a ROLE_CANCELLER account up to Queued, together with
+/// the proposal's timelock operation.
+pub fn cancel<H: Host>(host: &mut H) -> i32 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    let data_len = read_state(host, &mut data_buf);
+    if data_len < 0 { return data_len; }
+    let data_len = data_len as usize;
+
+    let mut caller = [0u8; ACCOUNT_ID_SIZE];
+    if host.get_current_account(&mut caller) < 0 {
+        return ERR_HOST_CALL;
+    }
+    // Fix #3: Caller identity double-read verification
+    let mut caller_verify = [0u8; ACCOUNT_ID_SIZE];
+    if host.get_current_account(&mut caller_verify) < 0 {
+        return ERR_HOST_CALL;
+    }
+    if caller != caller_verify {
+        return ERR_CALLER_VERIFICATION;
+    }
+
+    let current_time = host.get_current_ledger_time() as u32;
+
+    let proposal_id = match read_proposal_id(host) {
+        Ok(id) => id,
+        Err(code) => return code,
+    };
+    let prop_idx = match governor::find_proposal_by_id(&data_buf[..data_len], &proposal_id) {
+        Ok(idx) => idx,
+        Err(code) => return code,
+    };
+
+    match governor::cancel_proposal(
+        &data_buf[..data_len], data_len, prop_idx, &caller, current_time,
+    ) {
+        Ok((new_data, new_len)) => {
+            let rc = write_state(host, &new_data[..new_len]);
+            if rc < 0 {
+                return rc;
+            }
+            SUCCESS
+        }
+        Err(code) => code,
+    }
+}
+
+/// Delegate voting power. Mirrors ERC20Votes.delegate().
+///
+/// Memo 0 carries `delegatee=<hex40>`; naming the caller clears the
+/// delegation. Caller and delegatee must both be members.
+pub fn delegate_votes<H: Host>(host: &mut H) -> i32 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    let data_len = read_state(host, &mut data_buf);
+    if data_len < 0 { return data_len; }
+    let data_len = data_len as usize;
+
+    let mut caller = [0u8; ACCOUNT_ID_SIZE];
+    if host.get_current_account(&mut caller) < 0 {
+        return ERR_HOST_CALL;
+    }
+    // Fix #3: Caller identity double-read verification
+    let mut caller_verify = [0u8; ACCOUNT_ID_SIZE];
+    if host.get_current_account(&mut caller_verify) < 0 {
+        return ERR_HOST_CALL;
+    }
+    if caller != caller_verify {
+        return ERR_CALLER_VERIFICATION;
+    }
+
+    let mut params_buf = [0u8; MEMO_CAPACITY];
+    let delegatee = match read_params(host, &mut params_buf).account(b"delegatee") {
+        Ok(a) => a,
+        Err(code) => return code,
+    };
+
+    let current_time = host.get_current_ledger_time() as u32;
+    match xrp_votes::delegate(&data_buf[..data_len], data_len, &caller, &delegatee, current_time) {
+        Ok((new_data, new_len)) => {
+            let rc = write_state(host, &new_data[..new_len]);
+            if rc < 0 {
+                return rc;
+            }
+            SUCCESS
+        }
+        Err(code) => code,
+    }
+}
+
+/// Self-register as a DAO member. Fix #5: Permissionless registration.
+///
+/// Any account can register themselves as a member with 0 voting power
+/// and no roles. This replaces the admin-only member creation, reducing
+/// centralization. Admin still controls voting power grants via
+/// `set_voting_power()`, but cannot prevent registration.
+///
+/// Mirrors a permissionless ERC20Votes approach where anyone can hold
+/// tokens, but the DAO contract tracks them.
+pub fn self_register<H: Host>(host: &mut H) -> i32 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    let data_len = read_state(host, &mut data_buf);
+    if data_len < 0 { return data_len; }
+    let data_len = data_len as usize;
+
+    let mut caller = [0u8; ACCOUNT_ID_SIZE];
+    if host.get_current_account(&mut caller) < 0 {
+        return ERR_HOST_CALL;
+    }
+
+    // Check if already registered
+    if votes::get_votes(&data_buf[..data_len], &caller) > 0
+        || votes::get_roles(&data_buf[..data_len], &caller) > 0
+    {
+        // Already a member — check if they have an entry
+        return SUCCESS;
+    }
+
+    // Register with 0 power and no roles
+    let current_time = host.get_current_ledger_time() as u32;
+    match votes::set_member(
+        &data_buf[..data_len], data_len, &caller,
+        SELF_REGISTER_INITIAL_POWER, 0, current_time,
+    ) {
+        Ok((new_data, new_len)) => {
+            let rc = write_state(host, &new_data[..new_len]);
+            if rc < 0 {
+                return rc;
+            }
+            SUCCESS
+        }
+        Err(code) => code,
+    }
+}
+
+/// Admin: set voting power for a member. Fix #5 complement.
+///
+/// Admin can adjust voting power but cannot prevent self-registration.
+/// This is the decentralized alternative: anyone registers, admin
+/// allocates voting power based on token holdings / XRP balance.
+///
+/// Memo 0 carries `account=<hex40>;power=<drops>;roles=<bitmask>`.
+/// Adds the account, or updates it if already a member.
+pub fn add_member<H: Host>(host: &mut H) -> i32 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    let data_len = read_state(host, &mut data_buf);
+    if data_len < 0 { return data_len; }
+    let data_len = data_len as usize;
+
+    let mut caller = [0u8; ACCOUNT_ID_SIZE];
+    if host.get_current_account(&mut caller) < 0 {
+        return ERR_HOST_CALL;
+    }
+
+    // Caller must be admin
+    if !votes::has_role(&data_buf[..data_len], &caller, ROLE_ADMIN) {
+        return ERR_NOT_ADMIN;
+    }
+
+    let mut params_buf = [0u8; MEMO_CAPACITY];
+    let params = read_params(host, &mut params_buf);
+    let (account, power, roles) = match (params.account(b"account"), params.amount(b"power"), params.roles(b"roles")) {
+        (Ok(a), Ok(p), Ok(r)) => (a, p, r),
+        _ => return ERR_BAD_PARAM,
+    };
+
+    let current_time = host.get_current_ledger_time() as u32;
+    match votes::set_member(&data_buf[..data_len], data_len, &account, power, roles, current_time) {
+        Ok((new_data, new_len)) => {
+            let rc = write_state(host, &new_data[..new_len]);
+            if rc < 0 {
+                return rc;
+            }
+            SUCCESS
+        }
+        Err(code) => code,
+    }
+}
+
+/// Admin: remove a member and compact the `member_N` entries.
+///
+/// Memo 0 carries `account=<hex40>`. The last admin cannot be removed.
+pub fn remove_member<H: Host>(host: &mut H) -> i32 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    let data_len = read_state(host, &mut data_buf);
+    if data_len < 0 { return data_len; }
+    let data_len = data_len as usize;
+
+    let mut caller = [0u8; ACCOUNT_ID_SIZE];
+    if host.get_current_account(&mut caller) < 0 {
+        return ERR_HOST_CALL;
+    }
+
+    // Caller must be admin
+    if !votes::has_role(&data_buf[..data_len], &caller, ROLE_ADMIN) {
+        return ERR_NOT_ADMIN;
+    }
+
+    let mut params_buf = [0u8; MEMO_CAPACITY];
+    let account = match read_params(host, &mut params_buf).account(b"account") {
+        Ok(a) => a,
+        Err(code) => return code,
+    };
+
+    let current_time = host.get_current_ledger_time() as u32;
+    match votes::remove_member(&data_buf[..data_len], data_len, &account, current_time) {
+        Ok((new_data, new_len)) => {
+            let rc = write_state(host, &new_data[..new_len]);
+            if rc < 0 {
+                return rc;
+            }
+            SUCCESS
+        }
+        Err(code) => code,
+    }
+}
+
+/// Prune finished proposals (Executed, Defeated, Canceled, Expired) with
+/// their votes, snapshots and timelock ops, leaving a `hist_<id>` digest.
+/// Also compacts vote checkpoint histories.
+///
+/// Housekeeping only — outcomes are already final — so any account may
+/// call it. Returns the number of proposals pruned.
+pub fn prune<H: Host>(host: &mut H) -> i32 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    let data_len = read_state(host, &mut data_buf);
+    if data_len < 0 { return data_len; }
+    let data_len = data_len as usize;
+
+    if governor::is_locked(&data_buf[..data_len]) {
+        return ERR_REENTRANT;
+    }
+
+    let current_time = host.get_current_ledger_time() as u32;
+
+    match governance::prune::prune(&data_buf[..data_len], data_len, current_time) {
+        Ok((new_data, new_len, pruned)) => {
+            // Compacting checkpoints can shrink state with nothing pruned
+            if new_data[..new_len] != data_buf[..data_len] {
+                let rc = write_state(host, &new_data[..new_len]);
+                if rc < 0 {
+                    return rc;
+                }
+            }
+            pruned as i32
+        }
+        Err(code) => code,
+    }
+}
+
+/// Report Data field usage so operators can see saturation coming
+/// before writes start failing with ERR_DATA_FULL.
+///
+/// Returns `(bytes_used << 16) | bytes_available`; both fit in 16 bits
+/// because the Data field is FIELD_CAPACITY (4096) bytes. With
+/// `compact-state` this measures the encoded bytes actually stored.
+pub fn data_usage<H: Host>(host: &mut H) -> i32 {
+    let mut raw = [0u8; FIELD_CAPACITY];
+    let raw_len = host.get_data(&mut raw);
+    if raw_len < 0 { return ERR_DATA_READ; }
+    let raw_len = raw_len as usize;
+
+    let (used, available) = foundation::data::data_usage(&raw[..raw_len]);
+    ((used as i32) << 16) | available as i32
+}
+
+/// Upgrade the stored Data field to the current schema version in place.
+///
+/// Every other export rejects outdated state with ERR_SCHEMA_OUTDATED, so
+/// a live DAO calls this once after deploying a new release. Steps only
+/// rewrite layout, so it is idempotent and open to any caller.
+pub fn migrate<H: Host>(host: &mut H) -> i32 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    let data_len = read_state_unchecked(host, &mut data_buf);
+    if data_len < 0 { return data_len; }
+    let data_len = data_len as usize;
+
+    if foundation::migrate::check(&data_buf[..data_len]).is_ok() {
+        return SUCCESS;
+    }
+
+    match foundation::migrate::upgrade(&data_buf[..data_len], data_len) {
+        Ok((new_data, new_len)) => {
+            let rc = write_state(host, &new_data[..new_len]);
+            if rc < 0 {
+                return rc;
+            }
+            SUCCESS
+        }
+        Err(code) => code,
+    }
+}
+
+/// Verify the stored state against `foundation::invariants`.
+///
+/// Read-only, so any account can run it as an on-demand audit.
+/// Returns SUCCESS, or ERR_INVARIANT on the first inconsistency found.
+pub fn audit<H: Host>(host: &mut H) -> i32 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    let data_len = read_state(host, &mut data_buf);
+    if data_len < 0 { return data_len; }
+    let data_len = data_len as usize;
+
+    match foundation::invariants::check(&data_buf[..data_len]) {
+        Ok(()) => SUCCESS,
+        Err(code) => code,
+    }
+}
+
+/// Check a proposal's description against its stored hash.
+///
+/// Memo 0 carries `id=<hex64>` and memo 1 the description text as read
+/// off-ledger. Read-only. Returns SUCCESS if it hashes to `prop_N_desc`,
+/// else ERR_DESCRIPTION_MISMATCH.
+pub fn verify_description<H: Host>(host: &mut H) -> i32 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    let data_len = read_state(host, &mut data_buf);
+    if data_len < 0 { return data_len; }
+    let data_len = data_len as usize;
+
+    let proposal_id = match read_proposal_id(host) {
+        Ok(id) => id,
+        Err(code) => return code,
+    };
+    let mut description = [0u8; MEMO_CAPACITY];
+    let len = read_memo(host, 1, &mut description);
+    if len < 0 { return ERR_BAD_PARAM; }
+
+    match governor::verify_description(&data_buf[..data_len], &proposal_id, &description[..len as usize]) {
+        Ok(()) => SUCCESS,
+        Err(code) => code,
+    }
+}
+
+/// Read one governance setting. Mirrors the GovernorSettings getters.
+///
+/// `param` is a `SETTING_*` id. Returns the effective value (the stored
+/// override, else the compile-time default), or ERR_BAD_CONFIG for an
+/// unknown id. Read-only; i64 so the proposal threshold fits in drops.
+pub fn get_setting<H: Host>(host: &mut H, param: u32) -> i64 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    let data_len = read_state(host, &mut data_buf);
+    if data_len < 0 { return data_len as i64; }
+    let data_len = data_len as usize;
+
+    let param = match u8::try_from(param) {
+        Ok(p) => p,
+        Err(_) => return ERR_BAD_CONFIG as i64,
+    };
+    match governance::settings::get_setting(&data_buf[..data_len], param) {
+        Ok(value) => value as i64,
+        Err(code) => code as i64,
+    }
+}
+
+/// Effective votes of an account at a past ledger time. Mirrors
+/// ERC20Votes.getPastVotes().
+///
+/// Memo 0 carries `account=<hex40>`. Read-only; returns the power of the
+/// account's last checkpoint at or before `timepoint` (0 before its first).
+pub fn get_past_votes<H: Host>(host: &mut H, timepoint: u32) -> i64 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    let data_len = read_state(host, &mut data_buf);
+    if data_len < 0 { return data_len as i64; }
+    let data_len = data_len as usize;
+
+    let mut params_buf = [0u8; MEMO_CAPACITY];
+    let account = match read_params(host, &mut params_buf).account(b"account") {
+        Ok(a) => a,
+        Err(code) => return code as i64,
+    };
+    xrp_votes::get_past_votes(&data_buf[..data_len], &account, timepoint) as i64
+}
+
+/// Total voting power at a past ledger time. Mirrors
+/// ERC20Votes.getPastTotalSupply(). Read-only.
+pub fn get_past_total_supply<H: Host>(host: &mut H, timepoint: u32) -> i64 {
+    let mut data_buf = [0u8; DATA_CAPACITY];
+    let data_len = read_state(host, &mut data_buf);
+    if data_len < 0 { return data_len as i64; }
+    let data_len = data_len as usize;
+
+    xrp_votes::get_past_total_supply(&data_buf[..data_len], timepoint) as i64
+}
@@ -0,0 +1,120 @@
+//! Call-parameter ABI shared by the WASM exports.
+//!
+//! Scalar arguments (vote support, setting IDs) arrive as WASM function
+//! parameters. Anything larger, including 256-bit proposal IDs, is read
+//! from the first transaction memo, encoded like the Data field itself:
+//!
+//!   id=<hex64>;delegatee=<hex40>;actions=<action>,<action>
+//!
+//! | Type      | Encoding                                         |
+//! |-----------|--------------------------------------------------|
+//! | AccountID | 40 hex chars                                     |
+//! | Amount    | drops as decimal, at most MAX_XRP_DROPS          |
+//! | Hash      | 64 hex chars (32 bytes)                          |
+//! | Action    | `<target hex40>:<drops>:<tx type>:<payload hex>` |
+//! | Actions   | 1 to MAX_BATCH_OPS actions joined by `,`         |
+//!
+//! Every decoder fails with ERR_BAD_PARAM on a missing or malformed value.
+
+use crate::foundation::config::*;
+use crate::foundation::data::find_value;
+use crate::foundation::parse::parse_u32;
+use crate::foundation::types::{Action, Actions};
+use crate::crypto::hex::decode_hex;
+use crate::governance::governor::parse_u64;
+
+/// Decoded view over the call parameters of one transaction.
+#[derive(Clone, Copy)]
+pub struct Params<'a> {
+    raw: &'a [u8],
+}
+
+impl<'a> Params<'a> {
+    /// Wrap raw memo bytes. An empty slice means no parameters.
+    pub fn new(raw: &'a [u8]) -> Self {
+        Params { raw }
+    }
+
+    /// Whether `key` is present.
+    pub fn has(&self, key: &[u8]) -> bool {
+        find_value(self.raw, key).is_some()
+    }
+
+    /// The undecoded value of `key`.
+    pub fn raw(&self, key: &[u8]) -> Result<&'a [u8], i32> {
+        find_value(self.raw, key).ok_or(ERR_BAD_PARAM)
+    }
+
+    pub fn account(&self, key: &[u8]) -> Result<[u8; ACCOUNT_ID_SIZE], i32> {
+        decode_account(self.raw(key)?).ok_or(ERR_BAD_PARAM)
+    }
+
+    pub fn amount(&self, key: &[u8]) -> Result<u64, i32> {
+        decode_amount(self.raw(key)?).ok_or(ERR_BAD_PARAM)
+    }
+
+    pub fn hash(&self, key: &[u8]) -> Result<[u8; HASH_SIZE], i32> {
+        decode_hash(self.raw(key)?).ok_or(ERR_BAD_PARAM)
+    }
+
+    pub fn u32(&self, key: &[u8]) -> Result<u32, i32> {
+        parse_u32(self.raw(key)?).ok_or(ERR_BAD_PARAM)
+    }
+
+    /// A role bitmask; bits outside ALL_ROLES are rejected.
+    pub fn roles(&self, key: &[u8]) -> Result<u8, i32> {
+        match self.u32(key)? {
+            r if r & !(ALL_ROLES as u32) == 0 => Ok(r as u8),
+            _ => Err(ERR_BAD_PARAM),
+        }
+    }
+
+    pub fn actions(&self, key: &[u8]) -> Result<Actions, i32> {
+        decode_actions(self.raw(key)?)
+    }
+}
+
+/// Decode a 40-char hex AccountID.
+pub fn decode_account(hex: &[u8]) -> Option<[u8; ACCOUNT_ID_SIZE]> {
+    let mut account = [0u8; ACCOUNT_ID_SIZE];
+    (hex.len() == ACCOUNT_ID_SIZE * 2 && decode_hex(hex, &mut account)).then_some(account)
+}
+
+/// Decode a drops amount, rejecting anything above the XRP supply.
+pub fn decode_amount(text: &[u8]) -> Option<u64> {
+    parse_u64(text).filter(|&drops| drops <= MAX_XRP_DROPS)
+}
+
+/// Decode a 64-char hex hash.
+pub fn decode_hash(hex: &[u8]) -> Option<[u8; HASH_SIZE]> {
+    let mut hash = [0u8; HASH_SIZE];
+    (hex.len() == HASH_SIZE * 2 && decode_hex(hex, &mut hash)).then_some(hash)
+}
+
+/// Decode one `<target>:<drops>:<tx type>:<payload hex>` action.
+pub fn decode_action(text: &[u8]) -> Option<Action> {
+    let mut fields = text.split(|&b| b == b':');
+    let mut action = Action::new();
+    action.target = decode_account(fields.next()?)?;
+    action.amount = decode_amount(fields.next()?)?;
+    action.tx_type = parse_u32(fields.next()?).and_then(|t| u16::try_from(t).ok())?;
+
+    let payload_hex = fields.next()?;
+    let payload_len = payload_hex.len() / 2;
+    if payload_len > MAX_ACTION_PAYLOAD || !decode_hex(payload_hex, &mut action.payload) {
+        return None;
+    }
+    action.payload_len = payload_len as u8;
+
+    fields.next().is_none().then_some(action)
+}
+
+/// Decode a `,`-separated list of 1 to MAX_BATCH_OPS actions.
+pub fn decode_actions(text: &[u8]) -> Result<Actions, i32> {
+    let mut actions = Actions::new();
+    for item in text.split(|&b| b == b',') {
+        let action = decode_action(item).ok_or(ERR_BAD_PARAM)?;
+        actions.push(action).map_err(|_| ERR_BAD_PARAM)?;
+    }
+    Ok(actions)
+}
@@ -1,7 +1,9 @@
 //! WASM global allocator for no_std environment.
 //!
 //! The XRPL WASM sandbox provides memory but no allocator.
-//! This simple bump allocator grows memory as needed.
+//! This simple bump allocator grows memory as needed. It is only installed
+//! on wasm32; native builds use std's. The wasm32 panic handler comes from
+//! xrpl-wasm-stdlib, which traps with `unreachable`.
 
 use core::alloc::{GlobalAlloc, Layout};
 
@@ -19,12 +21,6 @@ unsafe impl GlobalAlloc for WasmAllocator {
     unsafe fn dealloc(&self, _ptr: *mut u8, _layout: Layout) {}
 }
 
-#[cfg(not(test))]
+#[cfg(all(target_arch = "wasm32", not(test)))]
 #[global_allocator]
 static ALLOCATOR: WasmAllocator = WasmAllocator;
-
-#[cfg(not(test))]
-#[panic_handler]
-fn panic(_info: &core::panic::PanicInfo) -> ! {
-    loop {}
-}
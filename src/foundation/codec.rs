@@ -0,0 +1,431 @@
+//! Compact binary encoding for contract state (`compact-state` feature).
+//!
+//! The module is always built so off-chain tools can decode a stored blob;
+//! only the `compact-state` build stores state in this form.
+//!
+//! The text format spends 40 hex chars per AccountID and a decimal string
+//! per tally. This codec stores the same key=value text as a stream of
+//! tag/length/value tokens:
+//!
+//! | Tag         | Payload                  | Decodes to                     |
+//! |-------------|--------------------------|--------------------------------|
+//! | 0x01 TEXT   | varint len + bytes       | the bytes verbatim             |
+//! | 0x02 NUM    | varint value             | canonical decimal              |
+//! | 0x03 ACCOUNT| 20 raw bytes             | 40 lowercase hex chars         |
+//! | 0x04 HEX    | varint len + raw bytes   | 2×len lowercase hex chars      |
+//! | 0x08 ACCOUNT_REF | 1-byte index        | an AccountID seen earlier      |
+//! | 0x09 NUM_SCALED | varint m + 1-byte e  | canonical decimal of m × 10^e  |
+//! | 0x05 / 0x06 / 0x07 | —                 | `=` / `;` / `:`                |
+//! | 0x20-0x7F   | —                        | dictionary word (`prop_`, ...) |
+//! | 0x80-0xFF   | —                        | small number 0-127             |
+//!
+//! The first 256 distinct AccountIDs are numbered in order of appearance;
+//! repeats (vote records, proposers, delegations) cost two bytes.
+//!
+//! Encoded blobs start with `MAGIC, VERSION`. Every token decodes to
+//! exactly the text it was built from, so `decode(encode(text)) == text`
+//! for any input — unknown key shapes simply fall back to TEXT tokens.
+
+use crate::foundation::config::{ERR_DATA_FULL, ERR_DATA_READ};
+use crate::crypto::hex::encode_hex;
+use crate::governance::governor::{parse_u64, format_u64};
+
+/// First byte of an encoded blob. Never valid as the start of text data.
+pub const MAGIC: u8 = 0xC5;
+/// Encoding version. Bump when the dictionary changes.
+pub const VERSION: u8 = 1;
+
+const TAG_TEXT: u8 = 0x01;
+const TAG_NUM: u8 = 0x02;
+const TAG_ACCOUNT: u8 = 0x03;
+const TAG_HEX: u8 = 0x04;
+const TAG_EQ: u8 = 0x05;
+const TAG_SEP: u8 = 0x06;
+const TAG_COLON: u8 = 0x07;
+const TAG_ACCOUNT_REF: u8 = 0x08;
+const TAG_NUM_SCALED: u8 = 0x09;
+const TAG_WORD_BASE: u8 = 0x20;
+const TAG_SMALL_BASE: u8 = 0x80;
+
+/// Shortest hex run stored as raw bytes rather than text.
+const MIN_HEX_RUN: usize = 16;
+
+/// AccountIDs that can be back-referenced by a one-byte index.
+const MAX_ACCOUNT_REFS: usize = 256;
+
+/// Length of an AccountID in hex chars.
+const ACCOUNT_HEX_LEN: usize = 40;
+
+/// Dictionary of common key fragments. Append only — indices are part of
+/// the encoding (tag = TAG_WORD_BASE + index).
+const WORDS: [&[u8]; 24] = [
+    b"member_count", b"member_", b"proposal_count", b"prop_",
+    b"op_count", b"op_", b"vote_", b"delegate_",
+    b"snap_", b"sigvote_", b"_proposer", b"_predecessor",
+    b"_prop", b"_id", b"_state", b"_start",
+    b"_end", b"_for", b"_against", b"_abstain",
+    b"_desc", b"_ready", b"_lock", b"_",
+];
+
+/// Check whether raw Data field bytes hold an encoded blob.
+pub fn is_compact(raw: &[u8]) -> bool {
+    raw.first() == Some(&MAGIC)
+}
+
+/// Encode key=value text into the compact form. Returns bytes written,
+/// or ERR_DATA_FULL if `out` is too small.
+pub fn encode(text: &[u8], out: &mut [u8]) -> Result<usize, i32> {
+    let mut w = Writer { out, pos: 0 };
+    w.byte(MAGIC)?;
+    w.byte(VERSION)?;
+
+    let mut accounts = AccountTable::new();
+    let mut pos = 0;
+    let mut literal_start = 0;
+
+    while pos < text.len() {
+        let (consumed, token) = next_token(text, pos);
+        match token {
+            Token::Literal => {}
+            _ => {
+                if literal_start < pos {
+                    w.byte(TAG_TEXT)?;
+                    w.varint((pos - literal_start) as u64)?;
+                    w.bytes(&text[literal_start..pos])?;
+                }
+                write_token(&mut w, &mut accounts, &text[pos..pos + consumed], token)?;
+                literal_start = pos + consumed;
+            }
+        }
+        pos += consumed;
+    }
+
+    if literal_start < text.len() {
+        w.byte(TAG_TEXT)?;
+        w.varint((text.len() - literal_start) as u64)?;
+        w.bytes(&text[literal_start..])?;
+    }
+
+    Ok(w.pos)
+}
+
+/// Decode a compact blob back into key=value text. Returns bytes written.
+/// Fails with ERR_DATA_READ on malformed input and ERR_DATA_FULL if `out`
+/// is too small.
+pub fn decode(blob: &[u8], out: &mut [u8]) -> Result<usize, i32> {
+    if blob.len() < 2 || blob[0] != MAGIC || blob[1] != VERSION {
+        return Err(ERR_DATA_READ);
+    }
+
+    let mut w = Writer { out, pos: 0 };
+    let mut accounts = AccountTable::new();
+    let mut pos = 2;
+
+    while pos < blob.len() {
+        let tag = blob[pos];
+        pos += 1;
+        match tag {
+            TAG_TEXT => {
+                let (len, used) = read_varint(&blob[pos..])?;
+                pos += used;
+                let end = pos.checked_add(len as usize).ok_or(ERR_DATA_READ)?;
+                if end > blob.len() { return Err(ERR_DATA_READ); }
+                w.bytes(&blob[pos..end])?;
+                pos = end;
+            }
+            TAG_NUM => {
+                let (value, used) = read_varint(&blob[pos..])?;
+                pos += used;
+                let mut num_buf = [0u8; 20];
+                let nlen = format_u64(value, &mut num_buf);
+                w.bytes(&num_buf[..nlen])?;
+            }
+            TAG_ACCOUNT => {
+                if pos + 20 > blob.len() { return Err(ERR_DATA_READ); }
+                accounts.push(w.pos);
+                w.hex(&blob[pos..pos + 20])?;
+                pos += 20;
+            }
+            TAG_ACCOUNT_REF => {
+                let index = *blob.get(pos).ok_or(ERR_DATA_READ)? as usize;
+                pos += 1;
+                if index >= accounts.len { return Err(ERR_DATA_READ); }
+                w.repeat(accounts.offsets[index], ACCOUNT_HEX_LEN)?;
+            }
+            TAG_HEX => {
+                let (len, used) = read_varint(&blob[pos..])?;
+                pos += used;
+                let end = pos.checked_add(len as usize).ok_or(ERR_DATA_READ)?;
+                if end > blob.len() { return Err(ERR_DATA_READ); }
+                w.hex(&blob[pos..end])?;
+                pos = end;
+            }
+            TAG_NUM_SCALED => {
+                let (mantissa, used) = read_varint(&blob[pos..])?;
+                pos += used;
+                let exp = *blob.get(pos).ok_or(ERR_DATA_READ)?;
+                pos += 1;
+                let value = 10u64.checked_pow(exp as u32)
+                    .and_then(|scale| mantissa.checked_mul(scale))
+                    .ok_or(ERR_DATA_READ)?;
+                let mut num_buf = [0u8; 20];
+                let nlen = format_u64(value, &mut num_buf);
+                w.bytes(&num_buf[..nlen])?;
+            }
+            TAG_EQ => w.byte(b'=')?,
+            TAG_SEP => w.byte(b';')?,
+            TAG_COLON => w.byte(b':')?,
+            t if t >= TAG_SMALL_BASE => {
+                let mut num_buf = [0u8; 3];
+                let nlen = format_u64((t - TAG_SMALL_BASE) as u64, &mut num_buf);
+                w.bytes(&num_buf[..nlen])?;
+            }
+            t if t >= TAG_WORD_BASE => {
+                let word = WORDS.get((t - TAG_WORD_BASE) as usize).ok_or(ERR_DATA_READ)?;
+                w.bytes(word)?;
+            }
+            _ => return Err(ERR_DATA_READ),
+        }
+    }
+
+    Ok(w.pos)
+}
+
+/// Convert raw Data field bytes to text, passing legacy text through
+/// unchanged so existing DAOs can adopt the compact form on next write.
+pub fn to_text(raw: &[u8], out: &mut [u8]) -> Result<usize, i32> {
+    if is_compact(raw) {
+        return decode(raw, out);
+    }
+    if raw.len() > out.len() {
+        return Err(ERR_DATA_FULL);
+    }
+    out[..raw.len()].copy_from_slice(raw);
+    Ok(raw.len())
+}
+
+// ——— Internal helpers ———
+
+#[derive(Clone, Copy)]
+enum Token {
+    Literal,
+    Eq,
+    Sep,
+    Colon,
+    Word(u8),
+    Num(u64),
+    Account,
+    Hex,
+}
+
+/// Classify the token starting at `pos`. Returns (bytes consumed, token).
+fn next_token(text: &[u8], pos: usize) -> (usize, Token) {
+    match text[pos] {
+        b'=' => return (1, Token::Eq),
+        b';' => return (1, Token::Sep),
+        b':' => return (1, Token::Colon),
+        _ => {}
+    }
+
+    // Longest dictionary match
+    let mut best: Option<(usize, u8)> = None;
+    for (i, word) in WORDS.iter().enumerate() {
+        if text[pos..].starts_with(word) && best.is_none_or(|(len, _)| word.len() > len) {
+            best = Some((word.len(), i as u8));
+        }
+    }
+    if let Some((len, index)) = best {
+        return (len, Token::Word(index));
+    }
+
+    // Maximal run of lowercase hex / decimal digits
+    let run = text[pos..].iter()
+        .take_while(|&&b| b.is_ascii_digit() || (b'a'..=b'f').contains(&b))
+        .count();
+    if run == 0 {
+        return (1, Token::Literal);
+    }
+    let digits = &text[pos..pos + run];
+
+    if run == ACCOUNT_HEX_LEN {
+        return (run, Token::Account);
+    }
+    if digits.iter().all(|b| b.is_ascii_digit()) && (digits[0] != b'0' || run == 1) {
+        if let Some(value) = parse_u64(digits) {
+            return (run, Token::Num(value));
+        }
+    }
+    if run >= MIN_HEX_RUN && run % 2 == 0 {
+        return (run, Token::Hex);
+    }
+    (run, Token::Literal)
+}
+
+fn write_token(
+    w: &mut Writer,
+    accounts: &mut AccountTable,
+    src: &[u8],
+    token: Token,
+) -> Result<(), i32> {
+    match token {
+        Token::Literal => Ok(()),
+        Token::Eq => w.byte(TAG_EQ),
+        Token::Sep => w.byte(TAG_SEP),
+        Token::Colon => w.byte(TAG_COLON),
+        Token::Word(index) => w.byte(TAG_WORD_BASE + index),
+        Token::Num(value) if value < 0x80 => w.byte(TAG_SMALL_BASE + value as u8),
+        Token::Num(value) => {
+            // Round amounts (drops, power) are cheaper as mantissa × 10^e
+            let mut mantissa = value;
+            let mut exp = 0u8;
+            while mantissa % 10 == 0 {
+                mantissa /= 10;
+                exp += 1;
+            }
+            if varint_len(mantissa) + 1 < varint_len(value) {
+                w.byte(TAG_NUM_SCALED)?;
+                w.varint(mantissa)?;
+                return w.byte(exp);
+            }
+            w.byte(TAG_NUM)?;
+            w.varint(value)
+        }
+        Token::Account => {
+            if let Some(index) = accounts.find(w, src) {
+                w.byte(TAG_ACCOUNT_REF)?;
+                return w.byte(index);
+            }
+            // Remember where the encoded raw bytes land, for find()
+            accounts.push(w.pos + 1);
+            w.byte(TAG_ACCOUNT)?;
+            w.raw_from_hex(src)
+        }
+        Token::Hex => {
+            w.byte(TAG_HEX)?;
+            w.varint((src.len() / 2) as u64)?;
+            w.raw_from_hex(src)
+        }
+    }
+}
+
+/// Encoded size of an LEB128 varint.
+fn varint_len(mut value: u64) -> usize {
+    let mut len = 1;
+    while value >= 0x80 {
+        value >>= 7;
+        len += 1;
+    }
+    len
+}
+
+/// Read an LEB128 varint. Returns (value, bytes consumed).
+fn read_varint(data: &[u8]) -> Result<(u64, usize), i32> {
+    let mut value: u64 = 0;
+    for (i, &b) in data.iter().enumerate().take(10) {
+        value |= ((b & 0x7F) as u64).checked_shl(7 * i as u32).ok_or(ERR_DATA_READ)?;
+        if b & 0x80 == 0 {
+            return Ok((value, i + 1));
+        }
+    }
+    Err(ERR_DATA_READ)
+}
+
+/// Output offsets of the AccountIDs seen so far, in order of appearance.
+/// The encoder records where the raw bytes were written, the decoder
+/// where the hex text was written.
+struct AccountTable {
+    offsets: [usize; MAX_ACCOUNT_REFS],
+    len: usize,
+}
+
+impl AccountTable {
+    fn new() -> Self {
+        AccountTable { offsets: [0; MAX_ACCOUNT_REFS], len: 0 }
+    }
+
+    fn push(&mut self, offset: usize) {
+        if self.len < MAX_ACCOUNT_REFS {
+            self.offsets[self.len] = offset;
+            self.len += 1;
+        }
+    }
+
+    /// Find a previously encoded AccountID matching `hex`.
+    fn find(&self, w: &Writer, hex: &[u8]) -> Option<u8> {
+        let mut raw = [0u8; 20];
+        if !crate::crypto::hex::decode_hex(hex, &mut raw) {
+            return None;
+        }
+        (0..self.len)
+            .find(|&i| w.out[self.offsets[i]..self.offsets[i] + 20] == raw)
+            .map(|i| i as u8)
+    }
+}
+
+/// Bounds-checked output cursor.
+struct Writer<'a> {
+    out: &'a mut [u8],
+    pos: usize,
+}
+
+impl Writer<'_> {
+    fn byte(&mut self, b: u8) -> Result<(), i32> {
+        self.bytes(&[b])
+    }
+
+    fn bytes(&mut self, src: &[u8]) -> Result<(), i32> {
+        let end = self.pos + src.len();
+        if end > self.out.len() {
+            return Err(ERR_DATA_FULL);
+        }
+        self.out[self.pos..end].copy_from_slice(src);
+        self.pos = end;
+        Ok(())
+    }
+
+    fn varint(&mut self, mut value: u64) -> Result<(), i32> {
+        loop {
+            let low = (value & 0x7F) as u8;
+            value >>= 7;
+            if value == 0 {
+                return self.byte(low);
+            }
+            self.byte(low | 0x80)?;
+        }
+    }
+
+    /// Copy `len` bytes already written at `from` to the cursor.
+    fn repeat(&mut self, from: usize, len: usize) -> Result<(), i32> {
+        let end = self.pos + len;
+        if end > self.out.len() {
+            return Err(ERR_DATA_FULL);
+        }
+        self.out.copy_within(from..from + len, self.pos);
+        self.pos = end;
+        Ok(())
+    }
+
+    /// Write raw bytes as lowercase hex text.
+    fn hex(&mut self, raw: &[u8]) -> Result<(), i32> {
+        let end = self.pos + raw.len() * 2;
+        if end > self.out.len() {
+            return Err(ERR_DATA_FULL);
+        }
+        encode_hex(raw, &mut self.out[self.pos..end]);
+        self.pos = end;
+        Ok(())
+    }
+
+    /// Write lowercase hex text as raw bytes.
+    fn raw_from_hex(&mut self, hex: &[u8]) -> Result<(), i32> {
+        let end = self.pos + hex.len() / 2;
+        if end > self.out.len() {
+            return Err(ERR_DATA_FULL);
+        }
+        if !crate::crypto::hex::decode_hex(hex, &mut self.out[self.pos..end]) {
+            return Err(ERR_DATA_READ);
+        }
+        self.pos = end;
+        Ok(())
+    }
+}
@@ -14,14 +14,70 @@
 /// Size of an XRPL AccountID in bytes (RIPEMD160 hash)
 pub const ACCOUNT_ID_SIZE: usize = 20;
 
+/// Size of a 256-bit hash in bytes: proposal and operation IDs
+/// (SHA-512Half) and description hashes
+pub const HASH_SIZE: usize = 32;
+
+/// Capacity limits. Indices and counts are u16 end to end, so these
+/// values (and the Data field size) are the only bound on DAO size.
+#[derive(Clone, Copy)]
+pub struct Limits {
+    /// Maximum number of DAO members / token holders tracked
+    pub max_members: u16,
+    /// Maximum proposals that can exist simultaneously
+    pub max_proposals: u16,
+    /// Maximum operations in a single timelock batch
+    pub max_batch_ops: u16,
+}
+
+/// Sized so the largest state these limits allow fits the Data field:
+/// every member voting on every proposal, full batches, full checkpoint
+/// histories and archive, every setting overridden, all at their widest.
+/// A member with its full vote history takes about 640 bytes, and a
+/// proposal with its votes, snapshots and operation about 1.3 KB plus 230
+/// per action, so 3 members and one proposal of 2 actions need ~3.9 KB.
+#[cfg(not(feature = "compact-state"))]
+pub const LIMITS: Limits = Limits {
+    max_members: 3,
+    max_proposals: 1,
+    max_batch_ops: 2,
+};
+
+/// Raised: compact-state stores AccountIDs as 20 raw bytes and numbers as
+/// varints. Sized from the same worst case, encoded: 6 members and 2
+/// proposals of 2 actions need ~3.9 KB.
+#[cfg(feature = "compact-state")]
+pub const LIMITS: Limits = Limits {
+    max_members: 6,
+    max_proposals: 2,
+    max_batch_ops: 2,
+};
+
 /// Maximum number of DAO members / token holders tracked
-pub const MAX_MEMBERS: usize = 20;
+pub const MAX_MEMBERS: usize = LIMITS.max_members as usize;
 
 /// Maximum proposals that can exist simultaneously
-pub const MAX_PROPOSALS: usize = 10;
+pub const MAX_PROPOSALS: usize = LIMITS.max_proposals as usize;
 
 /// Maximum operations in a single timelock batch
-pub const MAX_BATCH_OPS: usize = 5;
+pub const MAX_BATCH_OPS: usize = LIMITS.max_batch_ops as usize;
+
+/// Largest payload carried by a single proposal action, in bytes
+pub const MAX_ACTION_PAYLOAD: usize = 64;
+
+/// Checkpoints kept per vote history; pushing more drops the oldest
+pub const MAX_CHECKPOINTS: usize = 8;
+
+/// Archive digests kept per proposal ID; `prune` folds older ones into
+/// the running `hist_root` digest
+pub const MAX_ARCHIVED: usize = 2;
+
+/// Total XRP supply in drops (100 billion XRP); no amount can exceed it
+pub const MAX_XRP_DROPS: u64 = 100_000_000_000_000_000;
+
+/// Largest transaction memo payload read by an export (XRPL caps the
+/// serialized Memos field at 1 KB).
+pub const MEMO_CAPACITY: usize = 1024;
 
 // ═══════════════════════════════════════════════════════════════════════
 // GOVERNANCE SETTINGS (mirrors GovernorSettings.sol)
@@ -46,6 +102,16 @@ pub const PROPOSAL_THRESHOLD: u64 = 100_000_000; // 100 XRP
 /// Mirrors `GovernorVotesQuorumFraction`.
 pub const QUORUM_PERCENTAGE: u8 = 4; // 4% like OZ default
 
+/// A vote that first reaches quorum less than this many seconds before
+/// voting ends extends the vote. Mirrors GovernorPreventLateQuorum.
+/// Default: 1 day (0 disables the extension)
+pub const LATE_QUORUM_WINDOW: u32 = 86_400;
+
+/// Voting stays open at least this long after a late quorum.
+/// Mirrors `lateQuorumVoteExtension()` in GovernorPreventLateQuorum.
+/// Default: 1 day
+pub const LATE_QUORUM_EXTENSION: u32 = 86_400;
+
 // ═══════════════════════════════════════════════════════════════════════
 // TIMELOCK SETTINGS (mirrors TimelockController.sol)
 // ═══════════════════════════════════════════════════════════════════════
@@ -66,6 +132,67 @@ pub const TIMELOCK_GRACE_PERIOD: u32 = 1_209_600;
 /// Admin must grant voting power explicitly.
 pub const SELF_REGISTER_INITIAL_POWER: u64 = 0;
 
+// ═══════════════════════════════════════════════════════════════════════
+// RUNTIME SETTINGS (governance::settings)
+// ═══════════════════════════════════════════════════════════════════════
+//
+// The values above are defaults. A proposal can override each one within
+// the bounds below; see `governance::settings`.
+
+/// Setting id for `VOTING_DELAY`
+pub const SETTING_VOTING_DELAY: u8 = 0;
+/// Setting id for `VOTING_PERIOD`
+pub const SETTING_VOTING_PERIOD: u8 = 1;
+/// Setting id for `PROPOSAL_THRESHOLD`
+pub const SETTING_PROPOSAL_THRESHOLD: u8 = 2;
+/// Setting id for `QUORUM_PERCENTAGE`
+pub const SETTING_QUORUM_PERCENTAGE: u8 = 3;
+/// Setting id for `TIMELOCK_MIN_DELAY`
+pub const SETTING_TIMELOCK_MIN_DELAY: u8 = 4;
+/// Setting id for `TIMELOCK_GRACE_PERIOD`
+pub const SETTING_TIMELOCK_GRACE_PERIOD: u8 = 5;
+/// Setting id for `LATE_QUORUM_WINDOW`
+pub const SETTING_LATE_QUORUM_WINDOW: u8 = 6;
+/// Setting id for `LATE_QUORUM_EXTENSION`
+pub const SETTING_LATE_QUORUM_EXTENSION: u8 = 7;
+
+/// Action type that changes a setting instead of emitting a transaction:
+/// the payload is the one-byte `SETTING_*` id and the amount the new value.
+/// Outside the XRPL transaction-type range, so it never reaches the host.
+pub const ACTION_SET_SETTING: u16 = 0xFFFF;
+
+/// Voting may open immediately, but no later than 30 days after proposing.
+pub const MIN_VOTING_DELAY: u32 = 0;
+pub const MAX_VOTING_DELAY: u32 = 2_592_000;
+
+/// At least 1 hour of voting, at most 30 days.
+pub const MIN_VOTING_PERIOD: u32 = 3_600;
+pub const MAX_VOTING_PERIOD: u32 = 2_592_000;
+
+/// Up to the full 100B XRP supply, in drops.
+pub const MIN_PROPOSAL_THRESHOLD: u64 = 0;
+pub const MAX_PROPOSAL_THRESHOLD: u64 = MAX_XRP_DROPS;
+
+/// A zero quorum would let a single vote pass anything.
+pub const MIN_QUORUM_PERCENTAGE: u8 = 1;
+pub const MAX_QUORUM_PERCENTAGE: u8 = 100;
+
+/// The timelock cannot be disabled: at least 1 hour, at most 30 days.
+pub const MIN_TIMELOCK_DELAY: u32 = 3_600;
+pub const MAX_TIMELOCK_DELAY: u32 = 2_592_000;
+
+/// At least 1 day to execute a ready operation, at most 90 days.
+pub const MIN_GRACE_PERIOD: u32 = 86_400;
+pub const MAX_GRACE_PERIOD: u32 = 7_776_000;
+
+/// The window may be 0 (no extension), at most the longest voting period.
+pub const MIN_LATE_QUORUM_WINDOW: u32 = 0;
+pub const MAX_LATE_QUORUM_WINDOW: u32 = MAX_VOTING_PERIOD;
+
+/// At least 1 hour to react to a late quorum, at most 30 days.
+pub const MIN_LATE_QUORUM_EXTENSION: u32 = 3_600;
+pub const MAX_LATE_QUORUM_EXTENSION: u32 = 2_592_000;
+
 // ═══════════════════════════════════════════════════════════════════════
 // PROPOSAL STATES (mirrors IGovernor.ProposalState enum)
 // ═══════════════════════════════════════════════════════════════════════
@@ -108,6 +235,11 @@ pub const ROLE_PROPOSER: u8 = 1;
 pub const ROLE_EXECUTOR: u8 = 2;
 /// Role for the admin (can grant/revoke roles)
 pub const ROLE_ADMIN: u8 = 4;
+/// Role for guardians that can cancel any proposal before execution
+/// (mirrors CANCELLER_ROLE)
+pub const ROLE_CANCELLER: u8 = 8;
+/// Every defined role bit; anything outside is rejected
+pub const ALL_ROLES: u8 = ROLE_PROPOSER | ROLE_EXECUTOR | ROLE_ADMIN | ROLE_CANCELLER;
 
 // ═══════════════════════════════════════════════════════════════════════
 // TIMELOCK OPERATION STATES
@@ -151,3 +283,15 @@ pub const ERR_OVERFLOW: i32 = -19;
 pub const ERR_REENTRANT: i32 = -20;
 pub const ERR_OP_EXPIRED: i32 = -21;
 pub const ERR_CALLER_VERIFICATION: i32 = -22;
+pub const ERR_KEY_EXISTS: i32 = -23;
+pub const ERR_INVALID_ENTRY: i32 = -24;
+pub const ERR_DATA_FULL: i32 = -25;
+pub const ERR_PAGE_NOT_LOADED: i32 = -26;
+pub const ERR_SCHEMA_OUTDATED: i32 = -27;
+pub const ERR_SCHEMA_TOO_NEW: i32 = -28;
+pub const ERR_INVARIANT: i32 = -29;
+pub const ERR_NOT_GOVERNANCE: i32 = -30;
+pub const ERR_ALREADY_INITIALIZED: i32 = -31;
+pub const ERR_BAD_PARAM: i32 = -32;
+pub const ERR_EMIT_UNSUPPORTED: i32 = -33;
+pub const ERR_DESCRIPTION_MISMATCH: i32 = -34;
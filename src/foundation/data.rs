@@ -4,6 +4,17 @@
 //!   "notary_count=2;threshold=2;notary_0=abcd...;approval_count=1"
 //!
 //! All operations work on raw byte slices without heap allocation.
+//! Mutations go through `Store`, which edits a copy of the Data field
+//! in place and hands it back via `commit()`.
+//!
+//! State can also be split into pages (members, proposals, votes,
+//! operations), each in its own storage slot behind a directory page —
+//! see `load_pages` / `save_pages`.
+
+use crate::foundation::config::{
+    ERR_KEY_EXISTS, ERR_INVALID_ENTRY, ERR_DATA_FULL, ERR_PAGE_NOT_LOADED, ERR_HOST_CALL,
+};
+use crate::foundation::parse::format_u32;
 
 /// Find a value for a given key in semicolon-delimited "key=value" data.
 /// Returns the byte slice of the value, or None if key not found.
@@ -34,44 +45,29 @@ pub fn find_value<'a>(data: &'a [u8], key: &[u8]) -> Option<&'a [u8]> {
 
 /// Build a key like "notary_0", "approval_12", etc. into a buffer.
 /// Returns the number of bytes written.
-/// Fix #7: Supports multi-digit indices (0-255).
-pub fn build_indexed_key(prefix: &[u8], index: u8, out: &mut [u8]) -> usize {
+/// Fix #7: Supports multi-digit indices (0-65535).
+pub fn build_indexed_key(prefix: &[u8], index: u16, out: &mut [u8]) -> usize {
     let plen = prefix.len();
     if plen >= out.len() {
         return 0;
     }
     out[..plen].copy_from_slice(prefix);
     // Format index as multi-digit ASCII
-    let idx_len = if index >= 100 {
-        if plen + 3 > out.len() { return 0; }
-        out[plen] = b'0' + (index / 100);
-        out[plen + 1] = b'0' + ((index / 10) % 10);
-        out[plen + 2] = b'0' + (index % 10);
-        3
-    } else if index >= 10 {
-        if plen + 2 > out.len() { return 0; }
-        out[plen] = b'0' + (index / 10);
-        out[plen + 1] = b'0' + (index % 10);
-        2
-    } else {
-        if plen + 1 > out.len() { return 0; }
-        out[plen] = b'0' + index;
-        1
-    };
+    let idx_len = format_u32(index as u32, &mut out[plen..]);
     plen + idx_len
 }
 
-/// Write a key=value pair into data at the given position.
+/// Write a key=value pair, failing with ERR_DATA_FULL if it does not fit.
 /// Returns the new position after writing.
-pub fn write_entry(data: &mut [u8], pos: usize, key: &[u8], value: &[u8]) -> usize {
+pub fn try_write_entry(data: &mut [u8], pos: usize, key: &[u8], value: &[u8]) -> Result<usize, i32> {
     let needed = key.len() + 1 + value.len();
     if pos + needed > data.len() {
-        return pos;
+        return Err(ERR_DATA_FULL);
     }
     data[pos..pos + key.len()].copy_from_slice(key);
     data[pos + key.len()] = b'=';
     data[pos + key.len() + 1..pos + needed].copy_from_slice(value);
-    pos + needed
+    Ok(pos + needed)
 }
 
 /// Write a semicolon separator. Returns new position.
@@ -83,3 +79,500 @@ pub fn write_separator(data: &mut [u8], pos: usize) -> usize {
         pos
     }
 }
+
+// ═══════════════════════════════════════════════════════════════════════
+// Store — typed view over the escrow Data field
+// ═══════════════════════════════════════════════════════════════════════
+
+/// Size of the escrow Data field in bytes.
+pub const FIELD_CAPACITY: usize = 4096;
+
+/// Working size of the key=value text. Matches the Data field unless the
+/// `compact-state` feature stores the text encoded (see `foundation::codec`).
+#[cfg(not(feature = "compact-state"))]
+pub const DATA_CAPACITY: usize = FIELD_CAPACITY;
+
+/// Working size of the key=value text. With `compact-state` the Data field
+/// holds the encoded form, so the decoded text can outgrow the field.
+#[cfg(feature = "compact-state")]
+pub const DATA_CAPACITY: usize = 4 * FIELD_CAPACITY;
+
+/// Key-value store over a copy of the escrow Data field.
+///
+/// Edits are applied in place (existing entries keep their position), so
+/// callers never hand-roll a copy-and-rewrite loop. `commit()` hands back
+/// the final buffer to be written with `set_data`.
+///
+/// Example:
+///   let mut store = Store::load(b"a=1;b=2");
+///   store.set(b"b", b"3")?;          // a=1;b=3
+///   store.insert(b"c", b"4")?;       // a=1;b=3;c=4
+///   store.remove(b"a");              // b=3;c=4
+#[derive(Clone)]
+pub struct Store {
+    buf: [u8; DATA_CAPACITY],
+    len: usize,
+}
+
+impl Store {
+    /// Create an empty store.
+    pub fn new() -> Self {
+        Store { buf: [0u8; DATA_CAPACITY], len: 0 }
+    }
+
+    /// Load a store from raw Data field bytes.
+    /// Input beyond DATA_CAPACITY is ignored (the host never returns more).
+    pub fn load(data: &[u8]) -> Self {
+        let mut store = Store::new();
+        let len = data.len().min(DATA_CAPACITY);
+        store.buf[..len].copy_from_slice(&data[..len]);
+        store.len = len;
+        store
+    }
+
+    /// Current contents as "key=value;..." bytes.
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.buf[..self.len]
+    }
+
+    /// Number of bytes currently used.
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Total size of the Data field in bytes.
+    pub fn capacity(&self) -> usize {
+        DATA_CAPACITY
+    }
+
+    /// Bytes still free before writes start failing with ERR_DATA_FULL.
+    pub fn available(&self) -> usize {
+        DATA_CAPACITY - self.len
+    }
+
+    /// Get the value stored under `key`.
+    pub fn get(&self, key: &[u8]) -> Option<&[u8]> {
+        find_value(self.as_bytes(), key)
+    }
+
+    /// Insert or replace the value stored under `key`.
+    /// Existing entries are updated in place; new entries are appended.
+    /// Fails with ERR_DATA_FULL (leaving the store unchanged) if the
+    /// result would not fit in the Data field.
+    pub fn set(&mut self, key: &[u8], value: &[u8]) -> Result<(), i32> {
+        check_entry(key, value)?;
+        match self.locate(key) {
+            Some((_start, value_start, end)) => self.splice(value_start, end, &[value]),
+            None => self.append(key, value),
+        }
+    }
+
+    /// Append a new entry. Fails with ERR_KEY_EXISTS if `key` is already set.
+    pub fn insert(&mut self, key: &[u8], value: &[u8]) -> Result<(), i32> {
+        check_entry(key, value)?;
+        if self.locate(key).is_some() {
+            return Err(ERR_KEY_EXISTS);
+        }
+        self.append(key, value)
+    }
+
+    /// Remove the entry stored under `key`. Returns true if it existed.
+    pub fn remove(&mut self, key: &[u8]) -> bool {
+        let (start, _value_start, end) = match self.locate(key) {
+            Some(span) => span,
+            None => return false,
+        };
+        // Drop the entry together with one adjacent separator
+        let (from, to) = if end < self.len {
+            (start, end + 1)
+        } else if start > 0 {
+            (start - 1, end)
+        } else {
+            (start, end)
+        };
+        // Shrinking never exceeds capacity
+        let _ = self.splice(from, to, &[]);
+        true
+    }
+
+    /// Iterate over (key, value) pairs whose key starts with `prefix`.
+    pub fn iter_prefix<'a>(&'a self, prefix: &'a [u8]) -> PrefixIter<'a> {
+        iter_prefix(self.as_bytes(), prefix)
+    }
+
+    /// Finish editing and return the buffer to write back to the Data field.
+    pub fn commit(self) -> ([u8; DATA_CAPACITY], usize) {
+        (self.buf, self.len)
+    }
+
+    // ——— Internal helpers ———
+
+    /// Find an entry. Returns (entry_start, value_start, entry_end).
+    fn locate(&self, key: &[u8]) -> Option<(usize, usize, usize)> {
+        let data = self.as_bytes();
+        let mut pos = 0;
+        while pos < data.len() {
+            let entry_end = data[pos..].iter().position(|&b| b == b';')
+                .map(|p| pos + p)
+                .unwrap_or(data.len());
+
+            let entry = &data[pos..entry_end];
+            if let Some(eq_pos) = entry.iter().position(|&b| b == b'=') {
+                if &entry[..eq_pos] == key {
+                    return Some((pos, pos + eq_pos + 1, entry_end));
+                }
+            }
+            pos = entry_end + 1;
+        }
+        None
+    }
+
+    /// Append raw "k=v;k=v" text, preceded by a separator if non-empty.
+    fn extend_raw(&mut self, text: &[u8]) -> Result<(), i32> {
+        if text.is_empty() {
+            return Ok(());
+        }
+        let at = self.len;
+        if at > 0 {
+            self.splice(at, at, &[b";", text])
+        } else {
+            self.splice(at, at, &[text])
+        }
+    }
+
+    /// Append "key=value", preceded by a separator if the store is non-empty.
+    fn append(&mut self, key: &[u8], value: &[u8]) -> Result<(), i32> {
+        let at = self.len;
+        if at > 0 {
+            self.splice(at, at, &[b";", key, b"=", value])
+        } else {
+            self.splice(at, at, &[key, b"=", value])
+        }
+    }
+
+    /// Replace bytes [from, to) with the concatenation of `parts`.
+    fn splice(&mut self, from: usize, to: usize, parts: &[&[u8]]) -> Result<(), i32> {
+        let insert_len: usize = parts.iter().map(|p| p.len()).sum();
+        let new_len = self.len - (to - from) + insert_len;
+        if new_len > DATA_CAPACITY {
+            return Err(ERR_DATA_FULL);
+        }
+        self.buf.copy_within(to..self.len, from + insert_len);
+        let mut pos = from;
+        for part in parts {
+            self.buf[pos..pos + part.len()].copy_from_slice(part);
+            pos += part.len();
+        }
+        self.len = new_len;
+        Ok(())
+    }
+}
+
+impl Default for Store {
+    fn default() -> Self {
+        Store::new()
+    }
+}
+
+/// Iterator over entries whose key starts with a given prefix.
+pub struct PrefixIter<'a> {
+    data: &'a [u8],
+    prefix: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Iterator for PrefixIter<'a> {
+    type Item = (&'a [u8], &'a [u8]);
+
+    fn next(&mut self) -> Option<Self::Item> {
+        while self.pos < self.data.len() {
+            let start = self.pos;
+            let entry_end = self.data[start..].iter().position(|&b| b == b';')
+                .map(|p| start + p)
+                .unwrap_or(self.data.len());
+            self.pos = entry_end + 1;
+
+            let entry = &self.data[start..entry_end];
+            if let Some(eq_pos) = entry.iter().position(|&b| b == b'=') {
+                let key = &entry[..eq_pos];
+                if key.starts_with(self.prefix) {
+                    return Some((key, &entry[eq_pos + 1..]));
+                }
+            }
+        }
+        None
+    }
+}
+
+/// Report Data field usage as (bytes_used, bytes_available).
+/// `data` is the raw field content (encoded when `compact-state` is on).
+pub fn data_usage(data: &[u8]) -> (usize, usize) {
+    let used = data.len().min(FIELD_CAPACITY);
+    (used, FIELD_CAPACITY - used)
+}
+
+/// Check that key=value text fits the Data field once stored (encoded
+/// when `compact-state` is on). ERR_DATA_FULL otherwise.
+pub fn check_fits(text: &[u8]) -> Result<(), i32> {
+    #[cfg(not(feature = "compact-state"))]
+    {
+        if text.len() > FIELD_CAPACITY {
+            return Err(ERR_DATA_FULL);
+        }
+        Ok(())
+    }
+    #[cfg(feature = "compact-state")]
+    {
+        let mut raw = [0u8; FIELD_CAPACITY];
+        crate::foundation::codec::encode(text, &mut raw).map(|_| ())
+    }
+}
+
+/// Iterate over (key, value) pairs in raw data whose key starts with `prefix`.
+pub fn iter_prefix<'a>(data: &'a [u8], prefix: &'a [u8]) -> PrefixIter<'a> {
+    PrefixIter { data, prefix, pos: 0 }
+}
+
+/// Reject keys/values that would corrupt the key=value;... framing.
+fn check_entry(key: &[u8], value: &[u8]) -> Result<(), i32> {
+    if key.is_empty() || key.iter().any(|&b| b == b'=' || b == b';') {
+        return Err(ERR_INVALID_ENTRY);
+    }
+    if value.contains(&b';') {
+        return Err(ERR_INVALID_ENTRY);
+    }
+    Ok(())
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Paged state — one storage slot per key family
+// ═══════════════════════════════════════════════════════════════════════
+//
+// Slot 0 (the directory) maps to the escrow Data field and holds the slot
+// map plus any key no other page claims (`_lock`, ...). The XRPL WASM host
+// currently exposes only that one field, so the exports still use a single
+// slot; `MemorySlots` stands in for a multi-slot host.
+
+/// Size of a single page in bytes.
+pub const PAGE_CAPACITY: usize = FIELD_CAPACITY;
+
+/// Number of slots provided by `MemorySlots`.
+pub const MAX_SLOTS: usize = 8;
+
+/// A page of contract state. Keys are routed by prefix (`Page::for_key`).
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub enum Page {
+    Directory = 0,
+    Members = 1,
+    Proposals = 2,
+    Votes = 3,
+    Operations = 4,
+}
+
+impl Page {
+    /// Every page, directory first.
+    pub const ALL: [Page; 5] = [
+        Page::Directory, Page::Members, Page::Proposals, Page::Votes, Page::Operations,
+    ];
+
+    /// Page that owns `key`.
+    pub fn for_key(key: &[u8]) -> Page {
+        if key.starts_with(b"member_") || key.starts_with(b"delegate_") || key.starts_with(b"ckpt_") {
+            Page::Members
+        } else if key.starts_with(b"prop_") || key.starts_with(b"hist_") || key == b"proposal_count" {
+            Page::Proposals
+        } else if key.starts_with(b"vote_") || key.starts_with(b"sigvote_") || key.starts_with(b"snap_") {
+            Page::Votes
+        } else if key.starts_with(b"op_") {
+            Page::Operations
+        } else {
+            Page::Directory
+        }
+    }
+
+    /// Slot used when the directory has no entry for this page.
+    pub fn default_slot(self) -> u8 {
+        self as u8
+    }
+
+    /// Directory key recording this page's slot ("page_members", ...).
+    fn directory_key(self) -> &'static [u8] {
+        match self {
+            Page::Directory => b"page_directory",
+            Page::Members => b"page_members",
+            Page::Proposals => b"page_proposals",
+            Page::Votes => b"page_votes",
+            Page::Operations => b"page_ops",
+        }
+    }
+
+    /// Slot holding this page according to `directory`.
+    fn slot_in(self, directory: &[u8]) -> u8 {
+        if self == Page::Directory {
+            return 0;
+        }
+        find_value(directory, self.directory_key())
+            .and_then(crate::foundation::parse::parse_u8_digit)
+            .unwrap_or(self.default_slot())
+    }
+}
+
+/// Storage backend for paged state.
+pub trait SlotStore {
+    /// Read slot contents into `out`. Returns bytes read (0 if never written).
+    fn read_slot(&self, slot: u8, out: &mut [u8]) -> Result<usize, i32>;
+    /// Replace slot contents.
+    fn write_slot(&mut self, slot: u8, data: &[u8]) -> Result<(), i32>;
+}
+
+/// In-memory slot host for tests and off-chain tooling.
+/// Counts writes per slot so callers can check what was touched.
+pub struct MemorySlots {
+    slots: [[u8; PAGE_CAPACITY]; MAX_SLOTS],
+    lens: [usize; MAX_SLOTS],
+    writes: [u32; MAX_SLOTS],
+}
+
+impl MemorySlots {
+    /// Create a host with every slot empty.
+    pub fn new() -> Self {
+        MemorySlots {
+            slots: [[0u8; PAGE_CAPACITY]; MAX_SLOTS],
+            lens: [0; MAX_SLOTS],
+            writes: [0; MAX_SLOTS],
+        }
+    }
+
+    /// Raw contents of a slot.
+    pub fn slot(&self, slot: u8) -> &[u8] {
+        let i = slot as usize;
+        &self.slots[i][..self.lens[i]]
+    }
+
+    /// Number of writes made to a slot.
+    pub fn write_count(&self, slot: u8) -> u32 {
+        self.writes[slot as usize]
+    }
+}
+
+impl Default for MemorySlots {
+    fn default() -> Self {
+        MemorySlots::new()
+    }
+}
+
+impl SlotStore for MemorySlots {
+    fn read_slot(&self, slot: u8, out: &mut [u8]) -> Result<usize, i32> {
+        let i = slot as usize;
+        if i >= MAX_SLOTS { return Err(ERR_HOST_CALL); }
+        let len = self.lens[i];
+        if len > out.len() { return Err(ERR_DATA_FULL); }
+        out[..len].copy_from_slice(&self.slots[i][..len]);
+        Ok(len)
+    }
+
+    fn write_slot(&mut self, slot: u8, data: &[u8]) -> Result<(), i32> {
+        let i = slot as usize;
+        if i >= MAX_SLOTS { return Err(ERR_HOST_CALL); }
+        if data.len() > PAGE_CAPACITY { return Err(ERR_DATA_FULL); }
+        self.slots[i][..data.len()].copy_from_slice(data);
+        self.lens[i] = data.len();
+        self.writes[i] += 1;
+        Ok(())
+    }
+}
+
+/// Load the directory plus `pages` into one Store.
+///
+/// Only the requested slots are read. Keys from other pages are absent, so
+/// module functions see exactly the state the export declared it needs.
+pub fn load_pages<S: SlotStore>(slots: &S, pages: &[Page]) -> Result<Store, i32> {
+    let mut directory = [0u8; PAGE_CAPACITY];
+    let dir_len = slots.read_slot(0, &mut directory)?;
+    let directory = &directory[..dir_len];
+
+    let mut store = Store::new();
+    store.extend_raw(directory)?;
+
+    let mut page_buf = [0u8; PAGE_CAPACITY];
+    for (i, &page) in pages.iter().enumerate() {
+        if page == Page::Directory || pages[..i].contains(&page) {
+            continue;
+        }
+        let len = slots.read_slot(page.slot_in(directory), &mut page_buf)?;
+        store.extend_raw(&page_buf[..len])?;
+    }
+    Ok(store)
+}
+
+/// Split `text` back into pages and write the directory plus `pages`.
+///
+/// Fails with ERR_PAGE_NOT_LOADED if `text` holds a key from a page that
+/// was not loaded (its slot would be clobbered), with ERR_DATA_FULL if any
+/// page outgrows PAGE_CAPACITY, and with ERR_INVALID_ENTRY if the directory
+/// maps two saved pages to one slot. Every target slot is read before the
+/// first write, so nothing is written on failure, and slots whose contents
+/// did not change are not rewritten.
+pub fn save_pages<S: SlotStore>(slots: &mut S, text: &[u8], pages: &[Page]) -> Result<(), i32> {
+    let mut bufs = [[0u8; PAGE_CAPACITY]; Page::ALL.len()];
+    let mut lens = [0usize; Page::ALL.len()];
+
+    for (key, value) in iter_prefix(text, b"") {
+        let page = Page::for_key(key);
+        if page != Page::Directory && !pages.contains(&page) {
+            return Err(ERR_PAGE_NOT_LOADED);
+        }
+        let i = page as usize;
+        lens[i] = push_entry(&mut bufs[i], lens[i], key, value)?;
+    }
+
+    // Record the slot of every saved page in the directory
+    for &page in pages {
+        if page == Page::Directory || find_value(text, page.directory_key()).is_some() {
+            continue;
+        }
+        let slot = [b'0' + page.default_slot()];
+        lens[0] = push_entry(&mut bufs[0], lens[0], page.directory_key(), &slot)?;
+    }
+
+    // Resolve and compare every target slot before writing any of them, so
+    // a bad directory entry or unreadable slot fails with nothing written
+    let mut targets = [None; Page::ALL.len()];
+    let mut current = [0u8; PAGE_CAPACITY];
+    for page in Page::ALL {
+        if page != Page::Directory && !pages.contains(&page) {
+            continue;
+        }
+        let i = page as usize;
+        let slot = page.slot_in(&bufs[0][..lens[0]]);
+        // Two pages sharing a slot would overwrite each other
+        if targets.iter().flatten().any(|&(other, _)| other == slot) {
+            return Err(ERR_INVALID_ENTRY);
+        }
+        let current_len = slots.read_slot(slot, &mut current)?;
+        targets[i] = Some((slot, current[..current_len] != bufs[i][..lens[i]]));
+    }
+
+    for (i, target) in targets.iter().enumerate() {
+        if let Some((slot, true)) = *target {
+            slots.write_slot(slot, &bufs[i][..lens[i]])?;
+        }
+    }
+    Ok(())
+}
+
+/// Append "key=value" to a page buffer, with a separator if non-empty.
+fn push_entry(buf: &mut [u8], len: usize, key: &[u8], value: &[u8]) -> Result<usize, i32> {
+    if len == 0 {
+        return try_write_entry(buf, 0, key, value);
+    }
+    if len >= buf.len() {
+        return Err(ERR_DATA_FULL);
+    }
+    let pos = write_separator(buf, len);
+    try_write_entry(buf, pos, key, value)
+}
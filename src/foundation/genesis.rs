@@ -0,0 +1,152 @@
+//! Genesis state for a new DAO. Stands in for the Governor and
+//! TimelockController constructors.
+//!
+//! The `initialize` export reads a spec from the transaction memo, written
+//! in the Data field's own key=value format:
+//!
+//!   admin=<hex40>;member_0=<hex40>:<power>:<roles>;...;cfg_quorum=10
+//!
+//! `member_N` entries use the stored member format and are numbered from 0
+//! without gaps. `cfg_*` keys are optional `governance::settings`
+//! overrides. The admin always ends up a member holding ROLE_ADMIN; further
+//! members can be added later with `add_member`.
+
+use crate::foundation::abi::{decode_account, decode_amount};
+use crate::foundation::config::*;
+use crate::foundation::data::*;
+use crate::foundation::invariants;
+use crate::foundation::migrate::{CURRENT_SCHEMA_VERSION, SCHEMA_VERSION_KEY};
+use crate::foundation::parse::format_u32;
+use crate::foundation::types::Member;
+use crate::governance::governor::{parse_u64, write_count};
+use crate::governance::settings::{Settings, SETTING_KEYS};
+use crate::token::xrp_votes::{checkpoint_total_supply, checkpoint_votes};
+
+/// A parsed, validated genesis spec.
+pub struct Genesis {
+    pub admin: [u8; ACCOUNT_ID_SIZE],
+    pub members: [Member; MAX_MEMBERS],
+    pub member_count: u16,
+    pub settings: Settings,
+}
+
+impl Genesis {
+    /// Parse and validate a spec.
+    ///
+    /// Fails with ERR_INVALID_ENTRY on a malformed or unknown entry or a
+    /// member power above MAX_XRP_DROPS, ERR_KEY_EXISTS on a repeated
+    /// account, and ERR_BAD_CONFIG on unknown role bits, too many members
+    /// or an out-of-bounds setting.
+    pub fn parse(spec: &[u8]) -> Result<Genesis, i32> {
+        let mut genesis = Genesis {
+            admin: [0u8; ACCOUNT_ID_SIZE],
+            members: [Member::new(); MAX_MEMBERS],
+            member_count: 0,
+            settings: Settings::DEFAULT,
+        };
+
+        genesis.admin = find_value(spec, b"admin")
+            .and_then(decode_account)
+            .ok_or(ERR_INVALID_ENTRY)?;
+
+        // Each key may appear once; `seen` has a bit per setting plus admin
+        let mut seen: u16 = 0;
+        for (key, value) in iter_prefix(spec, b"") {
+            if key.starts_with(b"member_") {
+                continue;
+            }
+            let bit = if key == b"admin" {
+                1 << SETTING_KEYS.len()
+            } else {
+                let param = SETTING_KEYS.iter().position(|k| *k == key).ok_or(ERR_INVALID_ENTRY)?;
+                let value = parse_u64(value).ok_or(ERR_INVALID_ENTRY)?;
+                genesis.settings.set(param as u8, value)?;
+                1 << param
+            };
+            if seen & bit != 0 {
+                return Err(ERR_INVALID_ENTRY);
+            }
+            seen |= bit;
+        }
+
+        let declared = iter_prefix(spec, b"member_").count();
+        for i in 0..declared {
+            let index = u16::try_from(i).map_err(|_| ERR_INVALID_ENTRY)?;
+            let mut key_buf = [0u8; 16];
+            let klen = build_indexed_key(b"member_", index, &mut key_buf);
+            // Founding power obeys the same bound as `add_member`'s `power`
+            find_value(spec, &key_buf[..klen])
+                .and_then(|entry| entry.split(|&b| b == b':').nth(1))
+                .and_then(decode_amount)
+                .ok_or(ERR_INVALID_ENTRY)?;
+            let member = Member::load(spec, index).ok_or(ERR_INVALID_ENTRY)?;
+            genesis.push(member)?;
+        }
+
+        match genesis.members[..genesis.member_count as usize]
+            .iter_mut()
+            .find(|m| m.account == genesis.admin)
+        {
+            Some(admin) => admin.roles |= ROLE_ADMIN,
+            None => genesis.push(Member { account: genesis.admin, voting_power: 0, roles: ROLE_ADMIN })?,
+        }
+        Ok(genesis)
+    }
+
+    /// The founding members, admin included.
+    pub fn members(&self) -> &[Member] {
+        &self.members[..self.member_count as usize]
+    }
+
+    fn push(&mut self, member: Member) -> Result<(), i32> {
+        if member.roles & !ALL_ROLES != 0 {
+            return Err(ERR_BAD_CONFIG);
+        }
+        if self.members().iter().any(|m| m.account == member.account) {
+            return Err(ERR_KEY_EXISTS);
+        }
+        if self.member_count >= LIMITS.max_members {
+            return Err(ERR_BAD_CONFIG);
+        }
+        self.members[self.member_count as usize] = member;
+        self.member_count += 1;
+        Ok(())
+    }
+}
+
+/// Write the genesis state into an empty Data field.
+///
+/// Any existing state, including hand-built blobs from before this export
+/// existed, counts as initialized and fails with ERR_ALREADY_INITIALIZED.
+/// The result is stamped with the current `schema_version` and checked
+/// against `foundation::invariants` before it is returned. Members start
+/// their vote checkpoints at `current_time`.
+pub fn initialize(
+    data: &[u8],
+    data_len: usize,
+    genesis: &Genesis,
+    current_time: u32,
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
+    if !data[..data_len].is_empty() {
+        return Err(ERR_ALREADY_INITIALIZED);
+    }
+
+    let mut store = Store::new();
+    let mut ver_buf = [0u8; 10];
+    let vlen = format_u32(CURRENT_SCHEMA_VERSION, &mut ver_buf);
+    store.set(SCHEMA_VERSION_KEY, &ver_buf[..vlen])?;
+
+    write_count(&mut store, b"member_count", genesis.member_count)?;
+    for (i, member) in genesis.members().iter().enumerate() {
+        member.store(&mut store, i as u16)?;
+    }
+    genesis.settings.store(&mut store)?;
+    for member in genesis.members() {
+        checkpoint_votes(&mut store, &member.account, current_time)?;
+    }
+    checkpoint_total_supply(&mut store, current_time)?;
+
+    let (new_data, new_len) = store.commit();
+    invariants::check(&new_data[..new_len])?;
+    Ok((new_data, new_len))
+}
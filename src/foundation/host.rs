@@ -0,0 +1,311 @@
+//! Host abstraction for the WASM exports.
+//!
+//! Every export in `exports` reaches the ledger only through `Host`, so
+//! the same code runs against the real XRPL host functions (`XrplHost`,
+//! wired up by the `extern "C"` wrappers in `lib.rs`) or an in-memory
+//! `MockHost` in tests.
+
+use xrpl_wasm_stdlib::core::locator::Locator;
+use xrpl_wasm_stdlib::core::types::transaction_type::TransactionType;
+use xrpl_wasm_stdlib::sfield;
+
+use crate::foundation::config::*;
+use crate::foundation::data::{DATA_CAPACITY, FIELD_CAPACITY};
+use crate::foundation::types::{Action, Actions};
+
+/// Field codes (type << 16 | field) used to locate memo payloads.
+pub const SF_MEMOS: i32 = sfield::Memos;
+pub const SF_MEMO_DATA: i32 = sfield::MemoData;
+
+/// Memos a `MockHost` transaction can carry: parameters and a description.
+pub const MOCK_MEMOS: usize = 2;
+
+/// Locator of `Memos[index].MemoData` in the current transaction.
+pub fn memo_locator(index: i32) -> Locator {
+    let mut locator = Locator::new();
+    locator.pack(SF_MEMOS);
+    locator.pack(index);
+    locator.pack(SF_MEMO_DATA);
+    locator
+}
+
+/// The packed bytes of `locator`, as the host reads them.
+pub fn locator_bytes(locator: &Locator) -> &[u8] {
+    // The first `len()` bytes of the buffer are the packed codes
+    unsafe { core::slice::from_raw_parts(locator.as_ptr(), locator.len()) }
+}
+
+/// Whether the XRPL host can submit an action of `tx_type`: Payment,
+/// TrustSet or EscrowFinish.
+pub fn is_emittable(tx_type: u16) -> bool {
+    // Codes past i16::MAX wrap negative and map to Invalid
+    matches!(
+        TransactionType::from(tx_type as i16),
+        TransactionType::Payment | TransactionType::TrustSet | TransactionType::EscrowFinish
+    )
+}
+
+/// The host functions the contract imports. Return conventions follow the
+/// XRPL WASM host: a non-negative length or value, or a negative error.
+pub trait Host {
+    /// Copy the escrow Data field into `buf`. Returns bytes copied.
+    fn get_data(&mut self, buf: &mut [u8]) -> i32;
+    /// Replace the escrow Data field with `data`.
+    fn set_data(&mut self, data: &[u8]) -> i32;
+    /// AccountID of the account that submitted the transaction.
+    fn get_current_account(&mut self, buf: &mut [u8; ACCOUNT_ID_SIZE]) -> i32;
+    /// Close time of the parent ledger, in seconds.
+    fn get_current_ledger_time(&mut self) -> i64;
+    /// Copy a nested field of the transaction, addressed by a locator of
+    /// little-endian i32 field codes and array indices, into `buf`.
+    fn get_tx_nested_field(&mut self, locator: &[u8], buf: &mut [u8]) -> i32;
+    /// Submit `action` as a transaction signed by the DAO's own account.
+    /// Non-negative once the host accepts it.
+    fn emit_transaction(&mut self, action: &Action) -> i32;
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// XRPL host
+// ═══════════════════════════════════════════════════════════════════════
+
+// Transaction fields are read through xrpl-wasm-stdlib's `host_lib`
+// bindings. The Data field, caller, clock and emission imports are not in
+// its 0.7 binding set, so they are declared here against `env`.
+#[cfg(all(target_arch = "wasm32", not(test)))]
+extern "C" {
+    fn get_data(buf: *mut u8, len: u32) -> i32;
+    fn set_data(buf: *const u8, len: u32) -> i32;
+    fn get_current_account(buf: *mut u8, len: u32) -> i32;
+    fn get_current_ledger_time() -> i64;
+    fn emit_transaction(
+        tx_type: i32,
+        destination: *const u8,
+        amount: i64,
+        payload: *const u8,
+        payload_len: u32,
+    ) -> i32;
+}
+
+/// The real XRPL WASM host functions.
+#[cfg(all(target_arch = "wasm32", not(test)))]
+pub struct XrplHost;
+
+#[cfg(all(target_arch = "wasm32", not(test)))]
+impl Host for XrplHost {
+    fn get_data(&mut self, buf: &mut [u8]) -> i32 {
+        unsafe { get_data(buf.as_mut_ptr(), buf.len() as u32) }
+    }
+
+    fn set_data(&mut self, data: &[u8]) -> i32 {
+        unsafe { set_data(data.as_ptr(), data.len() as u32) }
+    }
+
+    fn get_current_account(&mut self, buf: &mut [u8; ACCOUNT_ID_SIZE]) -> i32 {
+        unsafe { get_current_account(buf.as_mut_ptr(), ACCOUNT_ID_SIZE as u32) }
+    }
+
+    fn get_current_ledger_time(&mut self) -> i64 {
+        unsafe { get_current_ledger_time() }
+    }
+
+    fn get_tx_nested_field(&mut self, locator: &[u8], buf: &mut [u8]) -> i32 {
+        unsafe {
+            xrpl_wasm_stdlib::host::get_tx_nested_field(locator.as_ptr(), locator.len(), buf.as_mut_ptr(), buf.len())
+        }
+    }
+
+    /// Payment, TrustSet and EscrowFinish go to the host's emit import with
+    /// the payload passed through as the type-specific fields; any other
+    /// type is refused with ERR_EMIT_UNSUPPORTED without a host call.
+    fn emit_transaction(&mut self, action: &Action) -> i32 {
+        if !is_emittable(action.tx_type) {
+            return ERR_EMIT_UNSUPPORTED;
+        }
+        let payload = action.payload();
+        unsafe {
+            emit_transaction(
+                action.tx_type as i32,
+                action.target.as_ptr(),
+                action.amount as i64,
+                payload.as_ptr(),
+                payload.len() as u32,
+            )
+        }
+    }
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// In-memory host
+// ═══════════════════════════════════════════════════════════════════════
+
+/// An in-memory ledger for tests and off-chain tooling.
+///
+/// Holds one escrow Data field, the submitting account, the ledger time,
+/// up to MOCK_MEMOS memos and the transactions emitted by the current
+/// call. Data is stored exactly as the contract writes it (encoded under
+/// `compact-state`); `state`/`with_state` work in text.
+pub struct MockHost {
+    /// The escrow Data field, as stored
+    pub data: [u8; FIELD_CAPACITY],
+    pub data_len: usize,
+    /// Account returned by `get_current_account`
+    pub caller: [u8; ACCOUNT_ID_SIZE],
+    /// If set, returned instead of `caller` from the second read on, to
+    /// exercise the caller double-read check (Fix #3)
+    pub spoofed_caller: Option<[u8; ACCOUNT_ID_SIZE]>,
+    /// Ledger close time
+    pub time: u32,
+    /// Memo payloads of the current transaction; the first `memo_count`
+    /// are present, with lengths in `memo_lens`
+    pub memos: [[u8; MEMO_CAPACITY]; MOCK_MEMOS],
+    pub memo_lens: [usize; MOCK_MEMOS],
+    pub memo_count: usize,
+    /// Number of successful `set_data` calls
+    pub writes: u32,
+    /// Actions emitted by the current transaction, in order. Types the
+    /// XRPL host cannot emit are refused as they are on-chain.
+    pub emitted: Actions,
+    /// If set, emitting an action of this transaction type fails with
+    /// ERR_HOST_CALL
+    pub failing_tx_type: Option<u16>,
+    account_reads: u32,
+}
+
+impl MockHost {
+    /// An empty escrow at time 0, called by the zero account.
+    pub fn new() -> Self {
+        MockHost {
+            data: [0u8; FIELD_CAPACITY],
+            data_len: 0,
+            caller: [0u8; ACCOUNT_ID_SIZE],
+            spoofed_caller: None,
+            time: 0,
+            memos: [[0u8; MEMO_CAPACITY]; MOCK_MEMOS],
+            memo_lens: [0; MOCK_MEMOS],
+            memo_count: 0,
+            writes: 0,
+            emitted: Actions::new(),
+            failing_tx_type: None,
+            account_reads: 0,
+        }
+    }
+
+    /// A host whose Data field holds `text` (encoded under `compact-state`).
+    pub fn with_state(text: &[u8]) -> Self {
+        let mut host = MockHost::new();
+        #[cfg(not(feature = "compact-state"))]
+        {
+            host.data[..text.len()].copy_from_slice(text);
+            host.data_len = text.len();
+        }
+        #[cfg(feature = "compact-state")]
+        {
+            let len = crate::foundation::codec::encode(text, &mut host.data).expect("state too large");
+            host.data_len = len;
+        }
+        host
+    }
+
+    /// The Data field as key=value text.
+    pub fn state(&self) -> ([u8; DATA_CAPACITY], usize) {
+        let mut out = [0u8; DATA_CAPACITY];
+        #[cfg(not(feature = "compact-state"))]
+        let len = {
+            out[..self.data_len].copy_from_slice(&self.data[..self.data_len]);
+            self.data_len
+        };
+        #[cfg(feature = "compact-state")]
+        let len = crate::foundation::codec::to_text(&self.data[..self.data_len], &mut out)
+            .expect("undecodable state");
+        (out, len)
+    }
+
+    /// Start a new transaction from `caller` with no memo.
+    pub fn call_as(&mut self, caller: &[u8; ACCOUNT_ID_SIZE]) -> &mut Self {
+        self.caller = *caller;
+        self.spoofed_caller = None;
+        self.memo_count = 0;
+        self.emitted = Actions::new();
+        self.account_reads = 0;
+        self
+    }
+
+    /// Attach `memo` to the current transaction as its next memo (memo 0,
+    /// then memo 1, ...).
+    pub fn with_memo(&mut self, memo: &[u8]) -> &mut Self {
+        let index = self.memo_count;
+        assert!(index < MOCK_MEMOS, "MockHost holds at most {MOCK_MEMOS} memos");
+        self.memos[index][..memo.len()].copy_from_slice(memo);
+        self.memo_lens[index] = memo.len();
+        self.memo_count += 1;
+        self
+    }
+
+    /// Move the ledger clock forward.
+    pub fn advance(&mut self, seconds: u32) {
+        self.time += seconds;
+    }
+}
+
+impl Default for MockHost {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Host for MockHost {
+    fn get_data(&mut self, buf: &mut [u8]) -> i32 {
+        if buf.len() < self.data_len {
+            return ERR_DATA_READ;
+        }
+        buf[..self.data_len].copy_from_slice(&self.data[..self.data_len]);
+        self.data_len as i32
+    }
+
+    fn set_data(&mut self, data: &[u8]) -> i32 {
+        if data.len() > self.data.len() {
+            return ERR_HOST_CALL;
+        }
+        self.data[..data.len()].copy_from_slice(data);
+        self.data_len = data.len();
+        self.writes += 1;
+        SUCCESS
+    }
+
+    fn get_current_account(&mut self, buf: &mut [u8; ACCOUNT_ID_SIZE]) -> i32 {
+        self.account_reads += 1;
+        *buf = match self.spoofed_caller {
+            Some(spoofed) if self.account_reads > 1 => spoofed,
+            _ => self.caller,
+        };
+        ACCOUNT_ID_SIZE as i32
+    }
+
+    fn get_current_ledger_time(&mut self) -> i64 {
+        self.time as i64
+    }
+
+    fn get_tx_nested_field(&mut self, locator: &[u8], buf: &mut [u8]) -> i32 {
+        for index in 0..self.memo_count {
+            let len = self.memo_lens[index];
+            if locator == locator_bytes(&memo_locator(index as i32)) && buf.len() >= len {
+                buf[..len].copy_from_slice(&self.memos[index][..len]);
+                return len as i32;
+            }
+        }
+        ERR_HOST_CALL
+    }
+
+    fn emit_transaction(&mut self, action: &Action) -> i32 {
+        if !is_emittable(action.tx_type) {
+            return ERR_EMIT_UNSUPPORTED;
+        }
+        if self.failing_tx_type == Some(action.tx_type) {
+            return ERR_HOST_CALL;
+        }
+        match self.emitted.push(*action) {
+            Ok(()) => 0,
+            Err(code) => code,
+        }
+    }
+}
@@ -0,0 +1,129 @@
+//! Global consistency checks over the Data field.
+//!
+//! Every export keeps these invariants; `check` verifies them after the
+//! fact, for tests and as an on-demand audit of live state:
+//!
+//! - `member_count` equals the number of `member_N` entries, all parseable
+//! - no AccountID is registered twice
+//! - each proposal's `for/against/abstain` equals the sum of its votes, and
+//!   its frozen quorum does not exceed its frozen supply
+//! - each proposal's `prop_N_act_*` actions parse
+//! - every operation links to an existing proposal: Queued while the op is
+//!   pending, Executed once it is done
+//! - the `_lock` reentrancy flag and `_exec` marker are cleared between
+//!   transactions
+//! - every stored `cfg_*` setting is within its bounds
+
+use crate::foundation::config::*;
+use crate::foundation::data::{find_value, iter_prefix};
+use crate::foundation::types::{Actions, Member, Proposal, TimelockOp, VoteRecord};
+use crate::governance::governor::{find_proposal_by_id, parse_u64, read_count};
+use crate::governance::settings::{check_bounds, SETTING_KEYS};
+
+/// Verify every invariant. Returns ERR_INVARIANT on the first violation.
+pub fn check(data: &[u8]) -> Result<(), i32> {
+    check_members(data)?;
+    check_tallies(data)?;
+    check_actions(data)?;
+    check_operations(data)?;
+    check_unlocked(data)?;
+    check_settings(data)
+}
+
+/// `member_count` matches the `member_N` entries; AccountIDs are unique.
+fn check_members(data: &[u8]) -> Result<(), i32> {
+    let member_count = read_count(data, b"member_count");
+    let entries = iter_prefix(data, b"member_")
+        .filter(|(key, _)| *key != b"member_count")
+        .count();
+    if entries != member_count as usize {
+        return Err(ERR_INVARIANT);
+    }
+
+    for i in 0..member_count {
+        let member = Member::load(data, i).ok_or(ERR_INVARIANT)?;
+        let duplicate = (0..i)
+            .filter_map(|j| Member::load(data, j))
+            .any(|other| other.account == member.account);
+        if duplicate {
+            return Err(ERR_INVARIANT);
+        }
+    }
+    Ok(())
+}
+
+/// Each proposal's tallies equal the sum of its `vote_P_*` records and its
+/// quorum fits within its supply.
+fn check_tallies(data: &[u8]) -> Result<(), i32> {
+    for i in 0..read_count(data, b"proposal_count") {
+        let proposal = Proposal::load(data, i).ok_or(ERR_INVARIANT)?;
+        if proposal.quorum > proposal.total_supply {
+            return Err(ERR_INVARIANT);
+        }
+
+        let mut sums = [0u64; 3];
+        let mut k: u16 = 0;
+        while let Some(vote) = VoteRecord::load(data, i, k) {
+            let sum = &mut sums[vote.support as usize];
+            *sum = sum.checked_add(vote.weight).ok_or(ERR_INVARIANT)?;
+            k = k.checked_add(1).ok_or(ERR_INVARIANT)?;
+        }
+
+        let [against, for_votes, abstain] = sums;
+        if (proposal.for_votes, proposal.against_votes, proposal.abstain_votes)
+            != (for_votes, against, abstain)
+        {
+            return Err(ERR_INVARIANT);
+        }
+    }
+    Ok(())
+}
+
+/// Each proposal's stored actions load in full.
+fn check_actions(data: &[u8]) -> Result<(), i32> {
+    for i in 0..read_count(data, b"proposal_count") {
+        Actions::load(data, i).ok_or(ERR_INVARIANT)?;
+    }
+    Ok(())
+}
+
+/// Every operation links to a proposal in the matching state.
+fn check_operations(data: &[u8]) -> Result<(), i32> {
+    for j in 0..read_count(data, b"op_count") {
+        let op = TimelockOp::load(data, j).ok_or(ERR_INVARIANT)?;
+        let index = find_proposal_by_id(data, &op.proposal_id).map_err(|_| ERR_INVARIANT)?;
+        let proposal = Proposal::load(data, index).ok_or(ERR_INVARIANT)?;
+
+        let expected = match op.state {
+            OP_STATE_PENDING => PROPOSAL_STATE_QUEUED,
+            OP_STATE_DONE => PROPOSAL_STATE_EXECUTED,
+            _ => continue,
+        };
+        if proposal.state != expected {
+            return Err(ERR_INVARIANT);
+        }
+    }
+    Ok(())
+}
+
+/// The reentrancy lock and execution marker never persist past a transaction.
+fn check_unlocked(data: &[u8]) -> Result<(), i32> {
+    if find_value(data, b"_exec").is_some() {
+        return Err(ERR_INVARIANT);
+    }
+    match find_value(data, b"_lock") {
+        None | Some(b"0") => Ok(()),
+        Some(_) => Err(ERR_INVARIANT),
+    }
+}
+
+/// Stored settings parse and sit within their bounds. `Settings::load`
+/// would silently fall back to the default for a bad value.
+fn check_settings(data: &[u8]) -> Result<(), i32> {
+    for (key, value) in iter_prefix(data, b"cfg_") {
+        let param = SETTING_KEYS.iter().position(|k| *k == key).ok_or(ERR_INVARIANT)?;
+        let value = parse_u64(value).ok_or(ERR_INVARIANT)?;
+        check_bounds(param as u8, value).map_err(|_| ERR_INVARIANT)?;
+    }
+    Ok(())
+}
@@ -0,0 +1,410 @@
+//! Schema versioning and in-place migration of the Data field layout.
+//!
+//! The blob records the layout that wrote it under `schema_version`.
+//! Blobs written before versioning have no such key and count as
+//! version 0. Each release that changes a key format appends a step to
+//! `STEPS`; `upgrade` runs the pending steps in order and stamps the new
+//! version after each one.
+//!
+//! Several steps grow the blob, and every other export rejects outdated
+//! state, so a DAO too full to upgrade could never prune its way out.
+//! When the steps run out of space `upgrade` drops finished proposals
+//! first (see `drop_finished`) and tries again.
+
+use crate::foundation::config::*;
+use crate::foundation::data::{find_value, iter_prefix, Store, DATA_CAPACITY};
+use crate::foundation::parse::{parse_u32, parse_u8_digit, format_u32};
+use crate::crypto::hash::legacy_id;
+use crate::crypto::hex::encode_hex;
+use crate::governance::governor::{build_prop_key, format_u64, read_count};
+use crate::governance::prune::split_indexed_key;
+use crate::governance::settings::Settings;
+use crate::foundation::types::Member;
+use crate::governance::votes::get_total_voting_power;
+use crate::token::xrp_votes::{checkpoint_total_supply, checkpoint_votes};
+
+/// Key holding the layout version.
+pub const SCHEMA_VERSION_KEY: &[u8] = b"schema_version";
+
+/// Layout version written by this build.
+pub const CURRENT_SCHEMA_VERSION: u32 = 8;
+
+/// Most proposals any layout can hold: even a v0 `prop_N_id=<decimal>;`
+/// entry takes 12 bytes. Sizes `drop_finished`'s scratch array.
+const MAX_STORED_PROPOSALS: usize = DATA_CAPACITY / b"prop_0_id=0;".len();
+
+/// One migration: upgrades a store from version `from` to `from + 1`.
+struct Step {
+    from: u32,
+    apply: fn(&mut Store) -> Result<(), i32>,
+}
+
+/// Ordered migration steps. `STEPS[i].from == i`.
+const STEPS: [Step; CURRENT_SCHEMA_VERSION as usize] = [
+    Step { from: 0, apply: v0_stamp_version },
+    Step { from: 1, apply: v1_backfill_eta },
+    Step { from: 2, apply: v2_widen_ids },
+    Step { from: 3, apply: v3_widen_descriptions },
+    Step { from: 4, apply: v4_freeze_quorum },
+    Step { from: 5, apply: v5_seed_checkpoints },
+    Step { from: 6, apply: v6_decimal_roles },
+    Step { from: 7, apply: v7_widen_archive_digests },
+];
+
+/// Layout version recorded in `data` (0 if unversioned).
+pub fn schema_version(data: &[u8]) -> u32 {
+    find_value(data, SCHEMA_VERSION_KEY)
+        .and_then(parse_u32)
+        .unwrap_or(0)
+}
+
+/// Check that `data` uses the layout this build understands.
+/// Empty data (a fresh escrow) is accepted.
+pub fn check(data: &[u8]) -> Result<(), i32> {
+    if data.is_empty() {
+        return Ok(());
+    }
+    let version = schema_version(data);
+    if version < CURRENT_SCHEMA_VERSION {
+        return Err(ERR_SCHEMA_OUTDATED);
+    }
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(ERR_SCHEMA_TOO_NEW);
+    }
+    Ok(())
+}
+
+/// Run every pending migration step on `data`.
+///
+/// Returns the upgraded data. Already-current data comes back unchanged;
+/// data from a newer release fails with ERR_SCHEMA_TOO_NEW. If the steps
+/// fail with ERR_DATA_FULL, finished proposals are dropped and the steps
+/// retried; with none to drop the error stands.
+pub fn upgrade(
+    data: &[u8],
+    data_len: usize,
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
+    match run_steps(Store::load(&data[..data_len])) {
+        Err(ERR_DATA_FULL) => {
+            let mut store = Store::load(&data[..data_len]);
+            if !drop_finished(&mut store)? {
+                return Err(ERR_DATA_FULL);
+            }
+            run_steps(store)
+        }
+        result => result,
+    }
+}
+
+/// Apply every pending step to `store`, stamping the version after each.
+fn run_steps(mut store: Store) -> Result<([u8; DATA_CAPACITY], usize), i32> {
+    let mut version = schema_version(store.as_bytes());
+    if version > CURRENT_SCHEMA_VERSION {
+        return Err(ERR_SCHEMA_TOO_NEW);
+    }
+
+    while version < CURRENT_SCHEMA_VERSION {
+        let step = &STEPS[version as usize];
+        debug_assert_eq!(step.from, version);
+        (step.apply)(&mut store)?;
+        version += 1;
+
+        let mut ver_buf = [0u8; 10];
+        let vlen = format_u32(version, &mut ver_buf);
+        store.set(SCHEMA_VERSION_KEY, &ver_buf[..vlen])?;
+    }
+
+    Ok(store.commit())
+}
+
+/// Drop proposals whose stored state is final (Canceled, Defeated,
+/// Executed, Expired) with their votes, snapshots, signature intents and
+/// timelock operations, and renumber the survivors. Returns whether
+/// anything was dropped.
+///
+/// Only reads entries every layout shares and compares IDs as stored
+/// text, so it runs before any step. Unlike `governance::prune` it leaves
+/// no `hist_` digest, which needs the current layout. An operation still
+/// named as a predecessor keeps its proposal, as in `prune`. Works over
+/// the stored `proposal_count`, which older releases allowed to exceed
+/// this build's MAX_PROPOSALS.
+fn drop_finished(store: &mut Store) -> Result<bool, i32> {
+    let snapshot = Store::load(store.as_bytes());
+    let data = snapshot.as_bytes();
+    let prop_count = read_count(data, b"proposal_count");
+    let op_count = read_count(data, b"op_count");
+    if prop_count as usize > MAX_STORED_PROPOSALS {
+        return Err(ERR_INVALID_ENTRY);
+    }
+
+    let mut dropped = [false; MAX_STORED_PROPOSALS];
+    for i in 0..prop_count {
+        dropped[i as usize] = matches!(
+            entry(data, b"prop_", i, b"_state").and_then(parse_u8_digit),
+            Some(PROPOSAL_STATE_CANCELED | PROPOSAL_STATE_DEFEATED
+                | PROPOSAL_STATE_EXECUTED | PROPOSAL_STATE_EXPIRED)
+        );
+    }
+    let dropped = &mut dropped[..prop_count as usize];
+
+    // Un-dropping a proposal can make its own op's predecessor live
+    loop {
+        let mut changed = false;
+        for j in 0..op_count {
+            let Some(owner) = op_owner(data, j, prop_count).filter(|&i| dropped[i as usize]) else { continue };
+            let Some(id) = entry(data, b"op_", j, b"_id") else { continue };
+            let named = (0..op_count).any(|k| {
+                !is_op_dropped(data, k, prop_count, dropped)
+                    && entry(data, b"op_", k, b"_predecessor") == Some(id)
+            });
+            if named {
+                dropped[owner as usize] = false;
+                changed = true;
+            }
+        }
+        if !changed { break; }
+    }
+    let dropped = &*dropped;
+    if !dropped.contains(&true) {
+        return Ok(false);
+    }
+
+    let new_prop_index = |i: u16| i - dropped[..i as usize].iter().filter(|&&d| d).count() as u16;
+    let new_op_index = |j: u16| j - (0..j).filter(|&k| is_op_dropped(data, k, prop_count, dropped)).count() as u16;
+    let dropped_id_key = |key: &[u8]| {
+        (0..prop_count).filter(|&i| dropped[i as usize]).any(|i| {
+            let Some(id) = entry(data, b"prop_", i, b"_id") else { return false };
+            [b"snap_" as &[u8], b"sigvote_"].iter().any(|prefix| {
+                key.strip_prefix(*prefix)
+                    .and_then(|rest| rest.strip_prefix(id))
+                    .is_some_and(|tail| tail.first() == Some(&b'_'))
+            })
+        })
+    };
+
+    let mut kept = Store::new();
+    let mut key_buf = [0u8; 48];
+    let mut num_buf = [0u8; 5];
+    for (key, value) in iter_prefix(data, b"") {
+        if key == b"proposal_count" {
+            let nlen = format_u32(new_prop_index(prop_count) as u32, &mut num_buf);
+            kept.insert(key, &num_buf[..nlen])?;
+        } else if key == b"op_count" {
+            let nlen = format_u32(new_op_index(op_count) as u32, &mut num_buf);
+            kept.insert(key, &num_buf[..nlen])?;
+        } else if let Some((i, rest)) = split_indexed_key(key, b"prop_") {
+            if dropped.get(i as usize) == Some(&false) {
+                let klen = build_prop_key(b"prop_", new_prop_index(i), rest, &mut key_buf);
+                kept.insert(&key_buf[..klen], value)?;
+            }
+        } else if let Some((i, rest)) = split_indexed_key(key, b"vote_") {
+            if dropped.get(i as usize) == Some(&false) {
+                let klen = build_prop_key(b"vote_", new_prop_index(i), rest, &mut key_buf);
+                kept.insert(&key_buf[..klen], value)?;
+            }
+        } else if let Some((j, rest)) = split_indexed_key(key, b"op_") {
+            if !is_op_dropped(data, j, prop_count, dropped) {
+                let klen = build_prop_key(b"op_", new_op_index(j), rest, &mut key_buf);
+                kept.insert(&key_buf[..klen], value)?;
+            }
+        } else if !dropped_id_key(key) {
+            kept.insert(key, value)?;
+        }
+    }
+
+    *store = kept;
+    Ok(true)
+}
+
+/// Index of the proposal whose stored ID op `j` names, if any.
+fn op_owner(data: &[u8], j: u16, prop_count: u16) -> Option<u16> {
+    let id = entry(data, b"op_", j, b"_prop")?;
+    (0..prop_count).find(|&i| entry(data, b"prop_", i, b"_id") == Some(id))
+}
+
+/// Whether op `j` belongs to a dropped proposal.
+fn is_op_dropped(data: &[u8], j: u16, prop_count: u16, dropped: &[bool]) -> bool {
+    op_owner(data, j, prop_count).is_some_and(|i| dropped[i as usize])
+}
+
+// ——— Steps ———
+
+/// 0 → 1: introduce `schema_version`. The v0 layout is otherwise kept.
+fn v0_stamp_version(_store: &mut Store) -> Result<(), i32> {
+    Ok(())
+}
+
+/// 1 → 2: add `prop_N_eta` to proposals queued before it was recorded,
+/// taken from the linked operation's ready time. Reads the raw v1 entries
+/// (decimal IDs) rather than the current `Proposal` layout.
+fn v1_backfill_eta(store: &mut Store) -> Result<(), i32> {
+    let snapshot = Store::load(store.as_bytes());
+    let data = snapshot.as_bytes();
+    let queued = [b'0' + PROPOSAL_STATE_QUEUED];
+
+    for i in 0..read_count(data, b"proposal_count") {
+        if entry(data, b"prop_", i, b"_state") != Some(&queued[..])
+            || !matches!(entry(data, b"prop_", i, b"_eta"), None | Some(b"0"))
+        {
+            continue;
+        }
+        let Some(id) = entry(data, b"prop_", i, b"_id") else { continue };
+        let ready = (0..read_count(data, b"op_count"))
+            .find(|&j| entry(data, b"op_", j, b"_prop") == Some(id))
+            .and_then(|j| entry(data, b"op_", j, b"_ready"));
+        if let Some(ready) = ready {
+            let mut key = [0u8; 32];
+            let klen = build_prop_key(b"prop_", i, b"_eta", &mut key);
+            store.set(&key[..klen], ready)?;
+        }
+    }
+    Ok(())
+}
+
+/// 2 → 3: proposal and operation IDs become 256-bit hex. Legacy decimal
+/// IDs are widened with `legacy_id` wherever they appear: the values of
+/// `prop_N_id`, `op_N_id`, `op_N_prop`, `op_N_predecessor` and `_exec`,
+/// and inside `snap_<id>_*`, `sigvote_<id>_*` and `hist_<id>` keys.
+fn v2_widen_ids(store: &mut Store) -> Result<(), i32> {
+    let snapshot = Store::load(store.as_bytes());
+    let mut widened = Store::new();
+    let mut key_buf = [0u8; 128];
+    let mut id_hex = [0u8; HASH_SIZE * 2];
+
+    for (key, value) in snapshot.iter_prefix(b"") {
+        match parse_u32(value) {
+            Some(id) if is_id_field(key) => {
+                encode_hex(&legacy_id(id), &mut id_hex);
+                widened.insert(key, &id_hex)?;
+            }
+            _ => match widen_id_key(key, &mut key_buf) {
+                Some(klen) => widened.insert(&key_buf[..klen], value)?,
+                None => widened.insert(key, value)?,
+            },
+        }
+    }
+
+    *store = widened;
+    Ok(())
+}
+
+/// 3 → 4: `prop_N_desc` becomes the 256-bit description hash. The 32-bit
+/// values stored before cannot be recovered as real digests and are
+/// widened with `legacy_id`. `hist_*` digests keep the inputs they were
+/// computed from.
+fn v3_widen_descriptions(store: &mut Store) -> Result<(), i32> {
+    let snapshot = Store::load(store.as_bytes());
+    let data = snapshot.as_bytes();
+    let mut desc_hex = [0u8; HASH_SIZE * 2];
+
+    for i in 0..read_count(data, b"proposal_count") {
+        if let Some(desc) = entry(data, b"prop_", i, b"_desc").and_then(parse_u32) {
+            encode_hex(&legacy_id(desc), &mut desc_hex);
+            let mut key = [0u8; 32];
+            let klen = build_prop_key(b"prop_", i, b"_desc", &mut key);
+            store.set(&key[..klen], &desc_hex)?;
+        }
+    }
+    Ok(())
+}
+
+/// 4 → 5: proposals carry the `prop_N_supply` and `prop_N_quorum` they are
+/// judged against. Supply at creation was never recorded, so existing
+/// proposals freeze today's total voting power and quorum setting.
+fn v4_freeze_quorum(store: &mut Store) -> Result<(), i32> {
+    let snapshot = Store::load(store.as_bytes());
+    let data = snapshot.as_bytes();
+    let supply = get_total_voting_power(data);
+    let quorum = Settings::load(data).quorum(supply);
+    let mut key = [0u8; 32];
+    let mut value = [0u8; 20];
+
+    for i in 0..read_count(data, b"proposal_count") {
+        if entry(data, b"prop_", i, b"_quorum").is_some() {
+            continue;
+        }
+        for (suffix, amount) in [(b"_supply" as &[u8], supply), (b"_quorum", quorum)] {
+            let klen = build_prop_key(b"prop_", i, suffix, &mut key);
+            let vlen = format_u64(amount, &mut value);
+            store.set(&key[..klen], &value[..vlen])?;
+        }
+    }
+    Ok(())
+}
+
+/// 5 → 6: accounts keep `ckpt_*` vote histories. Past changes were never
+/// recorded, so every member and the total start at time 0 with today's
+/// votes.
+fn v5_seed_checkpoints(store: &mut Store) -> Result<(), i32> {
+    let snapshot = Store::load(store.as_bytes());
+    let data = snapshot.as_bytes();
+
+    for member in (0..read_count(data, b"member_count")).filter_map(|i| Member::load(data, i)) {
+        checkpoint_votes(store, &member.account, 0)?;
+    }
+    checkpoint_total_supply(store, 0)
+}
+
+/// 6 → 7: the roles field of `member_N` becomes a decimal bitmask so
+/// ROLE_CANCELLER fits. Every stored bitmask is a single digit, which
+/// reads the same in decimal; the bump keeps older builds from misreading
+/// two-digit roles.
+fn v6_decimal_roles(_store: &mut Store) -> Result<(), i32> {
+    Ok(())
+}
+
+/// 7 → 8: `hist_*` digests become 256-bit SHA-512Half hex. The 32-bit
+/// digests stored before cannot be recomputed here (their inputs are gone)
+/// and are widened with `legacy_id`.
+fn v7_widen_archive_digests(store: &mut Store) -> Result<(), i32> {
+    let snapshot = Store::load(store.as_bytes());
+    let mut value_buf = [0u8; 2 + HASH_SIZE * 2];
+
+    for (key, value) in snapshot.iter_prefix(b"hist_") {
+        let Some(digest) = value.get(2..).and_then(parse_u32) else { continue };
+        value_buf[..2].copy_from_slice(&value[..2]);
+        encode_hex(&legacy_id(digest), &mut value_buf[2..]);
+        store.set(key, &value_buf)?;
+    }
+    Ok(())
+}
+
+/// Whether `key` holds a proposal or operation ID.
+fn is_id_field(key: &[u8]) -> bool {
+    key == b"_exec"
+        || (key.starts_with(b"prop_") && key.ends_with(b"_id"))
+        || (key.starts_with(b"op_")
+            && (key.ends_with(b"_id") || key.ends_with(b"_prop") || key.ends_with(b"_predecessor")))
+}
+
+/// Rewrite "snap_42_<hex>" as "snap_<legacy_id(42) hex>_<hex>" (likewise
+/// `sigvote_` and `hist_`). Returns the new key length, or None if `key`
+/// carries no decimal ID.
+fn widen_id_key(key: &[u8], out: &mut [u8; 128]) -> Option<usize> {
+    let prefix = [b"snap_" as &[u8], b"sigvote_", b"hist_"]
+        .into_iter()
+        .find(|p| key.starts_with(p))?;
+    let rest = &key[prefix.len()..];
+    let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+    if digits == 0 || !matches!(rest.get(digits), None | Some(b'_')) {
+        return None;
+    }
+    let id = parse_u32(&rest[..digits])?;
+    let tail = &rest[digits..];
+
+    let end = prefix.len() + HASH_SIZE * 2 + tail.len();
+    if end > out.len() {
+        return None;
+    }
+    out[..prefix.len()].copy_from_slice(prefix);
+    encode_hex(&legacy_id(id), &mut out[prefix.len()..prefix.len() + HASH_SIZE * 2]);
+    out[prefix.len() + HASH_SIZE * 2..end].copy_from_slice(tail);
+    Some(end)
+}
+
+/// The raw value of `<prefix><index><suffix>`, e.g. `prop_3_state`.
+fn entry<'a>(data: &'a [u8], prefix: &[u8], index: u16, suffix: &[u8]) -> Option<&'a [u8]> {
+    let mut key = [0u8; 32];
+    let klen = build_prop_key(prefix, index, suffix, &mut key);
+    find_value(data, &key[..klen])
+}
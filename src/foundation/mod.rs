@@ -1,5 +1,11 @@
 pub mod config;
 pub mod data;
+pub mod codec;
 pub mod parse;
+pub mod abi;
+pub mod migrate;
+pub mod genesis;
+pub mod invariants;
+pub mod host;
 pub mod allocator;
 pub mod types;
@@ -20,7 +20,7 @@ pub fn parse_u32(data: &[u8]) -> Option<u32> {
     }
     let mut result: u32 = 0;
     for &b in data {
-        if b < b'0' || b > b'9' {
+        if !b.is_ascii_digit() {
             return None;
         }
         result = result.checked_mul(10)?.checked_add((b - b'0') as u32)?;
@@ -1,18 +1,25 @@
 //! Core types for the XRPL Token DAO.
 //!
 //! These mirror OpenZeppelin's Governor and TimelockController structs,
-//! adapted for XRPL's on-chain data format.
+//! adapted for XRPL's on-chain data format. Each type reads itself from
+//! the data blob with `load` and writes itself back through a `Store`
+//! with `store`, so modules never hand-parse `prop_N_*` style keys.
 
 use crate::foundation::config::*;
+use crate::foundation::data::{build_indexed_key, find_value, Store};
+use crate::foundation::parse::{parse_u32, parse_u8_digit, format_u32};
+use crate::foundation::abi::{decode_action, decode_hash};
+use crate::crypto::hex::{encode_hex, decode_hex};
+use crate::governance::governor::{build_prop_key, read_count, parse_u64, format_u64};
 
 /// A governance proposal. Mirrors Governor's ProposalCore struct.
 ///
 /// Stored in the escrow's contract data as key=value pairs:
-///   prop_0_id=<hash>;prop_0_proposer=<hex>;prop_0_start=<time>;...
+///   prop_0_id=<hex64>;prop_0_proposer=<hex>;prop_0_start=<time>;...
 #[derive(Clone, Copy)]
 pub struct Proposal {
-    /// Unique proposal ID (first 8 bytes of description hash)
-    pub id: u32,
+    /// Unique 256-bit proposal ID (see `crypto::hash::hash_proposal`)
+    pub id: [u8; HASH_SIZE],
     /// AccountID of the proposer
     pub proposer: [u8; ACCOUNT_ID_SIZE],
     /// Ledger close time when voting starts (after voting delay)
@@ -29,14 +36,20 @@ pub struct Proposal {
     pub abstain_votes: u64,
     /// Timelock execution timestamp (0 if not queued)
     pub eta: u32,
-    /// Description hash (first 4 bytes for compact storage)
-    pub description_hash: u32,
+    /// SHA-512Half of the description (`crypto::hash::hash_description`)
+    pub description_hash: [u8; HASH_SIZE],
+    /// Total voting power when the proposal was created
+    pub total_supply: u64,
+    /// For + abstain votes needed, fixed from `total_supply` at creation
+    pub quorum: u64,
+    /// Seconds a late quorum added to `vote_end` (0 if not extended)
+    pub vote_extension: u32,
 }
 
 impl Proposal {
     pub fn new() -> Self {
         Proposal {
-            id: 0,
+            id: [0u8; HASH_SIZE],
             proposer: [0u8; ACCOUNT_ID_SIZE],
             vote_start: 0,
             vote_end: 0,
@@ -45,7 +58,10 @@ impl Proposal {
             against_votes: 0,
             abstain_votes: 0,
             eta: 0,
-            description_hash: 0,
+            description_hash: [0u8; HASH_SIZE],
+            total_supply: 0,
+            quorum: 0,
+            vote_extension: 0,
         }
     }
 
@@ -54,11 +70,78 @@ impl Proposal {
         self.for_votes + self.against_votes + self.abstain_votes
     }
 
+    /// Check if for + abstain votes meet the frozen quorum.
+    /// Mirrors Governor._quorumReached()
+    pub fn quorum_reached(&self) -> bool {
+        self.for_votes.saturating_add(self.abstain_votes) >= self.quorum
+    }
+
     /// Check if the vote succeeded (more for than against)
     /// Mirrors Governor._voteSucceeded()
     pub fn vote_succeeded(&self) -> bool {
         self.for_votes > self.against_votes
     }
+
+    /// Load proposal `index`. Returns None if it has no `prop_N_id`.
+    pub fn load(data: &[u8], index: u16) -> Option<Self> {
+        let field = |suffix: &[u8]| indexed_field(data, b"prop_", index, suffix);
+
+        let mut proposal = Proposal::new();
+        proposal.id = field(b"_id").and_then(decode_hash)?;
+        if let Some(hex) = field(b"_proposer") {
+            decode_hex(hex, &mut proposal.proposer);
+        }
+        proposal.state = field(b"_state")
+            .and_then(parse_u8_digit)
+            .unwrap_or(PROPOSAL_STATE_PENDING);
+        proposal.vote_start = field(b"_start").and_then(parse_u32).unwrap_or(0);
+        proposal.vote_end = field(b"_end").and_then(parse_u32).unwrap_or(0);
+        proposal.for_votes = field(b"_for").and_then(parse_u64).unwrap_or(0);
+        proposal.against_votes = field(b"_against").and_then(parse_u64).unwrap_or(0);
+        proposal.abstain_votes = field(b"_abstain").and_then(parse_u64).unwrap_or(0);
+        proposal.eta = field(b"_eta").and_then(parse_u32).unwrap_or(0);
+        proposal.description_hash = field(b"_desc").and_then(decode_hash).unwrap_or([0u8; HASH_SIZE]);
+        proposal.total_supply = field(b"_supply").and_then(parse_u64).unwrap_or(0);
+        proposal.quorum = field(b"_quorum").and_then(parse_u64).unwrap_or(0);
+        proposal.vote_extension = field(b"_ext").and_then(parse_u32).unwrap_or(0);
+        Some(proposal)
+    }
+
+    /// Write the proposal as `prop_N_*` entries, updating in place.
+    /// `prop_N_eta` only exists once the proposal has been queued, and
+    /// `prop_N_ext` once its vote has been extended.
+    pub fn store(&self, store: &mut Store, index: u16) -> Result<(), i32> {
+        let mut hex_buf = [0u8; 40];
+        encode_hex(&self.proposer, &mut hex_buf);
+
+        put_hash(store, b"prop_", index, b"_id", &self.id)?;
+        put(store, b"prop_", index, b"_proposer", &hex_buf)?;
+        put(store, b"prop_", index, b"_state", &[b'0' + self.state])?;
+        put_u32(store, b"prop_", index, b"_start", self.vote_start)?;
+        put_u32(store, b"prop_", index, b"_end", self.vote_end)?;
+        put_u64(store, b"prop_", index, b"_for", self.for_votes)?;
+        put_u64(store, b"prop_", index, b"_against", self.against_votes)?;
+        put_u64(store, b"prop_", index, b"_abstain", self.abstain_votes)?;
+        put_hash(store, b"prop_", index, b"_desc", &self.description_hash)?;
+        put_u64(store, b"prop_", index, b"_supply", self.total_supply)?;
+        put_u64(store, b"prop_", index, b"_quorum", self.quorum)?;
+        for (suffix, value) in [(b"_eta" as &[u8], self.eta), (b"_ext", self.vote_extension)] {
+            if value != 0 {
+                put_u32(store, b"prop_", index, suffix, value)?;
+            } else {
+                let mut key_buf = [0u8; 48];
+                let klen = build_prop_key(b"prop_", index, suffix, &mut key_buf);
+                store.remove(&key_buf[..klen]);
+            }
+        }
+        Ok(())
+    }
+}
+
+impl Default for Proposal {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// A DAO member with voting power. Mirrors ERC20Votes balances.
@@ -68,7 +151,7 @@ pub struct Member {
     pub account: [u8; ACCOUNT_ID_SIZE],
     /// Voting power in drops (XRP balance snapshot)
     pub voting_power: u64,
-    /// Role bitmask (ROLE_PROPOSER | ROLE_EXECUTOR | ROLE_ADMIN)
+    /// Role bitmask (ROLE_PROPOSER | ROLE_EXECUTOR | ROLE_ADMIN | ROLE_CANCELLER)
     pub roles: u8,
 }
 
@@ -84,29 +167,101 @@ impl Member {
     pub fn has_role(&self, role: u8) -> bool {
         self.roles & role != 0
     }
+
+    /// Load `member_N`. Returns None if the entry is missing or malformed.
+    pub fn load(data: &[u8], index: u16) -> Option<Self> {
+        let mut key_buf = [0u8; 16];
+        let klen = build_indexed_key(b"member_", index, &mut key_buf);
+        find_value(data, &key_buf[..klen]).and_then(parse_member)
+    }
+
+    /// Find a member by account. Returns (index, member).
+    pub fn find(data: &[u8], account: &[u8; ACCOUNT_ID_SIZE]) -> Option<(u16, Self)> {
+        let member_count = read_count(data, b"member_count");
+        (0..member_count).find_map(|i| {
+            Member::load(data, i)
+                .filter(|m| m.account == *account)
+                .map(|m| (i, m))
+        })
+    }
+
+    /// Write `member_N=<hex40>:<power>:<roles>`.
+    pub fn store(&self, store: &mut Store, index: u16) -> Result<(), i32> {
+        let mut key_buf = [0u8; 16];
+        let klen = build_indexed_key(b"member_", index, &mut key_buf);
+
+        let mut val_buf = [0u8; 64];
+        encode_hex(&self.account, &mut val_buf[..40]);
+        val_buf[40] = b':';
+        let mut pos = 41 + format_u64(self.voting_power, &mut val_buf[41..]);
+        val_buf[pos] = b':';
+        pos += 1 + format_u32(self.roles as u32, &mut val_buf[pos + 1..]);
+        store.set(&key_buf[..klen], &val_buf[..pos])
+    }
+}
+
+impl Default for Member {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 /// A timelock operation. Mirrors TimelockController's operation.
 #[derive(Clone, Copy)]
 pub struct TimelockOp {
-    /// Operation ID (hash of content)
-    pub id: u32,
+    /// 256-bit operation ID (see `crypto::hash::hash_operation`)
+    pub id: [u8; HASH_SIZE],
     /// Linked proposal ID
-    pub proposal_id: u32,
+    pub proposal_id: [u8; HASH_SIZE],
     /// Timestamp when operation becomes executable
     pub ready_at: u32,
     /// Current state
     pub state: u8,
+    /// Operation ID that must be Done first (all zero = none)
+    pub predecessor: [u8; HASH_SIZE],
 }
 
 impl TimelockOp {
     pub fn new() -> Self {
         TimelockOp {
-            id: 0,
-            proposal_id: 0,
+            id: [0u8; HASH_SIZE],
+            proposal_id: [0u8; HASH_SIZE],
             ready_at: 0,
             state: OP_STATE_UNSET,
+            predecessor: [0u8; HASH_SIZE],
+        }
+    }
+
+    /// Load operation `index`. Returns None if it has no `op_N_id`.
+    pub fn load(data: &[u8], index: u16) -> Option<Self> {
+        let field = |suffix: &[u8]| indexed_field(data, b"op_", index, suffix);
+
+        let mut op = TimelockOp::new();
+        op.id = field(b"_id").and_then(decode_hash)?;
+        op.proposal_id = field(b"_prop").and_then(decode_hash).unwrap_or([0u8; HASH_SIZE]);
+        op.ready_at = field(b"_ready").and_then(parse_u32).unwrap_or(u32::MAX);
+        op.state = field(b"_state").and_then(parse_u8_digit).unwrap_or(OP_STATE_UNSET);
+        op.predecessor = field(b"_predecessor").and_then(decode_hash).unwrap_or([0u8; HASH_SIZE]);
+        Some(op)
+    }
+
+    /// Write the operation as `op_N_*` entries, updating in place.
+    /// `op_N_predecessor` is only written when there is one.
+    pub fn store(&self, store: &mut Store, index: u16) -> Result<(), i32> {
+        put_hash(store, b"op_", index, b"_id", &self.id)?;
+        put_hash(store, b"op_", index, b"_prop", &self.proposal_id)?;
+        put_u32(store, b"op_", index, b"_ready", self.ready_at)?;
+        put(store, b"op_", index, b"_state", &[b'0' + self.state])?;
+        if self.predecessor != [0u8; HASH_SIZE] {
+            put_hash(store, b"op_", index, b"_predecessor", &self.predecessor)?;
         }
+        Ok(())
+    }
+}
+
+impl Default for TimelockOp {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -116,9 +271,269 @@ pub struct VoteRecord {
     /// Voter AccountID
     pub voter: [u8; ACCOUNT_ID_SIZE],
     /// Proposal ID
-    pub proposal_id: u32,
+    pub proposal_id: [u8; HASH_SIZE],
     /// Vote type (VOTE_FOR, VOTE_AGAINST, VOTE_ABSTAIN)
     pub support: u8,
     /// Weight of the vote (voting power at snapshot)
     pub weight: u64,
 }
+
+impl VoteRecord {
+    /// Load `vote_P_N`. `proposal_id` is taken from `prop_P_id`.
+    pub fn load(data: &[u8], proposal_index: u16, vote_index: u16) -> Option<Self> {
+        let mut key_buf = [0u8; 32];
+        let klen = build_vote_key(proposal_index, vote_index, &mut key_buf);
+        let val = find_value(data, &key_buf[..klen])?;
+
+        // Value format: <voter_hex40>:<support>:<weight>
+        if val.len() < 44 || val[40] != b':' || val[42] != b':' {
+            return None;
+        }
+        let support = parse_u8_digit(&val[41..42]).filter(|&s| s <= VOTE_ABSTAIN)?;
+        let weight = parse_u64(&val[43..])?;
+
+        let mut voter = [0u8; ACCOUNT_ID_SIZE];
+        if !decode_hex(&val[..40], &mut voter) {
+            return None;
+        }
+        let proposal_id = indexed_field(data, b"prop_", proposal_index, b"_id")
+            .and_then(decode_hash)
+            .unwrap_or([0u8; HASH_SIZE]);
+
+        Some(VoteRecord { voter, proposal_id, support, weight })
+    }
+
+    /// Write `vote_P_N=<voter_hex40>:<support>:<weight>`.
+    pub fn store(&self, store: &mut Store, proposal_index: u16, vote_index: u16) -> Result<(), i32> {
+        let mut key_buf = [0u8; 32];
+        let klen = build_vote_key(proposal_index, vote_index, &mut key_buf);
+
+        let mut val_buf = [0u8; 64];
+        encode_hex(&self.voter, &mut val_buf[..40]);
+        val_buf[40] = b':';
+        val_buf[41] = b'0' + self.support;
+        val_buf[42] = b':';
+        let wlen = format_u64(self.weight, &mut val_buf[43..]);
+        store.set(&key_buf[..klen], &val_buf[..43 + wlen])
+    }
+}
+
+/// One call a proposal makes when it executes. Mirrors one entry of OZ's
+/// `targets` / `values` / `calldatas`.
+#[derive(Clone, Copy)]
+pub struct Action {
+    /// Destination AccountID
+    pub target: [u8; ACCOUNT_ID_SIZE],
+    /// XRP amount in drops
+    pub amount: u64,
+    /// XRPL transaction type code (Payment = 0, ...)
+    pub tx_type: u16,
+    /// Type-specific payload bytes
+    pub payload: [u8; MAX_ACTION_PAYLOAD],
+    /// Bytes of `payload` in use
+    pub payload_len: u8,
+}
+
+impl Action {
+    pub fn new() -> Self {
+        Action {
+            target: [0u8; ACCOUNT_ID_SIZE],
+            amount: 0,
+            tx_type: 0,
+            payload: [0u8; MAX_ACTION_PAYLOAD],
+            payload_len: 0,
+        }
+    }
+
+    /// The payload bytes in use.
+    pub fn payload(&self) -> &[u8] {
+        &self.payload[..self.payload_len as usize]
+    }
+
+    /// Write `<target hex>:<drops>:<tx type>:<payload hex>`, the same text
+    /// `abi::decode_action` reads. Returns bytes written.
+    pub fn encode(&self, out: &mut [u8; ACTION_TEXT_SIZE]) -> usize {
+        encode_hex(&self.target, &mut out[..40]);
+        let mut pos = 40;
+        out[pos] = b':';
+        pos += 1;
+        pos += format_u64(self.amount, &mut out[pos..]);
+        out[pos] = b':';
+        pos += 1;
+        pos += format_u32(self.tx_type as u32, &mut out[pos..]);
+        out[pos] = b':';
+        pos += 1;
+        let hex_len = self.payload().len() * 2;
+        encode_hex(self.payload(), &mut out[pos..pos + hex_len]);
+        pos + hex_len
+    }
+}
+
+/// Longest stored action: hex target, u64 drops, u16 type, hex payload
+/// and three separators.
+pub const ACTION_TEXT_SIZE: usize = 2 * ACCOUNT_ID_SIZE + 20 + 5 + 2 * MAX_ACTION_PAYLOAD + 3;
+
+impl Default for Action {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A proposal's actions, in execution order (at most MAX_BATCH_OPS).
+#[derive(Clone, Copy)]
+pub struct Actions {
+    pub items: [Action; MAX_BATCH_OPS],
+    pub count: u8,
+}
+
+impl Actions {
+    pub fn new() -> Self {
+        Actions { items: [Action::new(); MAX_BATCH_OPS], count: 0 }
+    }
+
+    /// The actions in use.
+    pub fn as_slice(&self) -> &[Action] {
+        &self.items[..self.count as usize]
+    }
+
+    /// Append an action. ERR_BAD_CONFIG once MAX_BATCH_OPS are held.
+    pub fn push(&mut self, action: Action) -> Result<(), i32> {
+        let slot = self.items.get_mut(self.count as usize).ok_or(ERR_BAD_CONFIG)?;
+        *slot = action;
+        self.count += 1;
+        Ok(())
+    }
+
+    /// Load the actions of proposal `index` from `prop_N_act_count` and
+    /// `prop_N_act_K`. A proposal without them has no actions; None if an
+    /// entry is missing or malformed.
+    pub fn load(data: &[u8], index: u16) -> Option<Self> {
+        let mut actions = Actions::new();
+        let count = indexed_field(data, b"prop_", index, b"_act_count")
+            .map_or(Some(0), parse_u32)?;
+        for k in 0..count {
+            let mut suffix = [0u8; 16];
+            let slen = action_suffix(k as u16, &mut suffix);
+            let text = indexed_field(data, b"prop_", index, &suffix[..slen])?;
+            actions.push(decode_action(text)?).ok()?;
+        }
+        Some(actions)
+    }
+
+    /// Write the actions as `prop_N_act_*` entries. Nothing is written for
+    /// an empty list.
+    pub fn store(&self, store: &mut Store, index: u16) -> Result<(), i32> {
+        if self.count == 0 {
+            return Ok(());
+        }
+        put_u32(store, b"prop_", index, b"_act_count", self.count as u32)?;
+        for (k, action) in self.as_slice().iter().enumerate() {
+            let mut suffix = [0u8; 16];
+            let slen = action_suffix(k as u16, &mut suffix);
+            let mut text = [0u8; ACTION_TEXT_SIZE];
+            let tlen = action.encode(&mut text);
+            put(store, b"prop_", index, &suffix[..slen], &text[..tlen])?;
+        }
+        Ok(())
+    }
+
+    /// Outcome of action `k` of proposal `index`, recorded by `execute`:
+    /// SUCCESS, or the host's negative error code. None until executed.
+    pub fn result(data: &[u8], index: u16, k: u16) -> Option<i32> {
+        let mut suffix = [0u8; 16];
+        let slen = result_suffix(k, &mut suffix);
+        let text = indexed_field(data, b"prop_", index, &suffix[..slen])?;
+        match text.split_first() {
+            Some((b'-', digits)) => parse_u32(digits).map(|v| -(v as i32)),
+            _ => parse_u32(text).map(|v| v as i32),
+        }
+    }
+
+    /// Record the outcome of action `k` of proposal `index` as `prop_N_res_K`.
+    pub fn store_result(store: &mut Store, index: u16, k: u16, code: i32) -> Result<(), i32> {
+        let mut suffix = [0u8; 16];
+        let slen = result_suffix(k, &mut suffix);
+        let mut val_buf = [0u8; 11];
+        let mut vlen = 0;
+        if code < 0 {
+            val_buf[0] = b'-';
+            vlen = 1;
+        }
+        vlen += format_u32(code.unsigned_abs(), &mut val_buf[vlen..]);
+        put(store, b"prop_", index, &suffix[..slen], &val_buf[..vlen])
+    }
+}
+
+impl Default for Actions {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+// ——— Internal helpers ———
+
+/// Suffix "_act_<k>" of a proposal's k-th action key.
+fn action_suffix(k: u16, out: &mut [u8; 16]) -> usize {
+    out[..5].copy_from_slice(b"_act_");
+    5 + format_u32(k as u32, &mut out[5..])
+}
+
+/// Suffix "_res_<k>" of the key holding the k-th action's outcome.
+fn result_suffix(k: u16, out: &mut [u8; 16]) -> usize {
+    out[..5].copy_from_slice(b"_res_");
+    5 + format_u32(k as u32, &mut out[5..])
+}
+
+/// Look up "<prefix><index><suffix>", e.g. "prop_3_state".
+fn indexed_field<'a>(data: &'a [u8], prefix: &[u8], index: u16, suffix: &[u8]) -> Option<&'a [u8]> {
+    let mut key_buf = [0u8; 48];
+    let klen = build_prop_key(prefix, index, suffix, &mut key_buf);
+    find_value(data, &key_buf[..klen])
+}
+
+fn put(store: &mut Store, prefix: &[u8], index: u16, suffix: &[u8], value: &[u8]) -> Result<(), i32> {
+    let mut key_buf = [0u8; 48];
+    let klen = build_prop_key(prefix, index, suffix, &mut key_buf);
+    store.set(&key_buf[..klen], value)
+}
+
+fn put_u32(store: &mut Store, prefix: &[u8], index: u16, suffix: &[u8], value: u32) -> Result<(), i32> {
+    let mut val_buf = [0u8; 10];
+    let vlen = format_u32(value, &mut val_buf);
+    put(store, prefix, index, suffix, &val_buf[..vlen])
+}
+
+fn put_hash(store: &mut Store, prefix: &[u8], index: u16, suffix: &[u8], hash: &[u8; HASH_SIZE]) -> Result<(), i32> {
+    let mut hex_buf = [0u8; HASH_SIZE * 2];
+    encode_hex(hash, &mut hex_buf);
+    put(store, prefix, index, suffix, &hex_buf)
+}
+
+fn put_u64(store: &mut Store, prefix: &[u8], index: u16, suffix: &[u8], value: u64) -> Result<(), i32> {
+    let mut val_buf = [0u8; 20];
+    let vlen = format_u64(value, &mut val_buf);
+    put(store, prefix, index, suffix, &val_buf[..vlen])
+}
+
+/// Build "vote_P_N".
+fn build_vote_key(proposal_index: u16, vote_index: u16, out: &mut [u8]) -> usize {
+    let plen = build_prop_key(b"vote_", proposal_index, b"_", out);
+    plen + format_u32(vote_index as u32, &mut out[plen..])
+}
+
+/// Parse "hex40:power:roles".
+fn parse_member(val: &[u8]) -> Option<Member> {
+    if val.len() < 44 || val[40] != b':' {
+        return None;
+    }
+    let rest = &val[41..];
+    let colon = rest.iter().position(|&b| b == b':')?;
+    let voting_power = parse_u64(&rest[..colon])?;
+    let roles = u8::try_from(parse_u32(&rest[colon + 1..])?).ok()?;
+
+    let mut account = [0u8; ACCOUNT_ID_SIZE];
+    if !decode_hex(&val[..40], &mut account) {
+        return None;
+    }
+    Some(Member { account, voting_power, roles })
+}
@@ -11,131 +11,88 @@
 //!
 //! ## Security Fixes Applied
 //!
-//! - Cryptographic proposal ID via FNV-1a hash (not weak XOR)
+//! - 256-bit proposal ID via SHA-512Half (not weak XOR)
 //! - Checked arithmetic on all vote tallies (overflow protection)
-//! - Multi-digit index keys (supports 0-99, not just 0-9)
+//! - Multi-digit index keys (u16 indices; capacity set by `LIMITS`)
 
 use crate::foundation::config::*;
 use crate::foundation::data::*;
 use crate::foundation::parse::*;
+use crate::foundation::types::{Actions, Proposal};
+use crate::governance::settings::{self, Settings};
+use crate::governance::votes;
+use crate::timelock::controller;
+use crate::crypto::hash::{hash_description, hash_proposal};
 use crate::crypto::hex::encode_hex;
-use crate::crypto::hash::hash_proposal;
 
 /// Create a new proposal. Mirrors Governor.propose().
 ///
 /// Requirements:
-///   - Caller must have voting power >= PROPOSAL_THRESHOLD
-///   - Proposal count must be < MAX_PROPOSALS
+///   - Caller must have voting power >= the proposal threshold setting
+///   - Proposal count must be < LIMITS.max_proposals
+///   - Every `ACTION_SET_SETTING` action must name a known setting and an
+///     in-bounds value (ERR_BAD_CONFIG)
 ///
-/// Proposal ID is a cryptographic hash of (proposer, description, time, nonce),
-/// mirroring OZ's `keccak256(abi.encode(targets, values, calldatas, descriptionHash))`.
+/// `actions` are stored with the proposal as `prop_N_act_*` and run when it
+/// executes. Proposal ID is the SHA-512Half of (proposer, description,
+/// actions, time, nonce), mirroring OZ's
+/// `keccak256(abi.encode(targets, values, calldatas, descriptionHash))`.
+/// An ID already in use fails with ERR_KEY_EXISTS.
 pub fn propose(
     data: &[u8],
     data_len: usize,
     proposer: &[u8; ACCOUNT_ID_SIZE],
-    description_hash: u32,
+    description_hash: &[u8; HASH_SIZE],
+    actions: &Actions,
     current_time: u32,
     proposer_votes: u64,
-) -> Result<([u8; 4096], usize, u32), i32> {
+) -> Result<([u8; DATA_CAPACITY], usize, [u8; HASH_SIZE]), i32> {
+    let settings = Settings::load(data);
+
     // Check proposal threshold
-    if proposer_votes < PROPOSAL_THRESHOLD {
+    if proposer_votes < settings.proposal_threshold {
         return Err(ERR_BELOW_THRESHOLD);
     }
 
+    // A settings change that cannot apply would leave the proposal stuck
+    for action in actions.as_slice() {
+        if action.tx_type == ACTION_SET_SETTING {
+            settings::check_action(action)?;
+        }
+    }
+
     // Count existing proposals
     let prop_count = read_count(data, b"proposal_count");
 
-    if prop_count as usize >= MAX_PROPOSALS {
+    if prop_count >= LIMITS.max_proposals {
         return Err(ERR_MAX_PROPOSALS);
     }
 
     // Generate cryptographic proposal ID bound to all inputs
-    let proposal_id = hash_proposal(proposer, description_hash, current_time, prop_count);
-
-    // Build new data with proposal added
-    let mut new_data = [0u8; 4096];
-    let mut pos = 0;
-
-    // Copy existing entries
-    let mut scan = 0;
-    while scan < data_len {
-        let entry_end = data[scan..data_len].iter()
-            .position(|&b| b == b';')
-            .map(|p| scan + p)
-            .unwrap_or(data_len);
-
-        let entry = &data[scan..entry_end];
-
-        // Skip proposal_count (we'll rewrite it)
-        let skip = if let Some(eq) = entry.iter().position(|&b| b == b'=') {
-            &entry[..eq] == b"proposal_count"
-        } else { false };
-
-        if !skip && !entry.is_empty() {
-            if pos > 0 { pos = write_separator(&mut new_data, pos); }
-            let elen = entry.len();
-            if pos + elen <= new_data.len() {
-                new_data[pos..pos + elen].copy_from_slice(entry);
-                pos += elen;
-            }
-        }
-        scan = entry_end + 1;
+    let proposal_id = hash_proposal(
+        proposer, description_hash, actions.as_slice(), current_time, prop_count,
+    );
+    if find_proposal_by_id(data, &proposal_id).is_ok() {
+        return Err(ERR_KEY_EXISTS);
     }
 
-    let idx = prop_count;
-    let mut key_buf = [0u8; 48];
-    let mut val_buf = [0u8; 64];
-
-    // proposal_count
-    if pos > 0 { pos = write_separator(&mut new_data, pos); }
-    let count_len = format_u8(idx + 1, &mut val_buf);
-    pos = write_entry(&mut new_data, pos, b"proposal_count", &val_buf[..count_len]);
-
-    // prop_N_id=<id>
-    if pos > 0 { pos = write_separator(&mut new_data, pos); }
-    let key_len = build_prop_key(b"prop_", idx, b"_id", &mut key_buf);
-    let val_len = format_u32(proposal_id, &mut val_buf);
-    pos = write_entry(&mut new_data, pos, &key_buf[..key_len], &val_buf[..val_len]);
-
-    // prop_N_proposer=<hex>
-    if pos > 0 { pos = write_separator(&mut new_data, pos); }
-    let key_len = build_prop_key(b"prop_", idx, b"_proposer", &mut key_buf);
-    let mut hex_buf = [0u8; 40];
-    encode_hex(proposer, &mut hex_buf);
-    pos = write_entry(&mut new_data, pos, &key_buf[..key_len], &hex_buf);
-
-    // prop_N_state=0 (Pending)
-    if pos > 0 { pos = write_separator(&mut new_data, pos); }
-    let key_len = build_prop_key(b"prop_", idx, b"_state", &mut key_buf);
-    pos = write_entry(&mut new_data, pos, &key_buf[..key_len], b"0");
-
-    // prop_N_start=<time + voting_delay>
-    if pos > 0 { pos = write_separator(&mut new_data, pos); }
-    let key_len = build_prop_key(b"prop_", idx, b"_start", &mut key_buf);
-    let start_time = current_time + VOTING_DELAY;
-    let val_len = format_u32(start_time, &mut val_buf);
-    pos = write_entry(&mut new_data, pos, &key_buf[..key_len], &val_buf[..val_len]);
-
-    // prop_N_end=<start + voting_period>
-    if pos > 0 { pos = write_separator(&mut new_data, pos); }
-    let key_len = build_prop_key(b"prop_", idx, b"_end", &mut key_buf);
-    let end_time = start_time + VOTING_PERIOD;
-    let val_len = format_u32(end_time, &mut val_buf);
-    pos = write_entry(&mut new_data, pos, &key_buf[..key_len], &val_buf[..val_len]);
-
-    // prop_N_for=0; prop_N_against=0; prop_N_abstain=0
-    for suffix in [b"_for" as &[u8], b"_against", b"_abstain"] {
-        if pos > 0 { pos = write_separator(&mut new_data, pos); }
-        let key_len = build_prop_key(b"prop_", idx, suffix, &mut key_buf);
-        pos = write_entry(&mut new_data, pos, &key_buf[..key_len], b"0");
-    }
-
-    // prop_N_desc=<hash>
-    if pos > 0 { pos = write_separator(&mut new_data, pos); }
-    let key_len = build_prop_key(b"prop_", idx, b"_desc", &mut key_buf);
-    let val_len = format_u32(description_hash, &mut val_buf);
-    pos = write_entry(&mut new_data, pos, &key_buf[..key_len], &val_buf[..val_len]);
-
+    let vote_start = current_time.checked_add(settings.voting_delay).ok_or(ERR_OVERFLOW)?;
+    let mut proposal = Proposal::new();
+    proposal.id = proposal_id;
+    proposal.proposer = *proposer;
+    proposal.vote_start = vote_start;
+    proposal.vote_end = vote_start.checked_add(settings.voting_period).ok_or(ERR_OVERFLOW)?;
+    proposal.description_hash = *description_hash;
+    // Fixed now so later membership or cfg_quorum changes cannot flip the outcome
+    proposal.total_supply = votes::get_total_voting_power(data);
+    proposal.quorum = settings.quorum(proposal.total_supply);
+
+    let mut store = Store::load(&data[..data_len]);
+    write_count(&mut store, b"proposal_count", prop_count + 1)?;
+    proposal.store(&mut store, prop_count)?;
+    actions.store(&mut store, prop_count)?;
+
+    let (new_data, pos) = store.commit();
     Ok((new_data, pos, proposal_id))
 }
 
@@ -147,19 +104,16 @@ pub fn propose(
 ///   - After vote_end, quorum not met or defeated: Defeated (3)
 ///   - After vote_end, succeeded: Succeeded (4)
 ///   - Explicitly set states (Canceled, Queued, Executed) override
+///
+/// Quorum is the `prop_N_quorum` frozen at creation, as OZ evaluates it
+/// against `getPastTotalSupply(snapshot)`.
 pub fn get_proposal_state(
     data: &[u8],
-    proposal_index: u8,
+    proposal_index: u16,
     current_time: u32,
-    total_voting_power: u64,
 ) -> u8 {
-    let mut key_buf = [0u8; 48];
-
-    // Read stored state
-    let key_len = build_prop_key(b"prop_", proposal_index, b"_state", &mut key_buf);
-    let stored_state = find_value(data, &key_buf[..key_len])
-        .and_then(parse_u8_digit)
-        .unwrap_or(PROPOSAL_STATE_PENDING);
+    let proposal = Proposal::load(data, proposal_index).unwrap_or_default();
+    let stored_state = proposal.state;
 
     // If explicitly canceled, queued, or executed, return as-is
     if stored_state == PROPOSAL_STATE_CANCELED
@@ -170,51 +124,22 @@ pub fn get_proposal_state(
         return stored_state;
     }
 
-    // Read timing
-    let key_len = build_prop_key(b"prop_", proposal_index, b"_start", &mut key_buf);
-    let vote_start = find_value(data, &key_buf[..key_len])
-        .and_then(|v| parse_u32(v))
-        .unwrap_or(0);
-
-    let key_len = build_prop_key(b"prop_", proposal_index, b"_end", &mut key_buf);
-    let vote_end = find_value(data, &key_buf[..key_len])
-        .and_then(|v| parse_u32(v))
-        .unwrap_or(0);
-
-    if current_time < vote_start {
+    if current_time < proposal.vote_start {
         return PROPOSAL_STATE_PENDING;
     }
 
-    if current_time <= vote_end {
+    if current_time <= proposal.vote_end {
         return PROPOSAL_STATE_ACTIVE;
     }
 
-    // Voting ended — check results using checked arithmetic
-    let key_len = build_prop_key(b"prop_", proposal_index, b"_for", &mut key_buf);
-    let for_votes = find_value(data, &key_buf[..key_len])
-        .and_then(|v| parse_u64(v))
-        .unwrap_or(0);
-
-    let key_len = build_prop_key(b"prop_", proposal_index, b"_against", &mut key_buf);
-    let against_votes = find_value(data, &key_buf[..key_len])
-        .and_then(|v| parse_u64(v))
-        .unwrap_or(0);
-
-    let key_len = build_prop_key(b"prop_", proposal_index, b"_abstain", &mut key_buf);
-    let abstain_votes = find_value(data, &key_buf[..key_len])
-        .and_then(|v| parse_u64(v))
-        .unwrap_or(0);
+    // Voting ended — quorum: for + abstain must meet the frozen threshold (checked)
+    let quorum_votes = proposal.for_votes.saturating_add(proposal.abstain_votes);
 
-    let quorum_required = (total_voting_power / 100).saturating_mul(QUORUM_PERCENTAGE as u64);
-
-    // Quorum: for + abstain must meet threshold (checked)
-    let quorum_votes = for_votes.saturating_add(abstain_votes);
-
-    if quorum_votes < quorum_required {
+    if quorum_votes < proposal.quorum {
         return PROPOSAL_STATE_DEFEATED;
     }
 
-    if for_votes > against_votes {
+    if proposal.vote_succeeded() {
         PROPOSAL_STATE_SUCCEEDED
     } else {
         PROPOSAL_STATE_DEFEATED
@@ -222,56 +147,86 @@ pub fn get_proposal_state(
 }
 
 /// Cancel a proposal. Mirrors Governor._cancel().
-/// Only the proposer can cancel, and only while Pending.
+///
+/// The proposer can cancel while Pending. A ROLE_CANCELLER account, like
+/// TimelockController's CANCELLER_ROLE, can cancel any proposal up to and
+/// including Queued; a queued proposal's timelock operation is canceled
+/// in the same write.
 pub fn cancel_proposal(
     data: &[u8],
     data_len: usize,
-    proposal_index: u8,
+    proposal_index: u16,
     caller: &[u8; ACCOUNT_ID_SIZE],
     current_time: u32,
-    total_voting_power: u64,
-) -> Result<([u8; 4096], usize), i32> {
-    let mut key_buf = [0u8; 48];
-
-    // Verify caller is the proposer
-    let key_len = build_prop_key(b"prop_", proposal_index, b"_proposer", &mut key_buf);
-    let stored_proposer = find_value(data, &key_buf[..key_len])
-        .ok_or(ERR_PROPOSAL_NOT_FOUND)?;
-
-    let mut caller_hex = [0u8; 40];
-    encode_hex(caller, &mut caller_hex);
-    if stored_proposer != &caller_hex[..] {
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
+    let mut proposal = Proposal::load(data, proposal_index).ok_or(ERR_PROPOSAL_NOT_FOUND)?;
+    let state = get_proposal_state(data, proposal_index, current_time);
+
+    let cancellable = if votes::has_role(data, caller, ROLE_CANCELLER) {
+        matches!(
+            state,
+            PROPOSAL_STATE_PENDING | PROPOSAL_STATE_ACTIVE | PROPOSAL_STATE_SUCCEEDED | PROPOSAL_STATE_QUEUED
+        )
+    } else if proposal.proposer == *caller {
+        state == PROPOSAL_STATE_PENDING
+    } else {
         return Err(ERR_NOT_PROPOSER);
+    };
+    if !cancellable {
+        return Err(ERR_PROPOSAL_NOT_ACTIVE);
     }
 
-    // Check proposal is still Pending
-    let state = get_proposal_state(data, proposal_index, current_time, total_voting_power);
-    if state != PROPOSAL_STATE_PENDING {
-        return Err(ERR_PROPOSAL_NOT_ACTIVE);
+    let mut store = Store::load(&data[..data_len]);
+    if state == PROPOSAL_STATE_QUEUED {
+        let op_index = controller::find_operation_by_proposal(data, &proposal.id)?;
+        controller::cancel_in(data, &mut store, op_index, current_time)?;
     }
+    proposal.state = PROPOSAL_STATE_CANCELED;
+    proposal.store(&mut store, proposal_index)?;
+    Ok(store.commit())
+}
 
-    // Update state to Canceled
-    update_proposal_field(data, data_len, proposal_index, b"_state", b"2")
+/// Mark a proposal Queued once its timelock operation is scheduled.
+/// Mirrors GovernorTimelockControl._queueOperations(): `eta` records when
+/// the operation becomes executable.
+pub fn mark_queued(
+    data: &[u8],
+    data_len: usize,
+    proposal_index: u16,
+    eta: u32,
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
+    let mut proposal = Proposal::load(data, proposal_index).ok_or(ERR_PROPOSAL_NOT_FOUND)?;
+    proposal.state = PROPOSAL_STATE_QUEUED;
+    proposal.eta = eta;
+
+    let mut store = Store::load(&data[..data_len]);
+    proposal.store(&mut store, proposal_index)?;
+    Ok(store.commit())
 }
 
 /// Find a proposal index by its ID. Returns the index or error.
-pub fn find_proposal_by_id(data: &[u8], proposal_id: u32) -> Result<u8, i32> {
+pub fn find_proposal_by_id(data: &[u8], proposal_id: &[u8; HASH_SIZE]) -> Result<u16, i32> {
     let prop_count = read_count(data, b"proposal_count");
 
-    let mut key_buf = [0u8; 48];
-    let mut id_buf = [0u8; 10];
-    let id_len = format_u32(proposal_id, &mut id_buf);
-
-    for i in 0..prop_count {
-        let key_len = build_prop_key(b"prop_", i, b"_id", &mut key_buf);
-        if let Some(stored_id) = find_value(data, &key_buf[..key_len]) {
-            if stored_id == &id_buf[..id_len] {
-                return Ok(i);
-            }
-        }
-    }
+    (0..prop_count)
+        .find(|&i| Proposal::load(data, i).is_some_and(|p| p.id == *proposal_id))
+        .ok_or(ERR_PROPOSAL_NOT_FOUND)
+}
 
-    Err(ERR_PROPOSAL_NOT_FOUND)
+/// Check `description` against the hash stored with a proposal, so voters
+/// can confirm the text they read is the text that was proposed.
+/// Fails with ERR_DESCRIPTION_MISMATCH if it differs.
+pub fn verify_description(
+    data: &[u8],
+    proposal_id: &[u8; HASH_SIZE],
+    description: &[u8],
+) -> Result<(), i32> {
+    let index = find_proposal_by_id(data, proposal_id)?;
+    let proposal = Proposal::load(data, index).ok_or(ERR_PROPOSAL_NOT_FOUND)?;
+    if hash_description(description) != proposal.description_hash {
+        return Err(ERR_DESCRIPTION_MISMATCH);
+    }
+    Ok(())
 }
 
 // ═══════════════════════════════════════════════════════════════════════
@@ -289,63 +244,51 @@ pub fn set_lock(
     data: &[u8],
     data_len: usize,
     locked: bool,
-) -> Result<([u8; 4096], usize), i32> {
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
     let lock_val = if locked { b"1" as &[u8] } else { b"0" };
 
-    let mut new_data = [0u8; 4096];
-    let mut pos = 0;
-    let mut scan = 0;
-    let mut found = false;
-
-    while scan < data_len {
-        let entry_end = data[scan..data_len].iter()
-            .position(|&b| b == b';')
-            .map(|p| scan + p)
-            .unwrap_or(data_len);
-
-        let entry = &data[scan..entry_end];
-
-        let is_target = if let Some(eq) = entry.iter().position(|&b| b == b'=') {
-            &entry[..eq] == b"_lock"
-        } else { false };
-
-        if is_target {
-            if pos > 0 { pos = write_separator(&mut new_data, pos); }
-            pos = write_entry(&mut new_data, pos, b"_lock", lock_val);
-            found = true;
-        } else if !entry.is_empty() {
-            if pos > 0 { pos = write_separator(&mut new_data, pos); }
-            let elen = entry.len();
-            if pos + elen <= new_data.len() {
-                new_data[pos..pos + elen].copy_from_slice(entry);
-                pos += elen;
-            }
-        }
-
-        scan = entry_end + 1;
-    }
+    let mut store = Store::load(&data[..data_len]);
+    store.set(b"_lock", lock_val)?;
+    Ok(store.commit())
+}
 
-    if !found {
-        if pos > 0 { pos = write_separator(&mut new_data, pos); }
-        pos = write_entry(&mut new_data, pos, b"_lock", lock_val);
+/// Enter or leave the execution of a proposal: holds the reentrancy lock
+/// and records the proposal in `_exec`, which `settings::only_governance`
+/// requires. `None` releases both.
+pub fn set_executing(
+    data: &[u8],
+    data_len: usize,
+    proposal_id: Option<&[u8; HASH_SIZE]>,
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
+    let mut store = Store::load(&data[..data_len]);
+    match proposal_id {
+        Some(id) => {
+            let mut id_hex = [0u8; HASH_SIZE * 2];
+            encode_hex(id, &mut id_hex);
+            store.set(b"_lock", b"1")?;
+            store.set(b"_exec", &id_hex)?;
+        }
+        None => {
+            store.set(b"_lock", b"0")?;
+            store.remove(b"_exec");
+        }
     }
-
-    Ok((new_data, pos))
+    Ok(store.commit())
 }
 
 // ═══════════════════════════════════════════════════════════════════════
 // Internal helpers
 // ═══════════════════════════════════════════════════════════════════════
 
-/// Build a composite key like "prop_0_state", "prop_12_for".
-/// Supports multi-digit indices (0-99), fixing the single-digit limitation.
-pub fn build_prop_key(prefix: &[u8], index: u8, suffix: &[u8], out: &mut [u8]) -> usize {
+/// Build a composite key like "prop_0_state", "prop_1234_for".
+/// Supports the full u16 index range.
+pub fn build_prop_key(prefix: &[u8], index: u16, suffix: &[u8], out: &mut [u8]) -> usize {
     let mut pos = prefix.len();
     if pos > out.len() { return 0; }
     out[..pos].copy_from_slice(prefix);
 
-    // Write index as 1-3 digit ASCII
-    let idx_len = format_u8(index, &mut out[pos..]);
+    // Write index as 1-5 digit ASCII
+    let idx_len = format_u32(index as u32, &mut out[pos..]);
     pos += idx_len;
 
     let end = pos + suffix.len();
@@ -374,71 +317,39 @@ pub fn format_u8(value: u8, out: &mut [u8]) -> usize {
     }
 }
 
-/// Read a count field (supports multi-digit values 0-99).
-pub fn read_count(data: &[u8], key: &[u8]) -> u8 {
+/// Read a count field (0-65535).
+pub fn read_count(data: &[u8], key: &[u8]) -> u16 {
     find_value(data, key)
-        .and_then(|v| {
-            if v.is_empty() { return None; }
-            let mut result: u8 = 0;
-            for &b in v {
-                if b < b'0' || b > b'9' { return None; }
-                result = result.checked_mul(10)?.checked_add(b - b'0')?;
-            }
-            Some(result)
-        })
+        .and_then(parse_u32)
+        .and_then(|v| u16::try_from(v).ok())
         .unwrap_or(0)
 }
 
+/// Write a count field.
+pub fn write_count(store: &mut Store, key: &[u8], count: u16) -> Result<(), i32> {
+    let mut count_buf = [0u8; 5];
+    let count_len = format_u32(count as u32, &mut count_buf);
+    store.set(key, &count_buf[..count_len])
+}
+
 /// Update a single field on a proposal in the data store.
 pub fn update_proposal_field(
     data: &[u8],
     data_len: usize,
-    proposal_index: u8,
+    proposal_index: u16,
     field_suffix: &[u8],
     new_value: &[u8],
-) -> Result<([u8; 4096], usize), i32> {
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
     let mut key_buf = [0u8; 48];
     let key_len = build_prop_key(b"prop_", proposal_index, field_suffix, &mut key_buf);
     let target_key = &key_buf[..key_len];
 
-    let mut new_data = [0u8; 4096];
-    let mut pos = 0;
-    let mut scan = 0;
-    let mut found = false;
-
-    while scan < data_len {
-        let entry_end = data[scan..data_len].iter()
-            .position(|&b| b == b';')
-            .map(|p| scan + p)
-            .unwrap_or(data_len);
-
-        let entry = &data[scan..entry_end];
-
-        let is_target = if let Some(eq) = entry.iter().position(|&b| b == b'=') {
-            &entry[..eq] == target_key
-        } else { false };
-
-        if is_target {
-            if pos > 0 { pos = write_separator(&mut new_data, pos); }
-            pos = write_entry(&mut new_data, pos, target_key, new_value);
-            found = true;
-        } else if !entry.is_empty() {
-            if pos > 0 { pos = write_separator(&mut new_data, pos); }
-            let elen = entry.len();
-            if pos + elen <= new_data.len() {
-                new_data[pos..pos + elen].copy_from_slice(entry);
-                pos += elen;
-            }
-        }
-
-        scan = entry_end + 1;
-    }
-
-    if !found {
+    let mut store = Store::load(&data[..data_len]);
+    if store.get(target_key).is_none() {
         return Err(ERR_PROPOSAL_NOT_FOUND);
     }
-
-    Ok((new_data, pos))
+    store.set(target_key, new_value)?;
+    Ok(store.commit())
 }
 
 /// Parse a u64 from ASCII decimal bytes.
@@ -446,7 +357,7 @@ pub fn parse_u64(data: &[u8]) -> Option<u64> {
     if data.is_empty() { return None; }
     let mut result: u64 = 0;
     for &b in data {
-        if b < b'0' || b > b'9' { return None; }
+        if !b.is_ascii_digit() { return None; }
         result = result.checked_mul(10)?.checked_add((b - b'0') as u64)?;
     }
     Some(result)
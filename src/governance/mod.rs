@@ -2,3 +2,5 @@ pub mod governor;
 pub mod counting;
 pub mod votes;
 pub mod signatures;
+pub mod prune;
+pub mod settings;
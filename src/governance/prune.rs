@@ -0,0 +1,309 @@
+//! Pruning and archival of finished proposals.
+//!
+//! Executed, Defeated, Canceled and Expired proposals are removed together
+//! with their vote records (`vote_P_*`), signature intents (`sigvote_<id>_*`),
+//! snapshots (`snap_<id>_*`) and linked timelock operation. Surviving
+//! proposals and operations are renumbered so indices stay dense and
+//! `proposal_count` drops back below MAX_PROPOSALS.
+//!
+//! Each pruned proposal leaves one digest entry:
+//!   hist_<proposal_id hex64>=<final_state>:<digest hex64>
+//! where digest = hash_archive(id, proposer, description, state, tallies),
+//! so anyone holding the final values can check them against the DAO.
+//! Only the MAX_ARCHIVED most recent entries are kept; older ones are
+//! folded, oldest first, into one running digest:
+//!   hist_root=<folded count>:<root hex64>
+//! where root = hash_archive_root(previous root, digest).
+//!
+//! Vote checkpoint histories are compacted to the current horizon, which
+//! reclaims entries held back for proposals that have since closed.
+//!
+//! A Queued proposal whose operation passed its grace period is archived
+//! as Expired. An operation still named as predecessor by a surviving
+//! operation is kept (with its proposal) so the dependency stays checkable.
+
+use crate::foundation::config::*;
+use crate::foundation::data::*;
+use crate::foundation::parse::*;
+use crate::foundation::types::{Proposal, TimelockOp};
+use crate::foundation::abi::decode_hash;
+use crate::crypto::hash::{hash_archive, hash_archive_root};
+use crate::crypto::hex::encode_hex;
+use crate::governance::governor::{build_prop_key, get_proposal_state, read_count};
+use crate::timelock::controller;
+use crate::token::xrp_votes;
+
+/// Remove finished proposals. Returns (new_data, new_len, pruned_count).
+/// Works over the stored `proposal_count`, so state written under larger
+/// `LIMITS` than this build's still prunes; a count larger than the data
+/// could hold fails with ERR_INVALID_ENTRY.
+/// Vote checkpoints are compacted to the current horizon either way (see
+/// `xrp_votes::compact_checkpoints`), so with nothing to prune the data
+/// may still shrink.
+pub fn prune(
+    data: &[u8],
+    data_len: usize,
+    current_time: u32,
+) -> Result<([u8; DATA_CAPACITY], usize, u16), i32> {
+    let data = &data[..data_len];
+    let prop_count = read_count(data, b"proposal_count");
+    let op_count = read_count(data, b"op_count");
+    if prop_count as usize > MAX_STORED_PROPOSALS {
+        return Err(ERR_INVALID_ENTRY);
+    }
+
+    // Final state of each prunable proposal, and the op linked to it
+    let mut final_states = [None::<u8>; MAX_STORED_PROPOSALS];
+    let mut prop_ids = [[0u8; HASH_SIZE]; MAX_STORED_PROPOSALS];
+    let mut op_of_prop = [None::<u16>; MAX_STORED_PROPOSALS];
+    let mut key_buf = [0u8; 48];
+
+    for i in 0..prop_count {
+        let id = match Proposal::load(data, i) {
+            Some(proposal) => proposal.id,
+            None => continue,
+        };
+        prop_ids[i as usize] = id;
+        let op = controller::find_operation_by_proposal(data, &id).ok();
+        op_of_prop[i as usize] = op;
+        final_states[i as usize] = final_state(data, i, op, current_time);
+    }
+    let final_states = &mut final_states[..prop_count as usize];
+    let op_of_prop = &op_of_prop[..prop_count as usize];
+
+    // Keep operations (and their proposals) that a surviving op depends on.
+    // Un-pruning one can make its own predecessor live, so iterate to a fixpoint.
+    loop {
+        let mut changed = false;
+        for i in 0..final_states.len() {
+            let op = match (final_states[i], op_of_prop[i]) {
+                (Some(_), Some(op)) => op,
+                _ => continue,
+            };
+            if is_live_predecessor(data, op, op_count, final_states, op_of_prop) {
+                final_states[i] = None;
+                changed = true;
+            }
+        }
+        if !changed { break; }
+    }
+    let final_states = &*final_states;
+
+    let pruned_count = final_states.iter().filter(|s| s.is_some()).count() as u16;
+    if pruned_count == 0 {
+        let mut store = Store::load(data);
+        xrp_votes::compact_checkpoints(&mut store, current_time)?;
+        let (new_data, new_len) = store.commit();
+        return Ok((new_data, new_len, 0));
+    }
+
+    // Dense renumbering of survivors. Each proposal has at most one op,
+    // so an op's new index is its old one minus the pruned ops below it.
+    let mut new_prop_index = [0u16; MAX_STORED_PROPOSALS];
+    let mut next = 0u16;
+    for (i, state) in final_states.iter().enumerate() {
+        if state.is_none() {
+            new_prop_index[i] = next;
+            next += 1;
+        }
+    }
+    let new_prop_count = next;
+
+    let op_pruned = |j: u16| is_pruned_op(j, final_states, op_of_prop);
+    let new_op_index = |j: u16| j - (0..j).filter(|&k| op_pruned(k)).count() as u16;
+    let new_op_count = new_op_index(op_count);
+
+    let is_pruned_id = |id: &[u8; HASH_SIZE]| {
+        final_states.iter().zip(&prop_ids).any(|(state, pid)| state.is_some() && pid == id)
+    };
+
+    let mut store = Store::new();
+    let mut num_buf = [0u8; 5];
+    for (key, value) in iter_prefix(data, b"") {
+        if key == b"proposal_count" {
+            let nlen = format_u32(new_prop_count as u32, &mut num_buf);
+            store.insert(key, &num_buf[..nlen])?;
+        } else if key == b"op_count" {
+            let nlen = format_u32(new_op_count as u32, &mut num_buf);
+            store.insert(key, &num_buf[..nlen])?;
+        } else if let Some((i, rest)) = split_indexed_key(key, b"prop_") {
+            if final_states.get(i as usize).is_some_and(|s| s.is_none()) {
+                let klen = build_prop_key(b"prop_", new_prop_index[i as usize], rest, &mut key_buf);
+                store.insert(&key_buf[..klen], value)?;
+            }
+        } else if let Some((i, rest)) = split_indexed_key(key, b"vote_") {
+            if final_states.get(i as usize).is_some_and(|s| s.is_none()) {
+                let klen = build_prop_key(b"vote_", new_prop_index[i as usize], rest, &mut key_buf);
+                store.insert(&key_buf[..klen], value)?;
+            }
+        } else if let Some((j, rest)) = split_indexed_key(key, b"op_") {
+            if !op_pruned(j) {
+                let klen = build_prop_key(b"op_", new_op_index(j), rest, &mut key_buf);
+                store.insert(&key_buf[..klen], value)?;
+            }
+        } else if let Some((id, _)) = split_id_key(key, b"sigvote_").or_else(|| split_id_key(key, b"snap_")) {
+            if !is_pruned_id(&id) {
+                store.insert(key, value)?;
+            }
+        } else {
+            store.insert(key, value)?;
+        }
+    }
+
+    // Archive digests
+    let mut val_buf = [0u8; 2 + HASH_SIZE * 2];
+    for i in 0..prop_count {
+        let state = match final_states[i as usize] {
+            Some(state) => state,
+            None => continue,
+        };
+        let id = &prop_ids[i as usize];
+        let digest = archive_digest(data, i, id, state);
+
+        let mut hist_key = [0u8; HIST_KEY_LEN];
+        build_hist_key(id, &mut hist_key);
+        val_buf[0] = b'0' + state;
+        val_buf[1] = b':';
+        encode_hex(&digest, &mut val_buf[2..]);
+        store.set(&hist_key, &val_buf)?;
+    }
+    fold_archives(&mut store)?;
+    xrp_votes::compact_checkpoints(&mut store, current_time)?;
+
+    let (new_data, new_len) = store.commit();
+    Ok((new_data, new_len, pruned_count))
+}
+
+/// Read the archived (final_state, digest) for a pruned proposal.
+pub fn get_archived(data: &[u8], proposal_id: &[u8; HASH_SIZE]) -> Option<(u8, [u8; HASH_SIZE])> {
+    let mut hist_key = [0u8; HIST_KEY_LEN];
+    build_hist_key(proposal_id, &mut hist_key);
+    let value = find_value(data, &hist_key)?;
+    if value.len() < 3 || value[1] != b':' {
+        return None;
+    }
+    Some((parse_u8_digit(&value[..1])?, decode_hash(&value[2..])?))
+}
+
+/// Read the running root of folded archive digests: (folded count, root).
+pub fn get_archive_root(data: &[u8]) -> Option<(u32, [u8; HASH_SIZE])> {
+    let value = find_value(data, HIST_ROOT_KEY)?;
+    let sep = value.iter().position(|&b| b == b':')?;
+    Some((parse_u32(&value[..sep])?, decode_hash(&value[sep + 1..])?))
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Internal helpers
+// ═══════════════════════════════════════════════════════════════════════
+
+/// Final state of a proposal, or None if it may still change.
+fn final_state(
+    data: &[u8],
+    index: u16,
+    op: Option<u16>,
+    current_time: u32,
+) -> Option<u8> {
+    let state = get_proposal_state(data, index, current_time);
+    match state {
+        PROPOSAL_STATE_CANCELED | PROPOSAL_STATE_DEFEATED
+        | PROPOSAL_STATE_EXECUTED | PROPOSAL_STATE_EXPIRED => Some(state),
+        PROPOSAL_STATE_QUEUED => op
+            .filter(|&op| controller::is_operation_expired(data, op, current_time))
+            .map(|_| PROPOSAL_STATE_EXPIRED),
+        _ => None,
+    }
+}
+
+/// Whether op `j` belongs to a proposal being pruned.
+fn is_pruned_op(j: u16, final_states: &[Option<u8>], op_of_prop: &[Option<u16>]) -> bool {
+    final_states.iter().zip(op_of_prop).any(|(state, &op)| state.is_some() && op == Some(j))
+}
+
+/// Whether op `op` is the predecessor of an op that will survive pruning.
+fn is_live_predecessor(
+    data: &[u8],
+    op: u16,
+    op_count: u16,
+    final_states: &[Option<u8>],
+    op_of_prop: &[Option<u16>],
+) -> bool {
+    let op_id = match TimelockOp::load(data, op) {
+        Some(op) => op.id,
+        None => return false,
+    };
+
+    (0..op_count).any(|k| {
+        !is_pruned_op(k, final_states, op_of_prop)
+            && TimelockOp::load(data, k).is_some_and(|op| op.predecessor == op_id)
+    })
+}
+
+/// Fold the oldest `hist_<id>` entries into `hist_root` until at most
+/// MAX_ARCHIVED remain. Entries are kept in the order they were archived.
+fn fold_archives(store: &mut Store) -> Result<(), i32> {
+    loop {
+        let data = store.as_bytes();
+        let mut entries = iter_prefix(data, b"hist_").filter(|(key, _)| *key != HIST_ROOT_KEY);
+        let Some((oldest_key, oldest_value)) = entries.next() else { return Ok(()) };
+        if entries.count() < MAX_ARCHIVED {
+            return Ok(());
+        }
+
+        let key: [u8; HIST_KEY_LEN] = oldest_key.try_into().map_err(|_| ERR_INVALID_ENTRY)?;
+        let digest = oldest_value.get(2..).and_then(decode_hash).ok_or(ERR_INVALID_ENTRY)?;
+        let (count, root) = get_archive_root(data).unwrap_or((0, [0u8; HASH_SIZE]));
+        let count = count.checked_add(1).ok_or(ERR_OVERFLOW)?;
+
+        let mut val_buf = [0u8; 11 + HASH_SIZE * 2];
+        let mut vlen = format_u32(count, &mut val_buf);
+        val_buf[vlen] = b':';
+        vlen += 1;
+        encode_hex(&hash_archive_root(&root, &digest), &mut val_buf[vlen..vlen + HASH_SIZE * 2]);
+        vlen += HASH_SIZE * 2;
+
+        store.remove(&key);
+        store.set(HIST_ROOT_KEY, &val_buf[..vlen])?;
+    }
+}
+
+/// Digest of proposal `index`'s final result.
+fn archive_digest(data: &[u8], index: u16, proposal_id: &[u8; HASH_SIZE], state: u8) -> [u8; HASH_SIZE] {
+    let proposal = Proposal::load(data, index).unwrap_or_default();
+    let tallies = [proposal.for_votes, proposal.against_votes, proposal.abstain_votes];
+    hash_archive(proposal_id, &proposal.proposer, &proposal.description_hash, state, tallies)
+}
+
+/// Split "prop_12_state" into (12, "_state"). The index must be followed
+/// by '_' (so "op_count" is not an indexed key).
+pub fn split_indexed_key<'a>(key: &'a [u8], prefix: &[u8]) -> Option<(u16, &'a [u8])> {
+    let rest = key.strip_prefix(prefix)?;
+    let digits = rest.iter().take_while(|b| b.is_ascii_digit()).count();
+    if digits == 0 || rest.get(digits) != Some(&b'_') {
+        return None;
+    }
+    let index = parse_u32(&rest[..digits]).and_then(|i| u16::try_from(i).ok())?;
+    Some((index, &rest[digits..]))
+}
+
+/// Split "snap_<id hex64>_<hex>" into (id, "_<hex>").
+fn split_id_key<'a>(key: &'a [u8], prefix: &[u8]) -> Option<([u8; HASH_SIZE], &'a [u8])> {
+    let rest = key.strip_prefix(prefix)?;
+    let id = decode_hash(rest.get(..HASH_SIZE * 2)?)?;
+    Some((id, &rest[HASH_SIZE * 2..]))
+}
+
+/// Most proposals a current-layout state can hold: each keeps at least a
+/// `prop_N_id=<hex64>;` entry. Sizes the per-proposal scratch arrays.
+const MAX_STORED_PROPOSALS: usize = DATA_CAPACITY / (b"prop_0_id=;".len() + HASH_SIZE * 2);
+
+/// Key of the running root of folded archive digests.
+const HIST_ROOT_KEY: &[u8] = b"hist_root";
+
+/// Length of "hist_<proposal_id hex64>".
+const HIST_KEY_LEN: usize = 5 + HASH_SIZE * 2;
+
+/// Build "hist_<proposal_id hex64>".
+fn build_hist_key(proposal_id: &[u8; HASH_SIZE], out: &mut [u8; HIST_KEY_LEN]) {
+    out[..5].copy_from_slice(b"hist_");
+    encode_hex(proposal_id, &mut out[5..]);
+}
@@ -0,0 +1,233 @@
+//! GovernorSettings — mirrors OpenZeppelin's GovernorSettings.sol
+//!
+//! The governance parameters live in the Data field so a passed proposal
+//! can retune them without a redeploy. Each key is optional; a missing key
+//! reads as the compile-time default from `foundation::config`, so existing
+//! state needs no migration.
+//!
+//! ## Data Format
+//!
+//!   cfg_voting_delay=600;cfg_quorum=10;...
+//!
+//! ## Access Control
+//!
+//! Like OZ `onlyGovernance`, `set_setting` only succeeds while a proposal
+//! is executing: the reentrancy lock is held and `_exec` names the
+//! proposal being run (see `governor::set_executing`). Both flags are
+//! cleared before the transaction ends, so no direct call can pass.
+//!
+//! A proposal reaches it through an `ACTION_SET_SETTING` action, which
+//! `execute` applies with `apply_action` while the proposal is marked.
+
+use crate::foundation::config::*;
+use crate::foundation::data::*;
+use crate::foundation::abi::decode_hash;
+use crate::foundation::types::Action;
+use crate::governance::governor::{find_proposal_by_id, format_u64, is_locked, parse_u64};
+
+/// Storage key for each `SETTING_*` id, in id order.
+pub const SETTING_KEYS: [&[u8]; 8] = [
+    b"cfg_voting_delay",
+    b"cfg_voting_period",
+    b"cfg_threshold",
+    b"cfg_quorum",
+    b"cfg_min_delay",
+    b"cfg_grace",
+    b"cfg_late_window",
+    b"cfg_late_extension",
+];
+
+/// Inclusive (min, max) bounds for each `SETTING_*` id, in id order.
+const BOUNDS: [(u64, u64); 8] = [
+    (MIN_VOTING_DELAY as u64, MAX_VOTING_DELAY as u64),
+    (MIN_VOTING_PERIOD as u64, MAX_VOTING_PERIOD as u64),
+    (MIN_PROPOSAL_THRESHOLD, MAX_PROPOSAL_THRESHOLD),
+    (MIN_QUORUM_PERCENTAGE as u64, MAX_QUORUM_PERCENTAGE as u64),
+    (MIN_TIMELOCK_DELAY as u64, MAX_TIMELOCK_DELAY as u64),
+    (MIN_GRACE_PERIOD as u64, MAX_GRACE_PERIOD as u64),
+    (MIN_LATE_QUORUM_WINDOW as u64, MAX_LATE_QUORUM_WINDOW as u64),
+    (MIN_LATE_QUORUM_EXTENSION as u64, MAX_LATE_QUORUM_EXTENSION as u64),
+];
+
+/// Effective governance parameters.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Settings {
+    pub voting_delay: u32,
+    pub voting_period: u32,
+    pub proposal_threshold: u64,
+    pub quorum_percentage: u8,
+    pub timelock_min_delay: u32,
+    pub timelock_grace_period: u32,
+    pub late_quorum_window: u32,
+    pub late_quorum_extension: u32,
+}
+
+impl Settings {
+    /// The compile-time defaults from `foundation::config`.
+    pub const DEFAULT: Settings = Settings {
+        voting_delay: VOTING_DELAY,
+        voting_period: VOTING_PERIOD,
+        proposal_threshold: PROPOSAL_THRESHOLD,
+        quorum_percentage: QUORUM_PERCENTAGE,
+        timelock_min_delay: TIMELOCK_MIN_DELAY,
+        timelock_grace_period: TIMELOCK_GRACE_PERIOD,
+        late_quorum_window: LATE_QUORUM_WINDOW,
+        late_quorum_extension: LATE_QUORUM_EXTENSION,
+    };
+
+    /// Read the stored settings, falling back to the default per key.
+    pub fn load(data: &[u8]) -> Settings {
+        let mut s = Settings::DEFAULT;
+        for param in 0..SETTING_KEYS.len() as u8 {
+            if let Some(value) = stored(data, param) {
+                let _ = s.set(param, value);
+            }
+        }
+        s
+    }
+
+    /// Value of one parameter, widened to u64. None for an unknown id.
+    pub fn get(&self, param: u8) -> Option<u64> {
+        match param {
+            SETTING_VOTING_DELAY => Some(self.voting_delay as u64),
+            SETTING_VOTING_PERIOD => Some(self.voting_period as u64),
+            SETTING_PROPOSAL_THRESHOLD => Some(self.proposal_threshold),
+            SETTING_QUORUM_PERCENTAGE => Some(self.quorum_percentage as u64),
+            SETTING_TIMELOCK_MIN_DELAY => Some(self.timelock_min_delay as u64),
+            SETTING_TIMELOCK_GRACE_PERIOD => Some(self.timelock_grace_period as u64),
+            SETTING_LATE_QUORUM_WINDOW => Some(self.late_quorum_window as u64),
+            SETTING_LATE_QUORUM_EXTENSION => Some(self.late_quorum_extension as u64),
+            _ => None,
+        }
+    }
+
+    /// Votes required for quorum out of `total_voting_power`.
+    /// Mirrors GovernorVotesQuorumFraction.quorum().
+    pub fn quorum(&self, total_voting_power: u64) -> u64 {
+        (total_voting_power / 100).saturating_mul(self.quorum_percentage as u64)
+    }
+
+    /// Check every parameter against its bounds.
+    pub fn validate(&self) -> Result<(), i32> {
+        for param in 0..SETTING_KEYS.len() as u8 {
+            check_bounds(param, self.get(param).unwrap_or(0))?;
+        }
+        Ok(())
+    }
+
+    /// Write every parameter that differs from the default; remove the rest.
+    pub fn store(&self, store: &mut Store) -> Result<(), i32> {
+        for param in 0..SETTING_KEYS.len() as u8 {
+            let value = self.get(param).unwrap_or(0);
+            if Some(value) == Settings::DEFAULT.get(param) {
+                store.remove(SETTING_KEYS[param as usize]);
+            } else {
+                put(store, param, value)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Assign one parameter, rejecting unknown ids and out-of-bounds values.
+    pub fn set(&mut self, param: u8, value: u64) -> Result<(), i32> {
+        check_bounds(param, value)?;
+        // Bounds keep every value within its field's width.
+        match param {
+            SETTING_VOTING_DELAY => self.voting_delay = value as u32,
+            SETTING_VOTING_PERIOD => self.voting_period = value as u32,
+            SETTING_PROPOSAL_THRESHOLD => self.proposal_threshold = value,
+            SETTING_QUORUM_PERCENTAGE => self.quorum_percentage = value as u8,
+            SETTING_TIMELOCK_MIN_DELAY => self.timelock_min_delay = value as u32,
+            SETTING_TIMELOCK_GRACE_PERIOD => self.timelock_grace_period = value as u32,
+            SETTING_LATE_QUORUM_WINDOW => self.late_quorum_window = value as u32,
+            SETTING_LATE_QUORUM_EXTENSION => self.late_quorum_extension = value as u32,
+            _ => {}
+        }
+        Ok(())
+    }
+}
+
+/// Effective value of one parameter. Mirrors the individual OZ getters
+/// (`votingDelay()`, `votingPeriod()`, `proposalThreshold()`, ...).
+pub fn get_setting(data: &[u8], param: u8) -> Result<u64, i32> {
+    Settings::load(data).get(param).ok_or(ERR_BAD_CONFIG)
+}
+
+/// Change one parameter. Mirrors `setVotingDelay()` and friends in
+/// GovernorSettings, which are `onlyGovernance`.
+///
+/// Requirements:
+///   - A proposal must be executing (`only_governance`)
+///   - `param` must be a known `SETTING_*` id and `value` within its bounds
+pub fn set_setting(
+    data: &[u8],
+    data_len: usize,
+    param: u8,
+    value: u64,
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
+    only_governance(data)?;
+    check_bounds(param, value)?;
+
+    let mut store = Store::load(&data[..data_len]);
+    put(&mut store, param, value)?;
+    Ok(store.commit())
+}
+
+/// Apply an `ACTION_SET_SETTING` action through `set_setting`.
+pub fn apply_action(
+    data: &[u8],
+    data_len: usize,
+    action: &Action,
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
+    let param = check_action(action)?;
+    set_setting(data, data_len, param, action.amount)
+}
+
+/// The setting id of a well-formed `ACTION_SET_SETTING` action: a one-byte
+/// payload naming a known id and an amount within its bounds.
+pub fn check_action(action: &Action) -> Result<u8, i32> {
+    match action.payload() {
+        &[param] => {
+            check_bounds(param, action.amount)?;
+            Ok(param)
+        }
+        _ => Err(ERR_BAD_CONFIG),
+    }
+}
+
+/// Succeeds only inside the execution of a proposal.
+/// Mirrors the `onlyGovernance` modifier.
+pub fn only_governance(data: &[u8]) -> Result<(), i32> {
+    if !is_locked(data) {
+        return Err(ERR_NOT_GOVERNANCE);
+    }
+    let executing = find_value(data, b"_exec")
+        .and_then(decode_hash)
+        .ok_or(ERR_NOT_GOVERNANCE)?;
+    find_proposal_by_id(data, &executing).map_err(|_| ERR_NOT_GOVERNANCE)?;
+    Ok(())
+}
+
+/// Reject unknown ids and out-of-bounds values with ERR_BAD_CONFIG.
+pub fn check_bounds(param: u8, value: u64) -> Result<(), i32> {
+    let &(min, max) = BOUNDS.get(param as usize).ok_or(ERR_BAD_CONFIG)?;
+    if !(min..=max).contains(&value) {
+        return Err(ERR_BAD_CONFIG);
+    }
+    Ok(())
+}
+
+// ——— Internal helpers ———
+
+/// The stored value for `param`, if present, parseable and in bounds.
+fn stored(data: &[u8], param: u8) -> Option<u64> {
+    let value = parse_u64(find_value(data, SETTING_KEYS[param as usize])?)?;
+    check_bounds(param, value).ok()?;
+    Some(value)
+}
+
+fn put(store: &mut Store, param: u8, value: u64) -> Result<(), i32> {
+    let mut buf = [0u8; 20];
+    let len = format_u64(value, &mut buf);
+    store.set(SETTING_KEYS[param as usize], &buf[..len])
+}
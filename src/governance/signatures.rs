@@ -30,13 +30,15 @@
 //! - Nonce not needed: hasVoted() check prevents replay
 
 use crate::foundation::config::*;
+use crate::foundation::data::{Store, DATA_CAPACITY};
 use crate::crypto::hex::encode_hex;
+use crate::crypto::sha512::sha512_half;
 
 /// Size of a secp256k1 signature (r + s + v)
 pub const SIGNATURE_SIZE: usize = 65;
 
 /// Build the message that must be signed for a vote-by-signature.
-/// Message format: "xrpl-dao:vote:{proposal_id}:{support}:{voter_hex}"
+/// Message format: "xrpl-dao:vote:{proposal_id hex64}:{support}:{voter_hex}"
 ///
 /// This mirrors EIP-712 typed data hashing — binding the signature to:
 ///   - Domain (xrpl-dao)
@@ -45,7 +47,7 @@ pub const SIGNATURE_SIZE: usize = 65;
 ///   - Support (how they're voting)
 ///   - Voter (who is voting)
 pub fn build_vote_message(
-    proposal_id: u32,
+    proposal_id: &[u8; HASH_SIZE],
     support: u8,
     voter: &[u8; ACCOUNT_ID_SIZE],
     out: &mut [u8],
@@ -55,12 +57,10 @@ pub fn build_vote_message(
     if pos > out.len() { return 0; }
     out[..pos].copy_from_slice(prefix);
 
-    // Proposal ID as decimal
-    let mut id_buf = [0u8; 10];
-    let id_len = crate::foundation::parse::format_u32(proposal_id, &mut id_buf);
-    if pos + id_len > out.len() { return 0; }
-    out[pos..pos + id_len].copy_from_slice(&id_buf[..id_len]);
-    pos += id_len;
+    // Proposal ID as hex
+    if pos + HASH_SIZE * 2 > out.len() { return 0; }
+    encode_hex(proposal_id, &mut out[pos..pos + HASH_SIZE * 2]);
+    pos += HASH_SIZE * 2;
 
     // Separator
     if pos >= out.len() { return 0; }
@@ -87,23 +87,10 @@ pub fn build_vote_message(
     pos
 }
 
-/// Hash a vote message for signature verification.
-/// Uses the same FNV-1a hash as proposal IDs for consistency.
-pub fn hash_vote_message(message: &[u8], message_len: usize) -> u32 {
-    let mut h: u64 = 0xcbf29ce484222325;
-
-    for i in 0..message_len {
-        h ^= message[i] as u64;
-        h = h.wrapping_mul(0x100000001b3);
-    }
-
-    h ^= h >> 33;
-    h = h.wrapping_mul(0xff51afd7ed558ccd);
-    h ^= h >> 33;
-    h = h.wrapping_mul(0xc4ceb9fe1a85ec53);
-    h ^= h >> 33;
-
-    h as u32
+/// Hash a vote message for signature verification: SHA-512Half, the
+/// digest XRPL signs, as used for proposal IDs.
+pub fn hash_vote_message(message: &[u8], message_len: usize) -> [u8; HASH_SIZE] {
+    sha512_half(&message[..message_len])
 }
 
 /// Verify that a vote-by-signature message is well-formed.
@@ -127,7 +114,7 @@ pub fn hash_vote_message(message: &[u8], message_len: usize) -> u32 {
 /// }
 /// ```
 pub fn validate_vote_message(
-    proposal_id: u32,
+    proposal_id: &[u8; HASH_SIZE],
     support: u8,
     voter: &[u8; ACCOUNT_ID_SIZE],
 ) -> bool {
@@ -137,7 +124,7 @@ pub fn validate_vote_message(
     }
 
     // Validate proposal_id is non-zero
-    if proposal_id == 0 {
+    if *proposal_id == [0u8; HASH_SIZE] {
         return false;
     }
 
@@ -157,14 +144,14 @@ pub fn validate_vote_message(
 /// This stores the intent so it can be processed when the host
 /// exposes signature verification.
 ///
-/// Data format: sigvote_<proposal_id>_<voter_hex>=<support>
+/// Data format: sigvote_<proposal_id hex64>_<voter_hex>=<support>
 pub fn record_sig_vote_intent(
     data: &[u8],
     data_len: usize,
-    proposal_id: u32,
+    proposal_id: &[u8; HASH_SIZE],
     support: u8,
     voter: &[u8; ACCOUNT_ID_SIZE],
-) -> Result<([u8; 4096], usize), i32> {
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
     if !validate_vote_message(proposal_id, support, voter) {
         return Err(ERR_INVALID_VOTE);
     }
@@ -172,16 +159,14 @@ pub fn record_sig_vote_intent(
     let mut voter_hex = [0u8; 40];
     encode_hex(voter, &mut voter_hex);
 
-    // Build key: "sigvote_<prop_id>_<voter_hex>"
-    let mut key_buf = [0u8; 64];
+    // Build key: "sigvote_<prop_id hex64>_<voter_hex>"
+    let mut key_buf = [0u8; 8 + HASH_SIZE * 2 + 1 + 40];
     let prefix = b"sigvote_";
     let mut kpos = prefix.len();
     key_buf[..kpos].copy_from_slice(prefix);
 
-    let mut id_buf = [0u8; 10];
-    let id_len = crate::foundation::parse::format_u32(proposal_id, &mut id_buf);
-    key_buf[kpos..kpos + id_len].copy_from_slice(&id_buf[..id_len]);
-    kpos += id_len;
+    encode_hex(proposal_id, &mut key_buf[kpos..kpos + HASH_SIZE * 2]);
+    kpos += HASH_SIZE * 2;
 
     key_buf[kpos] = b'_';
     kpos += 1;
@@ -192,17 +177,12 @@ pub fn record_sig_vote_intent(
     // Value: support digit
     let val = [b'0' + support];
 
-    // Append to data
-    let mut new_data = [0u8; 4096];
-    if data_len > 0 {
-        new_data[..data_len].copy_from_slice(&data[..data_len]);
-    }
-    let mut pos = data_len;
-
-    if pos > 0 {
-        pos = crate::foundation::data::write_separator(&mut new_data, pos);
+    // One intent per voter per proposal
+    let mut store = Store::load(&data[..data_len]);
+    if store.get(&key_buf[..kpos]).is_some() {
+        return Err(ERR_ALREADY_VOTED);
     }
-    pos = crate::foundation::data::write_entry(&mut new_data, pos, &key_buf[..kpos], &val);
+    store.insert(&key_buf[..kpos], &val)?;
 
-    Ok((new_data, pos))
+    Ok(store.commit())
 }
@@ -16,23 +16,21 @@
 
 use crate::foundation::config::*;
 use crate::foundation::data::*;
-use crate::crypto::hex::encode_hex;
-use crate::governance::governor::{parse_u64, format_u64};
+use crate::foundation::types::Member;
+use crate::governance::governor::{read_count, write_count};
+use crate::governance::settings::Settings;
+use crate::token::xrp_votes::{
+    checkpoint_total_supply, checkpoint_votes, clear_delegation, get_delegate, preserve_snapshots,
+};
 
 /// Get voting power of an account. Mirrors Governor.getVotes().
 pub fn get_votes(data: &[u8], account: &[u8; ACCOUNT_ID_SIZE]) -> u64 {
-    match find_member(data, account) {
-        Some((_idx, _hex, power, _roles)) => power,
-        None => 0,
-    }
+    Member::find(data, account).map_or(0, |(_, m)| m.voting_power)
 }
 
 /// Get roles bitmask for an account.
 pub fn get_roles(data: &[u8], account: &[u8; ACCOUNT_ID_SIZE]) -> u8 {
-    match find_member(data, account) {
-        Some((_idx, _hex, _power, roles)) => roles,
-        None => 0,
-    }
+    Member::find(data, account).map_or(0, |(_, m)| m.roles)
 }
 
 /// Check if account has a specific role.
@@ -44,118 +42,140 @@ pub fn has_role(data: &[u8], account: &[u8; ACCOUNT_ID_SIZE], role: u8) -> bool
 /// Mirrors token.totalSupply() as used by GovernorVotesQuorumFraction.
 /// Fix #6: Uses saturating_add to prevent overflow.
 pub fn get_total_voting_power(data: &[u8]) -> u64 {
-    let member_count = read_member_count(data);
-
-    let mut total: u64 = 0;
-    let mut key_buf = [0u8; 16];
+    let member_count = get_member_count(data);
 
-    for i in 0..member_count {
-        let klen = build_member_key(i, &mut key_buf);
-        if let Some(val) = find_value(data, &key_buf[..klen]) {
-            if let Some((_hex, power, _roles)) = parse_member_record(val) {
-                total = total.saturating_add(power);
-            }
-        }
-    }
-    total
+    (0..member_count)
+        .filter_map(|i| Member::load(data, i))
+        .fold(0u64, |total, m| total.saturating_add(m.voting_power))
 }
 
-/// Calculate quorum required for a given total voting power.
-/// Mirrors GovernorVotesQuorumFraction.quorum().
+/// Calculate quorum required for a given total voting power at the
+/// default QUORUM_PERCENTAGE. Mirrors GovernorVotesQuorumFraction.quorum().
+/// State evaluation uses `Settings::quorum`, which honours overrides.
 pub fn quorum(total_voting_power: u64) -> u64 {
-    (total_voting_power / 100).saturating_mul(QUORUM_PERCENTAGE as u64)
-}
-
-/// Get number of registered members. Supports multi-digit counts (0-99).
-pub fn get_member_count(data: &[u8]) -> u8 {
-    read_member_count(data)
+    Settings::DEFAULT.quorum(total_voting_power)
 }
 
-/// Read member_count supporting multi-digit values.
-fn read_member_count(data: &[u8]) -> u8 {
-    find_value(data, b"member_count")
-        .and_then(|v| {
-            if v.is_empty() { return None; }
-            let mut result: u8 = 0;
-            for &b in v {
-                if b < b'0' || b > b'9' { return None; }
-                result = result.checked_mul(10)?.checked_add(b - b'0')?;
-            }
-            Some(result)
-        })
-        .unwrap_or(0)
+/// Get number of registered members.
+pub fn get_member_count(data: &[u8]) -> u16 {
+    read_count(data, b"member_count")
 }
 
 /// Add or update a member. Returns updated data.
+///
+/// A power change is snapshotted first for proposals open at `current_time`
+/// and checkpointed at `current_time` afterwards.
 pub fn set_member(
     data: &[u8],
     data_len: usize,
     account: &[u8; ACCOUNT_ID_SIZE],
     voting_power: u64,
     roles: u8,
-) -> Result<([u8; 4096], usize), i32> {
+    current_time: u32,
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
     let member_count = get_member_count(data);
+    let member = Member { account: *account, voting_power, roles };
+
+    let mut store = Store::load(&data[..data_len]);
 
-    // Build the member value: <hex>:<power>:<roles>
-    let mut val_buf = [0u8; 64];
-    let vlen = build_member_value(account, voting_power, roles, &mut val_buf);
+    // The power lands on the account's delegate: snapshot it for open proposals
+    let existing = Member::find(data, account);
+    let delegate = get_delegate(data, account);
+    let power_changed = existing.map_or(0, |(_, m)| m.voting_power) != voting_power;
+    if power_changed {
+        preserve_snapshots(data, &mut store, &delegate, current_time)?;
+    }
 
     // Check if member already exists
-    if let Some((idx, _, _, _)) = find_member(data, account) {
+    if let Some((idx, _)) = existing {
         // Update existing member
-        let mut key_buf = [0u8; 16];
-        let klen = build_member_key(idx, &mut key_buf);
-        return update_data_field(data, data_len, &key_buf[..klen], &val_buf[..vlen]);
-    }
+        member.store(&mut store, idx)?;
+    } else {
+        // New member — check capacity
+        if member_count >= LIMITS.max_members {
+            return Err(ERR_BAD_CONFIG);
+        }
 
-    // New member — check capacity
-    if member_count as usize >= MAX_MEMBERS {
-        return Err(ERR_BAD_CONFIG);
-    }
+        // Write updated member_count (multi-digit safe)
+        write_count(&mut store, b"member_count", member_count + 1)?;
 
-    // Append new member
-    let mut new_data = [0u8; 4096];
-    let mut pos = 0;
-    let mut scan = 0;
+        // Write new member entry
+        member.store(&mut store, member_count)?;
+    }
 
-    while scan < data_len {
-        let entry_end = data[scan..data_len].iter()
-            .position(|&b| b == b';')
-            .map(|p| scan + p)
-            .unwrap_or(data_len);
+    if power_changed {
+        checkpoint_votes(&mut store, &delegate, current_time)?;
+        checkpoint_total_supply(&mut store, current_time)?;
+    }
 
-        let entry = &data[scan..entry_end];
+    Ok(store.commit())
+}
 
-        // Skip old member_count
-        let skip = if let Some(eq) = entry.iter().position(|&b| b == b'=') {
-            &entry[..eq] == b"member_count"
-        } else { false };
+/// Remove a member and compact the registry. Returns updated data.
+///
+/// Later `member_N` entries shift down one index, so the indices stay
+/// contiguous and in registration order. The member's own delegation is
+/// dropped, and accounts that delegated to it fall back to self-delegation
+/// so their power is not stranded on a non-member. Every account whose
+/// votes change is snapshotted first for open proposals and checkpointed
+/// afterwards.
+///
+/// Fails with ERR_WRONG_ACCOUNT if `account` is not a member, and with
+/// ERR_BAD_CONFIG if it is the last member holding ROLE_ADMIN.
+pub fn remove_member(
+    data: &[u8],
+    data_len: usize,
+    account: &[u8; ACCOUNT_ID_SIZE],
+    current_time: u32,
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
+    let (idx, member) = Member::find(data, account).ok_or(ERR_WRONG_ACCOUNT)?;
+    let member_count = get_member_count(data);
 
-        if !skip && !entry.is_empty() {
-            if pos > 0 { pos = write_separator(&mut new_data, pos); }
-            let elen = entry.len();
-            if pos + elen <= new_data.len() {
-                new_data[pos..pos + elen].copy_from_slice(entry);
-                pos += elen;
-            }
+    if member.has_role(ROLE_ADMIN) {
+        let other_admin = (0..member_count)
+            .filter(|&i| i != idx)
+            .filter_map(|i| Member::load(data, i))
+            .any(|m| m.has_role(ROLE_ADMIN));
+        if !other_admin {
+            return Err(ERR_BAD_CONFIG);
         }
-
-        scan = entry_end + 1;
     }
 
-    // Write updated member_count (multi-digit safe)
-    if pos > 0 { pos = write_separator(&mut new_data, pos); }
-    let mut count_buf = [0u8; 3];
-    let count_len = crate::governance::governor::format_u8(member_count + 1, &mut count_buf);
-    pos = write_entry(&mut new_data, pos, b"member_count", &count_buf[..count_len]);
+    // Votes change for the member's delegate, the member, and the members
+    // that delegated to it (they get their own power back). Walked over
+    // the stored member count, which a migrated DAO may have above
+    // MAX_MEMBERS.
+    let delegate = get_delegate(data, account);
+    let delegators = (0..member_count)
+        .filter_map(|i| Member::load(data, i))
+        .map(|other| other.account)
+        .filter(|other| other != account && get_delegate(data, other) == *account);
+    let changed = || [delegate, *account].into_iter().chain(delegators.clone());
+
+    let mut store = Store::load(&data[..data_len]);
+    for changed_account in changed() {
+        preserve_snapshots(data, &mut store, &changed_account, current_time)?;
+    }
 
-    // Write new member entry
-    if pos > 0 { pos = write_separator(&mut new_data, pos); }
+    for i in idx + 1..member_count {
+        let next = Member::load(data, i).ok_or(ERR_INVALID_ENTRY)?;
+        next.store(&mut store, i - 1)?;
+    }
     let mut key_buf = [0u8; 16];
-    let klen = build_member_key(member_count, &mut key_buf);
-    pos = write_entry(&mut new_data, pos, &key_buf[..klen], &val_buf[..vlen]);
+    let klen = build_indexed_key(b"member_", member_count - 1, &mut key_buf);
+    store.remove(&key_buf[..klen]);
+    write_count(&mut store, b"member_count", member_count - 1)?;
 
-    Ok((new_data, pos))
+    for changed_account in changed().skip(1) {
+        clear_delegation(&mut store, &changed_account);
+    }
+
+    for changed_account in changed() {
+        checkpoint_votes(&mut store, &changed_account, current_time)?;
+    }
+    checkpoint_total_supply(&mut store, current_time)?;
+
+    Ok(store.commit())
 }
 
 /// Grant a role to an account (OR with existing roles).
@@ -164,10 +184,11 @@ pub fn grant_role(
     data_len: usize,
     account: &[u8; ACCOUNT_ID_SIZE],
     role: u8,
-) -> Result<([u8; 4096], usize), i32> {
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
     let current_roles = get_roles(data, account);
     let power = get_votes(data, account);
-    set_member(data, data_len, account, power, current_roles | role)
+    // Power is unchanged, so no snapshot is taken and the time is unused
+    set_member(data, data_len, account, power, current_roles | role, 0)
 }
 
 /// Revoke a role from an account (AND NOT with existing roles).
@@ -176,129 +197,8 @@ pub fn revoke_role(
     data_len: usize,
     account: &[u8; ACCOUNT_ID_SIZE],
     role: u8,
-) -> Result<([u8; 4096], usize), i32> {
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
     let current_roles = get_roles(data, account);
     let power = get_votes(data, account);
-    set_member(data, data_len, account, power, current_roles & !role)
-}
-
-// ——— Internal helpers ———
-
-/// Build "member_N" key — supports multi-digit indices (0-99).
-/// Fix #7: replaces single-digit `b'0' + index` with proper formatting.
-fn build_member_key(index: u8, out: &mut [u8]) -> usize {
-    let prefix = b"member_";
-    let plen = prefix.len();
-    out[..plen].copy_from_slice(prefix);
-    let idx_len = crate::governance::governor::format_u8(index, &mut out[plen..]);
-    plen + idx_len
-}
-
-/// Build member value: "<hex40>:<power>:<roles>"
-fn build_member_value(
-    account: &[u8; ACCOUNT_ID_SIZE],
-    voting_power: u64,
-    roles: u8,
-    out: &mut [u8],
-) -> usize {
-    let mut pos = 0;
-    let mut hex_buf = [0u8; 40];
-    encode_hex(account, &mut hex_buf);
-    out[pos..pos + 40].copy_from_slice(&hex_buf);
-    pos += 40;
-    out[pos] = b':';
-    pos += 1;
-    let plen = format_u64(voting_power, &mut out[pos..]);
-    pos += plen;
-    out[pos] = b':';
-    pos += 1;
-    out[pos] = b'0' + roles;
-    pos += 1;
-    pos
-}
-
-/// Find a member by account. Returns (index, hex_slice, power, roles).
-fn find_member<'a>(
-    data: &'a [u8],
-    account: &[u8; ACCOUNT_ID_SIZE],
-) -> Option<(u8, &'a [u8], u64, u8)> {
-    let mut hex_buf = [0u8; 40];
-    encode_hex(account, &mut hex_buf);
-
-    let member_count = read_member_count(data);
-
-    let mut key_buf = [0u8; 16];
-
-    for i in 0..member_count {
-        let klen = build_member_key(i, &mut key_buf);
-        if let Some(val) = find_value(data, &key_buf[..klen]) {
-            if val.len() >= 40 && &val[..40] == &hex_buf[..] {
-                if let Some((_hex, power, roles)) = parse_member_record(val) {
-                    return Some((i, &val[..40], power, roles));
-                }
-            }
-        }
-    }
-    None
-}
-
-/// Parse "hex40:power:roles" → (hex_slice, power, roles)
-fn parse_member_record(val: &[u8]) -> Option<(&[u8], u64, u8)> {
-    if val.len() < 44 { return None; } // 40 hex + : + at least 1 digit + : + 1 digit
-    if val[40] != b':' { return None; }
-
-    let rest = &val[41..];
-    let colon2 = rest.iter().position(|&b| b == b':')?;
-    let power = parse_u64(&rest[..colon2])?;
-    let roles_byte = rest.get(colon2 + 1)?;
-    let roles = roles_byte.checked_sub(b'0')?;
-
-    Some((&val[..40], power, roles))
-}
-
-/// Generic data field update helper
-fn update_data_field(
-    data: &[u8],
-    data_len: usize,
-    target_key: &[u8],
-    new_value: &[u8],
-) -> Result<([u8; 4096], usize), i32> {
-    let mut new_data = [0u8; 4096];
-    let mut pos = 0;
-    let mut scan = 0;
-    let mut found = false;
-
-    while scan < data_len {
-        let entry_end = data[scan..data_len].iter()
-            .position(|&b| b == b';')
-            .map(|p| scan + p)
-            .unwrap_or(data_len);
-
-        let entry = &data[scan..entry_end];
-
-        let is_target = if let Some(eq) = entry.iter().position(|&b| b == b'=') {
-            &entry[..eq] == target_key
-        } else { false };
-
-        if is_target {
-            if pos > 0 { pos = write_separator(&mut new_data, pos); }
-            pos = write_entry(&mut new_data, pos, target_key, new_value);
-            found = true;
-        } else if !entry.is_empty() {
-            if pos > 0 { pos = write_separator(&mut new_data, pos); }
-            let elen = entry.len();
-            if pos + elen <= new_data.len() {
-                new_data[pos..pos + elen].copy_from_slice(entry);
-                pos += elen;
-            }
-        }
-
-        scan = entry_end + 1;
-    }
-
-    if !found {
-        return Err(ERR_DATA_READ);
-    }
-
-    Ok((new_data, pos))
+    set_member(data, data_len, account, power, current_roles & !role, 0)
 }
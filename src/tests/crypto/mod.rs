@@ -1 +1,2 @@
 pub mod hex_tests;
+pub mod sha512_tests;
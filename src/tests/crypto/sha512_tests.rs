@@ -0,0 +1,76 @@
+use crate::crypto::hex::encode_hex;
+use crate::crypto::sha512::*;
+
+fn hex(digest: &[u8]) -> [u8; DIGEST_SIZE * 2] {
+    let mut out = [0u8; DIGEST_SIZE * 2];
+    encode_hex(digest, &mut out[..digest.len() * 2]);
+    out
+}
+
+// FIPS 180-4 example vectors
+
+#[test]
+fn test_sha512_empty() {
+    assert_eq!(
+        &hex(&sha512(b""))[..],
+        &b"cf83e1357eefb8bdf1542850d66d8007d620e4050b5715dc83f4a921d36ce9ce\
+           47d0d13c5d85f2b0ff8318d2877eec2f63b931bd47417a81a538327af927da3e"[..],
+    );
+}
+
+#[test]
+fn test_sha512_abc() {
+    assert_eq!(
+        &hex(&sha512(b"abc"))[..],
+        &b"ddaf35a193617abacc417349ae20413112e6fa4e89a97ea20a9eeee64b55d39a\
+           2192992a274fc1a836ba3c23a3feebbd454d4423643ce80e2a9ac94fa54ca49f"[..],
+    );
+}
+
+#[test]
+fn test_sha512_two_block_message() {
+    let message = b"abcdefghbcdefghicdefghijdefghijkefghijklfghijklmghijklmn\
+                    hijklmnoijklmnopjklmnopqklmnopqrlmnopqrsmnopqrstnopqrstu";
+    assert_eq!(
+        &hex(&sha512(message))[..],
+        &b"8e959b75dae313da8cf4f72814fc143f8f7779c6eb9f7fa17299aeadb6889018\
+           501d289e4900f7e4331b99dec4b5433ac7d329eeb6dd26545e96e55b874be909"[..],
+    );
+}
+
+#[test]
+fn test_sha512_million_a() {
+    let mut hasher = Sha512::new();
+    for _ in 0..1000 {
+        hasher.update(&[b'a'; 1000]);
+    }
+    assert_eq!(
+        &hex(&hasher.finalize())[..],
+        &b"e718483d0ce769644e2e42c7bc15b4638e1f98b13b2044285632a803afa973eb\
+           de0ff244877ea60a4cb0432ce577c31beb009c5c2c49aa2e4eadb217ad8cc09b"[..],
+    );
+}
+
+#[test]
+fn test_streaming_matches_one_shot_across_blocks() {
+    let mut message = [0u8; 300];
+    for (i, b) in message.iter_mut().enumerate() {
+        *b = i as u8;
+    }
+    // Lengths around the 112-byte padding boundary and the 128-byte block
+    for len in [0, 1, 111, 112, 113, 127, 128, 129, 255, 256, 300] {
+        let expected = sha512(&message[..len]);
+        for split in [0, len / 3, len / 2, len] {
+            let mut hasher = Sha512::new();
+            hasher.update(&message[..split]);
+            hasher.update(&message[split..len]);
+            assert_eq!(hasher.finalize(), expected, "len {len}, split {split}");
+        }
+    }
+}
+
+#[test]
+fn test_sha512_half_is_digest_prefix() {
+    let full = sha512(b"abc");
+    assert_eq!(sha512_half(b"abc")[..], full[..HALF_SIZE]);
+}
@@ -0,0 +1,616 @@
+use crate::foundation::config::*;
+use crate::foundation::data::*;
+use crate::foundation::host::MockHost;
+use crate::foundation::types::{Actions, Proposal, TimelockOp};
+use crate::crypto::hex::encode_hex;
+use crate::exports;
+use crate::governance::governor::parse_u64;
+use crate::governance::votes;
+use crate::tests::*;
+
+const GENESIS_TIME: u32 = 1_000_000;
+
+/// `key=<hex40>` for `account`, appended to `out` after an optional `;`.
+fn push_account(out: &mut [u8; 1024], len: &mut usize, key: &[u8], account: &[u8; ACCOUNT_ID_SIZE]) {
+    if *len > 0 {
+        out[*len] = b';';
+        *len += 1;
+    }
+    out[*len..*len + key.len()].copy_from_slice(key);
+    *len += key.len();
+    out[*len] = b'=';
+    encode_hex(account, &mut out[*len + 1..*len + 41]);
+    *len += 41;
+}
+
+/// Append raw text to `out`.
+fn push(out: &mut [u8; 1024], len: &mut usize, text: &[u8]) {
+    out[*len..*len + text.len()].copy_from_slice(text);
+    *len += text.len();
+}
+
+/// A DAO initialized by alice (admin + proposer, 1000 XRP), with bob
+/// (500 XRP) and dave (executor).
+fn genesis_host() -> MockHost {
+    let mut spec = [0u8; 1024];
+    let mut len = 0;
+    push_account(&mut spec, &mut len, b"admin", &alice());
+    push_account(&mut spec, &mut len, b"member_0", &alice());
+    push(&mut spec, &mut len, b":1000000000:5");
+    push_account(&mut spec, &mut len, b"member_1", &bob());
+    push(&mut spec, &mut len, b":500000000:0");
+    push_account(&mut spec, &mut len, b"member_2", &dave());
+    push(&mut spec, &mut len, b":0:2");
+
+    let mut host = MockHost::new();
+    host.time = GENESIS_TIME;
+    host.call_as(&alice()).with_memo(&spec[..len]);
+    assert_eq!(exports::initialize(&mut host), SUCCESS);
+    host
+}
+
+/// Propose a 1 XRP payment to carol as alice; returns the new proposal id.
+fn propose(host: &mut MockHost) -> [u8; HASH_SIZE] {
+    propose_actions(host, &[(&carol(), b":1000000:0:")])
+}
+
+/// Description text sent as the second memo of every test proposal.
+const DESCRIPTION: &[u8] = b"Pay carol 1 XRP for the audit";
+
+/// Propose `actions` as alice, each a target plus its
+/// `:<drops>:<tx type>:<payload hex>` tail; returns the new proposal id.
+fn propose_actions(host: &mut MockHost, actions: &[(&[u8; ACCOUNT_ID_SIZE], &[u8])]) -> [u8; HASH_SIZE] {
+    let mut memo = [0u8; 1024];
+    let mut len = 0;
+    push(&mut memo, &mut len, b"actions=");
+    for (i, (target, tail)) in actions.iter().enumerate() {
+        if i > 0 {
+            push(&mut memo, &mut len, b",");
+        }
+        encode_hex(*target, &mut memo[len..len + 40]);
+        len += 40;
+        push(&mut memo, &mut len, tail);
+    }
+    host.call_as(&alice()).with_memo(&memo[..len]).with_memo(DESCRIPTION);
+    assert_eq!(exports::propose(host), SUCCESS);
+
+    let (state, state_len) = host.state();
+    let count = crate::governance::governor::read_count(&state[..state_len], b"proposal_count");
+    Proposal::load(&state[..state_len], count - 1).unwrap().id
+}
+
+/// Start a call by `caller` whose memo carries `id=<hex64>`.
+fn call_with_id<'a>(host: &'a mut MockHost, caller: &[u8; ACCOUNT_ID_SIZE], id: &[u8; HASH_SIZE]) -> &'a mut MockHost {
+    let mut memo = [0u8; 1024];
+    let mut len = 0;
+    push(&mut memo, &mut len, b"id=");
+    encode_hex(id, &mut memo[len..len + HASH_SIZE * 2]);
+    len += HASH_SIZE * 2;
+    host.call_as(caller).with_memo(&memo[..len])
+}
+
+/// Propose, vote For as alice and run out the voting period.
+fn succeeded_proposal(host: &mut MockHost) -> [u8; HASH_SIZE] {
+    let id = propose(host);
+    host.advance(VOTING_DELAY + 1);
+    call_with_id(host, &alice(), &id);
+    assert_eq!(exports::cast_vote(host, VOTE_FOR), SUCCESS);
+    host.advance(VOTING_PERIOD);
+    id
+}
+
+fn value(host: &MockHost, key: &[u8]) -> Option<u64> {
+    let (state, len) = host.state();
+    find_value(&state[..len], key).and_then(parse_u64)
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Lifecycle
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_initialize_writes_genesis_once() {
+    let mut host = genesis_host();
+    assert_eq!(value(&host, b"member_count"), Some(3));
+    let (state, len) = host.state();
+    assert!(votes::has_role(&state[..len], &alice(), ROLE_ADMIN));
+    assert_invariants(&state[..len]);
+
+    // Second call fails and leaves the state alone
+    let writes = host.writes;
+    let mut spec = [0u8; 1024];
+    let mut spec_len = 0;
+    push_account(&mut spec, &mut spec_len, b"admin", &alice());
+    host.call_as(&alice()).with_memo(&spec[..spec_len]);
+    assert_eq!(exports::initialize(&mut host), ERR_ALREADY_INITIALIZED);
+    assert_eq!(host.writes, writes);
+}
+
+#[test]
+fn test_full_lifecycle_queue_and_execute() {
+    let mut host = genesis_host();
+    let id = succeeded_proposal(&mut host);
+
+    // Queue: proposal becomes Queued with eta, op pending
+    let queued_at = host.time;
+    call_with_id(&mut host, &bob(), &id);
+    assert_eq!(exports::queue(&mut host), SUCCESS);
+    let (state, len) = host.state();
+    let proposal = Proposal::load(&state[..len], 0).unwrap();
+    assert_eq!(proposal.state, PROPOSAL_STATE_QUEUED);
+    assert_eq!(proposal.eta, queued_at + TIMELOCK_MIN_DELAY);
+    assert_eq!(TimelockOp::load(&state[..len], 0).unwrap().state, OP_STATE_PENDING);
+    assert_invariants(&state[..len]);
+
+    // Too early
+    call_with_id(&mut host, &dave(), &id);
+    assert!(exports::execute(&mut host) < 0);
+    assert_eq!(value(&host, b"_lock"), Some(0));
+
+    // Execute: proposal Executed, op done, lock released, marker removed
+    host.advance(TIMELOCK_MIN_DELAY);
+    call_with_id(&mut host, &dave(), &id);
+    assert_eq!(exports::execute(&mut host), SUCCESS);
+    let (state, len) = host.state();
+    assert_eq!(Proposal::load(&state[..len], 0).unwrap().state, PROPOSAL_STATE_EXECUTED);
+    assert_eq!(TimelockOp::load(&state[..len], 0).unwrap().state, OP_STATE_DONE);
+    assert_eq!(find_value(&state[..len], b"_lock"), Some(b"0" as &[u8]));
+    assert_eq!(find_value(&state[..len], b"_exec"), None);
+    assert_invariants(&state[..len]);
+    host.call_as(&eve());
+    assert_eq!(exports::audit(&mut host), SUCCESS);
+}
+
+#[test]
+fn test_canceller_stops_queued_proposal() {
+    let mut host = genesis_host();
+    let mut memo = [0u8; 1024];
+    let mut len = 0;
+    push_account(&mut memo, &mut len, b"account", &dave());
+    push(&mut memo, &mut len, b";power=0;roles=10");
+    host.call_as(&alice()).with_memo(&memo[..len]);
+    assert_eq!(exports::add_member(&mut host), SUCCESS);
+
+    let id = succeeded_proposal(&mut host);
+    call_with_id(&mut host, &bob(), &id);
+    assert_eq!(exports::queue(&mut host), SUCCESS);
+
+    // Only the guardian can cancel once voting has opened
+    call_with_id(&mut host, &alice(), &id);
+    assert_eq!(exports::cancel(&mut host), ERR_PROPOSAL_NOT_ACTIVE);
+    call_with_id(&mut host, &bob(), &id);
+    assert_eq!(exports::cancel(&mut host), ERR_NOT_PROPOSER);
+    call_with_id(&mut host, &dave(), &id);
+    assert_eq!(exports::cancel(&mut host), SUCCESS);
+
+    let (state, len) = host.state();
+    assert_eq!(Proposal::load(&state[..len], 0).unwrap().state, PROPOSAL_STATE_CANCELED);
+    assert_eq!(TimelockOp::load(&state[..len], 0).unwrap().state, OP_STATE_UNSET);
+    assert_invariants(&state[..len]);
+
+    host.advance(TIMELOCK_MIN_DELAY);
+    call_with_id(&mut host, &dave(), &id);
+    assert!(exports::execute(&mut host) < 0);
+}
+
+#[test]
+fn test_queue_rejects_unfinished_vote() {
+    let mut host = genesis_host();
+    let id = propose(&mut host);
+    call_with_id(&mut host, &bob(), &id);
+    assert_eq!(exports::queue(&mut host), ERR_PROPOSAL_NOT_ACTIVE);
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Caller verification and locking
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_spoofed_caller_rejected() {
+    let mut host = genesis_host();
+    let id = propose(&mut host);
+    host.advance(VOTING_DELAY + 1);
+    let writes = host.writes;
+
+    call_with_id(&mut host, &bob(), &id).spoofed_caller = Some(alice());
+    assert_eq!(exports::cast_vote(&mut host, VOTE_FOR), ERR_CALLER_VERIFICATION);
+    call_with_id(&mut host, &bob(), &id).spoofed_caller = Some(dave());
+    assert_eq!(exports::execute(&mut host), ERR_CALLER_VERIFICATION);
+
+    let mut memo = [0u8; 1024];
+    let mut len = 0;
+    push_account(&mut memo, &mut len, b"delegatee", &bob());
+    host.call_as(&eve()).with_memo(&memo[..len]).spoofed_caller = Some(alice());
+    assert_eq!(exports::delegate_votes(&mut host), ERR_CALLER_VERIFICATION);
+    assert_eq!(host.writes, writes);
+}
+
+#[test]
+fn test_execute_rejects_held_lock() {
+    let mut host = genesis_host();
+    let id = succeeded_proposal(&mut host);
+    call_with_id(&mut host, &bob(), &id);
+    assert_eq!(exports::queue(&mut host), SUCCESS);
+    host.advance(TIMELOCK_MIN_DELAY);
+
+    // A lock left set by an interrupted execution blocks execute and prune
+    let (state, len) = host.state();
+    let mut store = Store::load(&state[..len]);
+    store.set(b"_lock", b"1").unwrap();
+    let (locked, locked_len) = store.commit();
+    let mut host = MockHost::with_state(&locked[..locked_len]);
+    host.time = GENESIS_TIME + VOTING_DELAY + VOTING_PERIOD + TIMELOCK_MIN_DELAY + 1;
+
+    call_with_id(&mut host, &dave(), &id);
+    assert_eq!(exports::execute(&mut host), ERR_REENTRANT);
+    assert_eq!(exports::prune(&mut host), ERR_REENTRANT);
+    assert_eq!(host.writes, 0);
+}
+
+#[test]
+fn test_execute_requires_executor() {
+    let mut host = genesis_host();
+    let id = succeeded_proposal(&mut host);
+    call_with_id(&mut host, &bob(), &id);
+    assert_eq!(exports::queue(&mut host), SUCCESS);
+    host.advance(TIMELOCK_MIN_DELAY);
+    call_with_id(&mut host, &bob(), &id);
+    assert_eq!(exports::execute(&mut host), ERR_NOT_EXECUTOR);
+}
+
+#[test]
+fn test_execute_emits_actions_and_records_outcomes() {
+    let mut host = genesis_host();
+    let id = propose_actions(&mut host, &[
+        (&carol(), b":25000000:0:"),
+        (&eve(), b":0:20:dead"),
+    ]);
+    host.advance(VOTING_DELAY + 1);
+    call_with_id(&mut host, &alice(), &id);
+    assert_eq!(exports::cast_vote(&mut host, VOTE_FOR), SUCCESS);
+    host.advance(VOTING_PERIOD);
+    call_with_id(&mut host, &bob(), &id);
+    assert_eq!(exports::queue(&mut host), SUCCESS);
+    host.advance(TIMELOCK_MIN_DELAY);
+
+    // Nothing is emitted before execution; the TrustSet is rejected by the
+    // host after the payment went out
+    host.failing_tx_type = Some(20);
+    call_with_id(&mut host, &dave(), &id);
+    assert_eq!(exports::execute(&mut host), SUCCESS);
+
+    let emitted = host.emitted.as_slice();
+    assert_eq!(emitted.len(), 1);
+    assert_eq!((emitted[0].target, emitted[0].amount), (carol(), 25_000_000));
+
+    let (state, len) = host.state();
+    assert_eq!(Actions::result(&state[..len], 0, 0), Some(SUCCESS));
+    assert_eq!(Actions::result(&state[..len], 0, 1), Some(ERR_HOST_CALL));
+    assert_eq!(Proposal::load(&state[..len], 0).unwrap().state, PROPOSAL_STATE_EXECUTED);
+    assert_invariants(&state[..len]);
+
+    // A second execute emits nothing
+    call_with_id(&mut host, &dave(), &id);
+    assert!(exports::execute(&mut host) < 0);
+    assert_eq!(host.emitted.count, 0);
+}
+
+#[test]
+fn test_execute_records_unsupported_action_type() {
+    let mut host = genesis_host();
+    let id = propose_actions(&mut host, &[
+        (&bob(), b":1000000:0:"),
+        (&carol(), b":1000000:1:"),
+    ]);
+    host.advance(VOTING_DELAY + 1);
+    call_with_id(&mut host, &alice(), &id);
+    assert_eq!(exports::cast_vote(&mut host, VOTE_FOR), SUCCESS);
+    host.advance(VOTING_PERIOD);
+    call_with_id(&mut host, &bob(), &id);
+    assert_eq!(exports::queue(&mut host), SUCCESS);
+    host.advance(TIMELOCK_MIN_DELAY);
+
+    // EscrowCreate is not an emittable type
+    call_with_id(&mut host, &dave(), &id);
+    assert_eq!(exports::execute(&mut host), SUCCESS);
+    assert_eq!(host.emitted.as_slice().len(), 1);
+
+    let (state, len) = host.state();
+    assert_eq!(Actions::result(&state[..len], 0, 0), Some(SUCCESS));
+    assert_eq!(Actions::result(&state[..len], 0, 1), Some(ERR_EMIT_UNSUPPORTED));
+    assert_invariants(&state[..len]);
+}
+
+#[test]
+fn test_execute_fails_closed_when_first_action_is_rejected() {
+    let mut host = genesis_host();
+    let id = propose_actions(&mut host, &[
+        (&eve(), b":0:20:dead"),
+        (&carol(), b":25000000:0:"),
+    ]);
+    host.advance(VOTING_DELAY + 1);
+    call_with_id(&mut host, &alice(), &id);
+    assert_eq!(exports::cast_vote(&mut host, VOTE_FOR), SUCCESS);
+    host.advance(VOTING_PERIOD);
+    call_with_id(&mut host, &bob(), &id);
+    assert_eq!(exports::queue(&mut host), SUCCESS);
+    host.advance(TIMELOCK_MIN_DELAY);
+
+    // Nothing went out, so nothing is written: still Queued and Ready
+    let (before, before_len) = host.state();
+    host.failing_tx_type = Some(20);
+    call_with_id(&mut host, &dave(), &id);
+    assert_eq!(exports::execute(&mut host), ERR_HOST_CALL);
+    assert_eq!(host.emitted.count, 0);
+    let (after, after_len) = host.state();
+    assert_eq!(&after[..after_len], &before[..before_len]);
+
+    // Retried once the host accepts it
+    host.failing_tx_type = None;
+    call_with_id(&mut host, &dave(), &id);
+    assert_eq!(exports::execute(&mut host), SUCCESS);
+    assert_eq!(host.emitted.count, 2);
+}
+
+/// Pad the Data field with a filler entry until it is within a few
+/// bytes of FIELD_CAPACITY as stored.
+fn fill_data_field(host: &mut MockHost) {
+    let (state, len) = host.state();
+    let filler = [b'x'; FIELD_CAPACITY];
+    let mut pad = FIELD_CAPACITY - host.data_len;
+    loop {
+        let mut store = Store::load(&state[..len]);
+        let fits = store.set(b"pad", &filler[..pad]).is_ok();
+        #[cfg(feature = "compact-state")]
+        let fits = fits && crate::foundation::codec::encode(store.as_bytes(), &mut [0u8; FIELD_CAPACITY]).is_ok();
+        if fits {
+            let padded = MockHost::with_state(store.as_bytes());
+            host.data = padded.data;
+            host.data_len = padded.data_len;
+            return;
+        }
+        pad -= 1;
+    }
+}
+
+#[test]
+fn test_execute_without_room_for_results_emits_nothing() {
+    let mut host = genesis_host();
+    let id = succeeded_proposal(&mut host);
+    call_with_id(&mut host, &bob(), &id);
+    assert_eq!(exports::queue(&mut host), SUCCESS);
+    host.advance(TIMELOCK_MIN_DELAY);
+
+    fill_data_field(&mut host);
+    let writes = host.writes;
+    call_with_id(&mut host, &dave(), &id);
+    assert_eq!(exports::execute(&mut host), ERR_DATA_FULL);
+    assert_eq!(host.emitted.count, 0);
+    assert_eq!(host.writes, writes);
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Parameters and membership
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_missing_memo_is_bad_param() {
+    let mut host = genesis_host();
+    host.call_as(&alice());
+    assert_eq!(exports::propose(&mut host), ERR_BAD_PARAM);
+    assert_eq!(exports::add_member(&mut host), ERR_BAD_PARAM);
+    assert_eq!(exports::delegate_votes(&mut host), ERR_BAD_PARAM);
+    assert_eq!(exports::cast_vote(&mut host, VOTE_FOR), ERR_BAD_PARAM);
+    assert_eq!(exports::cancel(&mut host), ERR_BAD_PARAM);
+}
+
+#[test]
+fn test_unknown_proposal_id_not_found() {
+    let mut host = genesis_host();
+    let id = propose(&mut host);
+    let mut other = id;
+    other[0] ^= 1;
+    call_with_id(&mut host, &bob(), &other);
+    assert_eq!(exports::queue(&mut host), ERR_PROPOSAL_NOT_FOUND);
+    call_with_id(&mut host, &alice(), &other);
+    assert_eq!(exports::cancel(&mut host), ERR_PROPOSAL_NOT_FOUND);
+    call_with_id(&mut host, &alice(), &id);
+    assert_eq!(exports::cancel(&mut host), SUCCESS);
+}
+
+#[test]
+fn test_propose_requires_actions() {
+    let mut host = genesis_host();
+    let mut memo = [0u8; 1024];
+    let mut len = 0;
+    push(&mut memo, &mut len, b"actions=");
+    encode_hex(&carol(), &mut memo[len..len + 40]);
+    len += 40;
+    push(&mut memo, &mut len, b":1000000:0:");
+
+    // Actions without a description memo, and a description without actions
+    host.call_as(&alice()).with_memo(&memo[..len]);
+    assert_eq!(exports::propose(&mut host), ERR_BAD_PARAM);
+    host.call_as(&alice()).with_memo(b"").with_memo(DESCRIPTION);
+    assert_eq!(exports::propose(&mut host), ERR_BAD_PARAM);
+
+    let id = propose(&mut host);
+    let (state, state_len) = host.state();
+    let actions = Actions::load(&state[..state_len], 0).unwrap();
+    assert_eq!(actions.count, 1);
+    assert_eq!((actions.items[0].target, actions.items[0].amount), (carol(), 1_000_000));
+    assert_eq!(Proposal::load(&state[..state_len], 0).unwrap().id, id);
+}
+
+#[test]
+fn test_redelegating_after_voting_does_not_count_twice() {
+    let mut host = genesis_host();
+    let id = propose(&mut host);
+    host.advance(VOTING_DELAY + 1);
+    call_with_id(&mut host, &alice(), &id);
+    assert_eq!(exports::cast_vote(&mut host, VOTE_FOR), SUCCESS);
+
+    // alice hands her 1000 XRP to bob, who then votes with his own 500 only
+    let mut memo = [0u8; 1024];
+    let mut len = 0;
+    push_account(&mut memo, &mut len, b"delegatee", &bob());
+    host.call_as(&alice()).with_memo(&memo[..len]);
+    assert_eq!(exports::delegate_votes(&mut host), SUCCESS);
+    call_with_id(&mut host, &bob(), &id);
+    assert_eq!(exports::cast_vote(&mut host, VOTE_FOR), SUCCESS);
+
+    let (state, len) = host.state();
+    let proposal = Proposal::load(&state[..len], 0).unwrap();
+    assert_eq!(proposal.for_votes, 1_500_000_000);
+    assert_invariants(&state[..len]);
+}
+
+#[test]
+fn test_get_past_votes() {
+    let mut host = genesis_host();
+    let mut memo = [0u8; 1024];
+    let mut len = 0;
+    push_account(&mut memo, &mut len, b"delegatee", &bob());
+    host.advance(100);
+    host.call_as(&alice()).with_memo(&memo[..len]);
+    assert_eq!(exports::delegate_votes(&mut host), SUCCESS);
+
+    let writes = host.writes;
+    let mut len = 0;
+    push_account(&mut memo, &mut len, b"account", &bob());
+    for (time, votes) in [(GENESIS_TIME - 1, 0), (GENESIS_TIME, 500_000_000), (GENESIS_TIME + 100, 1_500_000_000)] {
+        host.call_as(&eve()).with_memo(&memo[..len]);
+        assert_eq!(exports::get_past_votes(&mut host, time), votes);
+    }
+    assert_eq!(exports::get_past_total_supply(&mut host, GENESIS_TIME + 100), 1_500_000_000);
+    host.call_as(&eve());
+    assert_eq!(exports::get_past_votes(&mut host, GENESIS_TIME), ERR_BAD_PARAM as i64);
+    assert_eq!(host.writes, writes);
+}
+
+#[test]
+fn test_membership_change_after_vote_keeps_outcome() {
+    let mut host = genesis_host();
+    let id = succeeded_proposal(&mut host);
+    let (state, len) = host.state();
+    let proposal = Proposal::load(&state[..len], 0).unwrap();
+    assert_eq!((proposal.total_supply, proposal.quorum), (1_500_000_000, 60_000_000));
+
+    // Making dave a whale would push a live 4% quorum past alice's 1000 XRP
+    let mut memo = [0u8; 1024];
+    let mut len = 0;
+    push_account(&mut memo, &mut len, b"account", &dave());
+    push(&mut memo, &mut len, b";power=100000000000;roles=2");
+    host.call_as(&alice()).with_memo(&memo[..len]);
+    assert_eq!(exports::add_member(&mut host), SUCCESS);
+
+    call_with_id(&mut host, &bob(), &id);
+    assert_eq!(exports::queue(&mut host), SUCCESS);
+}
+
+#[test]
+fn test_verify_description() {
+    use crate::crypto::hash::hash_description;
+
+    let mut host = genesis_host();
+    let id = propose(&mut host);
+    let (state, state_len) = host.state();
+    let stored = find_value(&state[..state_len], b"prop_0_desc").and_then(crate::foundation::abi::decode_hash);
+    assert_eq!(stored, Some(hash_description(DESCRIPTION)));
+
+    let writes = host.writes;
+    call_with_id(&mut host, &eve(), &id).with_memo(DESCRIPTION);
+    assert_eq!(exports::verify_description(&mut host), SUCCESS);
+    call_with_id(&mut host, &eve(), &id).with_memo(b"Pay carol 100 XRP for the audit");
+    assert_eq!(exports::verify_description(&mut host), ERR_DESCRIPTION_MISMATCH);
+    call_with_id(&mut host, &eve(), &id);
+    assert_eq!(exports::verify_description(&mut host), ERR_BAD_PARAM);
+    assert_eq!(host.writes, writes);
+}
+
+#[test]
+fn test_add_and_remove_member() {
+    let mut host = genesis_host();
+    let mut memo = [0u8; 1024];
+    let mut len = 0;
+    push_account(&mut memo, &mut len, b"account", &dave());
+    host.call_as(&alice()).with_memo(&memo[..len]);
+    assert_eq!(exports::remove_member(&mut host), SUCCESS);
+    assert_eq!(value(&host, b"member_count"), Some(2));
+
+    let mut memo = [0u8; 1024];
+    let mut len = 0;
+    push_account(&mut memo, &mut len, b"account", &carol());
+    push(&mut memo, &mut len, b";power=250;roles=1");
+
+    host.call_as(&bob()).with_memo(&memo[..len]);
+    assert_eq!(exports::add_member(&mut host), ERR_NOT_ADMIN);
+    host.call_as(&alice()).with_memo(&memo[..len]);
+    assert_eq!(exports::add_member(&mut host), SUCCESS);
+    assert_eq!(value(&host, b"member_count"), Some(3));
+    let (state, state_len) = host.state();
+    assert_eq!(votes::get_votes(&state[..state_len], &carol()), 250);
+    assert_invariants(&state[..state_len]);
+}
+
+#[test]
+fn test_delegate_and_get_setting() {
+    let mut host = genesis_host();
+    let mut memo = [0u8; 1024];
+    let mut len = 0;
+    push_account(&mut memo, &mut len, b"delegatee", &alice());
+    host.call_as(&bob()).with_memo(&memo[..len]);
+    assert_eq!(exports::delegate_votes(&mut host), SUCCESS);
+    let (state, state_len) = host.state();
+    assert_eq!(
+        crate::token::xrp_votes::get_effective_votes(&state[..state_len], &alice()),
+        1_500_000_000,
+    );
+
+    assert_eq!(exports::get_setting(&mut host, SETTING_QUORUM_PERCENTAGE as u32), QUORUM_PERCENTAGE as i64);
+    assert_eq!(exports::get_setting(&mut host, 99), ERR_BAD_CONFIG as i64);
+}
+
+#[test]
+fn test_setting_change_proposal_applies_on_execute() {
+    let mut host = genesis_host();
+    // Raise the quorum to 10 % (setting 3) alongside a payment
+    let id = propose_actions(&mut host, &[
+        (&carol(), b":1000000:0:"),
+        (&[0u8; ACCOUNT_ID_SIZE], b":10:65535:03"),
+    ]);
+    host.advance(VOTING_DELAY + 1);
+    call_with_id(&mut host, &alice(), &id);
+    assert_eq!(exports::cast_vote(&mut host, VOTE_FOR), SUCCESS);
+    host.advance(VOTING_PERIOD);
+    call_with_id(&mut host, &bob(), &id);
+    assert_eq!(exports::queue(&mut host), SUCCESS);
+    assert_eq!(exports::get_setting(&mut host, SETTING_QUORUM_PERCENTAGE as u32), QUORUM_PERCENTAGE as i64);
+    host.advance(TIMELOCK_MIN_DELAY);
+
+    call_with_id(&mut host, &dave(), &id);
+    assert_eq!(exports::execute(&mut host), SUCCESS);
+    assert_eq!(exports::get_setting(&mut host, SETTING_QUORUM_PERCENTAGE as u32), 10);
+
+    // Only the payment reaches the host; both outcomes are recorded
+    assert_eq!(host.emitted.count, 1);
+    let (state, len) = host.state();
+    assert_eq!(Actions::result(&state[..len], 0, 0), Some(SUCCESS));
+    assert_eq!(Actions::result(&state[..len], 0, 1), Some(SUCCESS));
+    assert_eq!(value(&host, b"cfg_quorum"), Some(10));
+    assert_eq!(find_value(&state[..len], b"_exec"), None);
+    assert_invariants(&state[..len]);
+}
+
+#[test]
+fn test_outdated_schema_needs_migrate() {
+    // Hand-built state from before schema versioning
+    let (data, len) = build_dao_data(&[(&alice(), 1_000_000_000, ROLE_PROPOSER | ROLE_ADMIN)]);
+    let mut host = MockHost::with_state(&data[..len]);
+    host.call_as(&alice());
+    assert_eq!(exports::audit(&mut host), ERR_SCHEMA_OUTDATED);
+
+    host.call_as(&eve());
+    assert_eq!(exports::migrate(&mut host), SUCCESS);
+    assert_eq!(exports::audit(&mut host), SUCCESS);
+}
@@ -0,0 +1,120 @@
+use crate::foundation::abi::*;
+use crate::foundation::config::*;
+use crate::crypto::hex::encode_hex;
+use crate::tests::*;
+
+fn hex40(account: &[u8; ACCOUNT_ID_SIZE]) -> [u8; 40] {
+    let mut out = [0u8; 40];
+    encode_hex(account, &mut out);
+    out
+}
+
+#[test]
+fn test_decode_account() {
+    assert_eq!(decode_account(&hex40(&alice())), Some(alice()));
+    assert_eq!(decode_account(&hex40(&alice())[..38]), None);
+    assert_eq!(decode_account(b"zz00000000000000000000000000000000000000"), None);
+}
+
+#[test]
+fn test_decode_amount() {
+    assert_eq!(decode_amount(b"0"), Some(0));
+    assert_eq!(decode_amount(b"100000000"), Some(100_000_000));
+    assert_eq!(decode_amount(b"100000000000000000"), Some(MAX_XRP_DROPS));
+    assert_eq!(decode_amount(b"100000000000000001"), None);
+    assert_eq!(decode_amount(b"1.5"), None);
+    assert_eq!(decode_amount(b""), None);
+}
+
+#[test]
+fn test_decode_hash() {
+    let hex = [b'a'; 64];
+    assert_eq!(decode_hash(&hex), Some([0xaa; HASH_SIZE]));
+    assert_eq!(decode_hash(&hex[..62]), None);
+}
+
+/// `<account hex>` followed by `rest`.
+fn action_text(account: &[u8; ACCOUNT_ID_SIZE], rest: &[u8]) -> ([u8; 256], usize) {
+    let mut out = [0u8; 256];
+    out[..40].copy_from_slice(&hex40(account));
+    out[40..40 + rest.len()].copy_from_slice(rest);
+    (out, 40 + rest.len())
+}
+
+#[test]
+fn test_decode_action() {
+    let (text, len) = action_text(&bob(), b":5000000:0:cafe");
+    let action = decode_action(&text[..len]).unwrap();
+    assert_eq!(action.target, bob());
+    assert_eq!(action.amount, 5_000_000);
+    assert_eq!(action.tx_type, 0);
+    assert_eq!(action.payload(), &[0xca, 0xfe]);
+
+    // Empty payload is allowed
+    let (text, len) = action_text(&bob(), b":1:20:");
+    assert_eq!(decode_action(&text[..len]).map(|a| a.payload_len), Some(0));
+
+    // Missing field, extra field, tx type beyond u16, odd payload hex
+    for rest in [b":1:20" as &[u8], b":1:20:ab:ab", b":1:70000:ab", b":1:0:abc"] {
+        let (text, len) = action_text(&bob(), rest);
+        assert!(decode_action(&text[..len]).is_none());
+    }
+}
+
+#[test]
+fn test_decode_action_payload_limit() {
+    let mut text = [b'0'; 40 + 5 + MAX_ACTION_PAYLOAD * 2 + 2];
+    text[40..45].copy_from_slice(b":1:0:");
+    let at_limit = 45 + MAX_ACTION_PAYLOAD * 2;
+    assert_eq!(decode_action(&text[..at_limit]).map(|a| a.payload_len as usize), Some(MAX_ACTION_PAYLOAD));
+    assert!(decode_action(&text[..at_limit + 2]).is_none());
+}
+
+#[test]
+fn test_decode_actions() {
+    let (one, one_len) = action_text(&carol(), b":7:0:");
+    let mut list = [0u8; 46 * (MAX_BATCH_OPS + 1)];
+    let mut len = 0;
+    for i in 0..=MAX_BATCH_OPS {
+        if i > 0 {
+            list[len] = b',';
+            len += 1;
+        }
+        list[len..len + one_len].copy_from_slice(&one[..one_len]);
+        len += one_len;
+        if i + 1 == MAX_BATCH_OPS {
+            let actions = decode_actions(&list[..len]).unwrap();
+            assert_eq!(actions.count as usize, MAX_BATCH_OPS);
+            assert!(actions.as_slice().iter().all(|a| a.target == carol() && a.amount == 7));
+        }
+    }
+    // One past MAX_BATCH_OPS, and an empty list
+    assert_eq!(decode_actions(&list[..len]).err(), Some(ERR_BAD_PARAM));
+    assert_eq!(decode_actions(b"").err(), Some(ERR_BAD_PARAM));
+}
+
+#[test]
+fn test_params() {
+    let mut raw = [0u8; 256];
+    let mut len = 0;
+    for part in [b"delegatee=" as &[u8], &hex40(&dave()), b";power=250;roles=5;desc=", &[b'0'; 64]] {
+        raw[len..len + part.len()].copy_from_slice(part);
+        len += part.len();
+    }
+    let params = Params::new(&raw[..len]);
+
+    assert!(params.has(b"power"));
+    assert!(!params.has(b"actions"));
+    assert_eq!(params.account(b"delegatee"), Ok(dave()));
+    assert_eq!(params.amount(b"power"), Ok(250));
+    assert_eq!(params.roles(b"roles"), Ok(ROLE_PROPOSER | ROLE_ADMIN));
+    assert_eq!(params.hash(b"desc"), Ok([0u8; HASH_SIZE]));
+    assert_eq!(params.u32(b"power"), Ok(250));
+
+    // Missing and mistyped values
+    assert_eq!(params.account(b"account"), Err(ERR_BAD_PARAM));
+    assert_eq!(params.account(b"power"), Err(ERR_BAD_PARAM));
+    assert_eq!(params.actions(b"actions").err(), Some(ERR_BAD_PARAM));
+    assert_eq!(Params::new(b"roles=16").roles(b"roles"), Err(ERR_BAD_PARAM));
+    assert_eq!(Params::new(b"").raw(b"desc"), Err(ERR_BAD_PARAM));
+}
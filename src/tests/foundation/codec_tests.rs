@@ -0,0 +1,208 @@
+use crate::foundation::codec::*;
+use crate::foundation::config::*;
+use crate::foundation::data::*;
+use crate::crypto::hex::encode_hex;
+use crate::governance::governor::{build_prop_key, format_u64};
+use crate::tests::*;
+
+/// Encode then decode `text`, asserting it comes back byte for byte.
+/// Returns the encoded size.
+fn assert_round_trip(text: &[u8]) -> usize {
+    let mut blob = [0u8; DATA_CAPACITY];
+    let blen = encode(text, &mut blob).unwrap();
+    let mut back = [0u8; DATA_CAPACITY];
+    let tlen = decode(&blob[..blen], &mut back).unwrap();
+    assert_eq!(&back[..tlen], text);
+    blen
+}
+
+/// Build a DAO at the compact-state limits: MAX_MEMBERS members and
+/// MAX_PROPOSALS proposals, each with three votes.
+fn build_full_dao() -> ([u8; DATA_CAPACITY], usize) {
+    let mut store = Store::new();
+    let mut key = [0u8; 32];
+    let mut val = [0u8; 80];
+    let mut num = [0u8; 20];
+
+    let nlen = format_u64(MAX_MEMBERS as u64, &mut num);
+    store.set(b"member_count", &num[..nlen]).unwrap();
+    for i in 0..MAX_MEMBERS {
+        let klen = build_prop_key(b"member_", i as u16, b"", &mut key);
+        encode_hex(&mock_account(i as u8 + 1), &mut val);
+        val[40..51].copy_from_slice(b":100000000:");
+        val[51] = b'1';
+        store.insert(&key[..klen], &val[..52]).unwrap();
+    }
+
+    let nlen = format_u64(MAX_PROPOSALS as u64, &mut num);
+    store.set(b"proposal_count", &num[..nlen]).unwrap();
+    for p in 0..MAX_PROPOSALS as u8 {
+        let fields: [(&[u8], &[u8]); 9] = [
+            (b"_id", b"2654435769"), (b"_proposer", b""), (b"_state", b"1"),
+            (b"_start", b"1700000300"), (b"_end", b"1700259500"),
+            (b"_for", b"300000000"), (b"_against", b"100000000"),
+            (b"_abstain", b"0"), (b"_desc", b"3141592653"),
+        ];
+        for (suffix, value) in fields {
+            let klen = build_prop_key(b"prop_", p as u16, suffix, &mut key);
+            if suffix == b"_proposer" {
+                encode_hex(&mock_account(p + 1), &mut val);
+                store.insert(&key[..klen], &val[..40]).unwrap();
+            } else {
+                store.insert(&key[..klen], value).unwrap();
+            }
+        }
+        for v in 0..3u8 {
+            let mut vk = [0u8; 32];
+            let klen = build_prop_key(b"vote_", p as u16, b"_", &mut vk);
+            let nlen = crate::governance::governor::format_u8(v, &mut vk[klen..]);
+            encode_hex(&mock_account(v + 1), &mut val);
+            val[40..52].copy_from_slice(b":1:100000000");
+            store.insert(&vk[..klen + nlen], &val[..52]).unwrap();
+        }
+    }
+    store.commit()
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Round trip
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_round_trip_empty() {
+    let mut blob = [0u8; 8];
+    assert_eq!(encode(b"", &mut blob), Ok(2));
+    assert!(is_compact(&blob[..2]));
+    assert_round_trip(b"");
+}
+
+#[test]
+fn test_round_trip_dao_with_proposal() {
+    let members = [(&alice(), 500_000_000u64, 7u8), (&bob(), 300_000_000, 1), (&carol(), 200_000_000, 1)];
+    let (data, len) = build_dao_with_proposal(&members, 42, &alice(), 1000, 2000, 1);
+    assert_round_trip(&data[..len]);
+}
+
+#[test]
+fn test_round_trip_full_dao() {
+    let (data, len) = build_full_dao();
+    assert_round_trip(&data[..len]);
+}
+
+#[test]
+fn test_round_trip_irregular_text() {
+    // Leading zeros, uppercase hex, odd/oversized hex runs, numbers past
+    // u64, unknown keys and stray separators all fall back losslessly.
+    let cases: [&[u8]; 11] = [
+        b"a=007;b=0;c=00",
+        b"r=100000000;s=18440000000000000000;t=1000",
+        b"k=AABBCCDDEEFF00112233445566778899AABBCCDD",
+        b"k=aabbccddeeff00112233445566778899aabbccd",
+        b"k=aabbccddeeff00112233445566778899aabbccddee",
+        b"n=18446744073709551615;m=18446744073709551616",
+        b"weird key=some value;;=;x",
+        b"prop_=_;member_countx=1",
+        b"h=0000000000000000000000000000000000000000",
+        b"deadbeef=cafe;delegate_=face",
+        b"\x00\x7f\xff=\xc5\x01",
+    ];
+    for case in cases {
+        assert_round_trip(case);
+    }
+}
+
+#[test]
+fn test_reencode_is_stable() {
+    let (data, len) = build_full_dao();
+    let mut blob = [0u8; DATA_CAPACITY];
+    let blen = encode(&data[..len], &mut blob).unwrap();
+    let mut text = [0u8; DATA_CAPACITY];
+    let tlen = decode(&blob[..blen], &mut text).unwrap();
+    let mut blob2 = [0u8; DATA_CAPACITY];
+    let blen2 = encode(&text[..tlen], &mut blob2).unwrap();
+    assert_eq!(&blob[..blen], &blob2[..blen2]);
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Size
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_account_encodes_to_20_bytes() {
+    let mut text = [0u8; 40];
+    encode_hex(&alice(), &mut text);
+    let mut blob = [0u8; 64];
+    // MAGIC + VERSION + TAG + 20 raw bytes
+    assert_eq!(encode(&text, &mut blob), Ok(23));
+}
+
+#[test]
+fn test_encoding_is_under_forty_percent_of_text() {
+    let (data, len) = build_full_dao();
+    let blen = assert_round_trip(&data[..len]);
+    assert!(blen * 5 <= len * 2, "encoded {} vs text {}", blen, len);
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Errors and legacy data
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_repeated_account_is_back_referenced() {
+    let mut text = [0u8; 81];
+    encode_hex(&alice(), &mut text[..40]);
+    text[40] = b':';
+    encode_hex(&alice(), &mut text[41..]);
+    let mut blob = [0u8; 64];
+    // MAGIC + VERSION + ACCOUNT(21) + ':' + ACCOUNT_REF(2)
+    assert_eq!(encode(&text, &mut blob), Ok(26));
+    assert_round_trip(&text);
+}
+
+#[test]
+fn test_encode_overflow_returns_data_full() {
+    let mut blob = [0u8; 8];
+    assert_eq!(encode(b"some=much;longer=text", &mut blob), Err(ERR_DATA_FULL));
+}
+
+#[test]
+fn test_decode_rejects_malformed_blobs() {
+    let mut out = [0u8; 64];
+    assert_eq!(decode(b"", &mut out), Err(ERR_DATA_READ));
+    assert_eq!(decode(b"a=1", &mut out), Err(ERR_DATA_READ));
+    assert_eq!(decode(&[MAGIC, VERSION + 1], &mut out), Err(ERR_DATA_READ));
+    // Truncated TEXT, ACCOUNT and varint payloads
+    assert_eq!(decode(&[MAGIC, VERSION, 0x01, 5, b'a'], &mut out), Err(ERR_DATA_READ));
+    assert_eq!(decode(&[MAGIC, VERSION, 0x03, 1, 2, 3], &mut out), Err(ERR_DATA_READ));
+    assert_eq!(decode(&[MAGIC, VERSION, 0x02, 0x80], &mut out), Err(ERR_DATA_READ));
+    // Back-reference to an account never seen, scaled number past u64
+    assert_eq!(decode(&[MAGIC, VERSION, 0x08, 0], &mut out), Err(ERR_DATA_READ));
+    assert_eq!(decode(&[MAGIC, VERSION, 0x09, 2, 20], &mut out), Err(ERR_DATA_READ));
+    // Unassigned tag and dictionary index past the table
+    assert_eq!(decode(&[MAGIC, VERSION, 0x10], &mut out), Err(ERR_DATA_READ));
+    assert_eq!(decode(&[MAGIC, VERSION, 0x7F], &mut out), Err(ERR_DATA_READ));
+}
+
+#[test]
+fn test_decode_overflow_returns_data_full() {
+    let mut text = [0u8; 40];
+    encode_hex(&alice(), &mut text);
+    let mut blob = [0u8; 64];
+    let blen = encode(&text, &mut blob).unwrap();
+    let mut out = [0u8; 39];
+    assert_eq!(decode(&blob[..blen], &mut out), Err(ERR_DATA_FULL));
+}
+
+#[test]
+fn test_to_text_passes_legacy_text_through() {
+    let (data, len) = build_dao_data(&[(&alice(), 100, 7)]);
+    let mut out = [0u8; DATA_CAPACITY];
+    let tlen = to_text(&data[..len], &mut out).unwrap();
+    assert_eq!(&out[..tlen], &data[..len]);
+
+    let mut blob = [0u8; FIELD_CAPACITY];
+    let blen = encode(&data[..len], &mut blob).unwrap();
+    let tlen = to_text(&blob[..blen], &mut out).unwrap();
+    assert_eq!(&out[..tlen], &data[..len]);
+}
+
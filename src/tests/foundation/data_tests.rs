@@ -1,3 +1,4 @@
+use crate::foundation::config::*;
 use crate::foundation::data::*;
 
 #[test]
@@ -26,18 +27,18 @@ fn test_find_value_single_entry() {
 }
 
 #[test]
-fn test_write_entry_basic() {
+fn test_try_write_entry_basic() {
     let mut buf = [0u8; 64];
-    let pos = write_entry(&mut buf, 0, b"key", b"val");
+    let pos = try_write_entry(&mut buf, 0, b"key", b"val").unwrap();
     assert_eq!(&buf[..pos], b"key=val");
 }
 
 #[test]
 fn test_write_separator() {
     let mut buf = [0u8; 64];
-    let pos = write_entry(&mut buf, 0, b"a", b"1");
+    let pos = try_write_entry(&mut buf, 0, b"a", b"1").unwrap();
     let pos = write_separator(&mut buf, pos);
-    let pos = write_entry(&mut buf, pos, b"b", b"2");
+    let pos = try_write_entry(&mut buf, pos, b"b", b"2").unwrap();
     assert_eq!(&buf[..pos], b"a=1;b=2");
 }
 
@@ -52,13 +53,307 @@ fn test_build_indexed_key() {
 fn test_round_trip_data() {
     let mut buf = [0u8; 128];
     let mut pos = 0;
-    pos = write_entry(&mut buf, pos, b"count", b"2");
+    pos = try_write_entry(&mut buf, pos, b"count", b"2").unwrap();
     pos = write_separator(&mut buf, pos);
-    pos = write_entry(&mut buf, pos, b"item_0", b"hello");
+    pos = try_write_entry(&mut buf, pos, b"item_0", b"hello").unwrap();
     pos = write_separator(&mut buf, pos);
-    pos = write_entry(&mut buf, pos, b"item_1", b"world");
+    pos = try_write_entry(&mut buf, pos, b"item_1", b"world").unwrap();
 
     assert_eq!(find_value(&buf[..pos], b"count"), Some(b"2" as &[u8]));
     assert_eq!(find_value(&buf[..pos], b"item_0"), Some(b"hello" as &[u8]));
     assert_eq!(find_value(&buf[..pos], b"item_1"), Some(b"world" as &[u8]));
 }
+
+// ═══════════════════════════════════════════════════════════════════════
+// Store tests
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_store_set_updates_in_place() {
+    let mut store = Store::load(b"a=1;b=2;c=3");
+    store.set(b"b", b"22").unwrap();
+    assert_eq!(store.as_bytes(), b"a=1;b=22;c=3");
+}
+
+#[test]
+fn test_store_set_appends_new_key() {
+    let mut store = Store::load(b"a=1");
+    store.set(b"b", b"2").unwrap();
+    assert_eq!(store.as_bytes(), b"a=1;b=2");
+
+    let mut empty = Store::new();
+    empty.set(b"x", b"9").unwrap();
+    assert_eq!(empty.as_bytes(), b"x=9");
+}
+
+#[test]
+fn test_store_insert_rejects_existing_key() {
+    let mut store = Store::load(b"a=1");
+    assert_eq!(store.insert(b"a", b"2"), Err(ERR_KEY_EXISTS));
+    assert_eq!(store.get(b"a"), Some(b"1" as &[u8]));
+    assert!(store.insert(b"b", b"2").is_ok());
+}
+
+#[test]
+fn test_store_remove_first_middle_last() {
+    let mut store = Store::load(b"a=1;b=2;c=3");
+    assert!(store.remove(b"b"));
+    assert_eq!(store.as_bytes(), b"a=1;c=3");
+    assert!(store.remove(b"c"));
+    assert_eq!(store.as_bytes(), b"a=1");
+    assert!(store.remove(b"a"));
+    assert!(store.is_empty());
+    assert!(!store.remove(b"a"));
+}
+
+#[test]
+fn test_store_rejects_framing_characters() {
+    let mut store = Store::new();
+    assert_eq!(store.set(b"a;b", b"1"), Err(ERR_INVALID_ENTRY));
+    assert_eq!(store.set(b"a=b", b"1"), Err(ERR_INVALID_ENTRY));
+    assert_eq!(store.set(b"", b"1"), Err(ERR_INVALID_ENTRY));
+    assert_eq!(store.set(b"a", b"1;evil=1"), Err(ERR_INVALID_ENTRY));
+    assert!(store.is_empty());
+}
+
+#[test]
+fn test_store_iter_prefix() {
+    let store = Store::load(b"member_count=2;member_0=x;prop_0_id=5;member_1=y");
+    let mut iter = store.iter_prefix(b"member_");
+    assert_eq!(iter.next(), Some((b"member_count" as &[u8], b"2" as &[u8])));
+    assert_eq!(iter.next(), Some((b"member_0" as &[u8], b"x" as &[u8])));
+    assert_eq!(iter.next(), Some((b"member_1" as &[u8], b"y" as &[u8])));
+    assert_eq!(iter.next(), None);
+}
+
+#[test]
+fn test_store_commit_round_trip() {
+    let mut store = Store::load(b"a=1");
+    store.set(b"b", b"2").unwrap();
+    let (buf, len) = store.commit();
+    assert_eq!(&buf[..len], b"a=1;b=2");
+    assert_eq!(find_value(&buf[..len], b"b"), Some(b"2" as &[u8]));
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Capacity tests
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_try_write_entry_reports_full() {
+    let mut buf = [0u8; 8];
+    assert_eq!(try_write_entry(&mut buf, 0, b"key", b"val"), Ok(7));
+    assert_eq!(try_write_entry(&mut buf, 7, b"k", b"v"), Err(ERR_DATA_FULL));
+}
+
+#[test]
+fn test_store_capacity_accounting() {
+    let mut store = Store::load(b"a=1");
+    assert_eq!(store.capacity(), DATA_CAPACITY);
+    assert_eq!(store.available(), DATA_CAPACITY - 3);
+    store.set(b"b", b"2").unwrap();
+    assert_eq!(store.available(), DATA_CAPACITY - 7);
+}
+
+#[test]
+fn test_store_rejects_write_past_capacity() {
+    let filler = [b'x'; DATA_CAPACITY - 2];
+    let mut store = Store::new();
+    store.set(b"f", &filler).unwrap();
+    assert_eq!(store.available(), 0);
+
+    // Neither a new entry nor a growing update fits — store left unchanged
+    assert_eq!(store.set(b"g", b"1"), Err(ERR_DATA_FULL));
+    assert_eq!(store.insert(b"g", b"1"), Err(ERR_DATA_FULL));
+    assert_eq!(store.set(b"f", &[b'y'; DATA_CAPACITY - 1]), Err(ERR_DATA_FULL));
+    assert_eq!(store.len(), DATA_CAPACITY);
+    assert_eq!(store.get(b"f"), Some(&filler[..]));
+
+    // Shrinking still works and frees space
+    store.set(b"f", b"short").unwrap();
+    assert_eq!(store.available(), DATA_CAPACITY - 7);
+}
+
+#[test]
+fn test_data_usage() {
+    assert_eq!(data_usage(b""), (0, FIELD_CAPACITY));
+    assert_eq!(data_usage(b"a=1;b=2"), (7, FIELD_CAPACITY - 7));
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Paged state
+// ═══════════════════════════════════════════════════════════════════════
+
+/// DAO text touching every page: members, a proposal with one vote,
+/// a scheduled op, a delegation and the lock.
+fn build_paged_dao() -> ([u8; DATA_CAPACITY], usize) {
+    use crate::tests::*;
+    let members = [(&alice(), 500_000_000u64, 7u8), (&bob(), 300_000_000, 1)];
+    let (data, len) = build_dao_with_proposal(&members, 42, &alice(), 100, 200, 1);
+    let (data, len) = crate::governance::counting::cast_vote(
+        &data[..len], len, 0, &bob(), 1, 300_000_000, 150,
+    ).unwrap();
+    let (data, len, _) = crate::timelock::controller::schedule(
+        &data[..len], len, &mock_id(42), 300, TIMELOCK_MIN_DELAY,
+    ).unwrap();
+    let (data, len) = crate::token::xrp_votes::delegate(&data[..len], len, &alice(), &bob(), 0).unwrap();
+    let mut store = Store::load(&data[..len]);
+    store.set(b"_lock", b"0").unwrap();
+    store.commit()
+}
+
+#[test]
+fn test_page_for_key() {
+    assert_eq!(Page::for_key(b"member_count"), Page::Members);
+    assert_eq!(Page::for_key(b"member_3"), Page::Members);
+    assert_eq!(Page::for_key(b"delegate_aa"), Page::Members);
+    assert_eq!(Page::for_key(b"ckpt_total"), Page::Members);
+    assert_eq!(Page::for_key(b"proposal_count"), Page::Proposals);
+    assert_eq!(Page::for_key(b"prop_0_state"), Page::Proposals);
+    assert_eq!(Page::for_key(b"hist_42"), Page::Proposals);
+    assert_eq!(Page::for_key(b"vote_0_1"), Page::Votes);
+    assert_eq!(Page::for_key(b"sigvote_42_aa"), Page::Votes);
+    assert_eq!(Page::for_key(b"snap_42_aa"), Page::Votes);
+    assert_eq!(Page::for_key(b"op_count"), Page::Operations);
+    assert_eq!(Page::for_key(b"op_0_ready"), Page::Operations);
+    assert_eq!(Page::for_key(b"_lock"), Page::Directory);
+    assert_eq!(Page::for_key(b"page_members"), Page::Directory);
+}
+
+#[test]
+fn test_save_and_load_all_pages_round_trip() {
+    let (data, len) = build_paged_dao();
+    let mut slots = MemorySlots::new();
+    save_pages(&mut slots, &data[..len], &Page::ALL).unwrap();
+
+    // Each family landed in its own slot
+    assert!(find_value(slots.slot(1), b"member_0").is_some());
+    assert!(find_value(slots.slot(2), b"prop_0_state").is_some());
+    assert!(find_value(slots.slot(3), b"vote_0_0").is_some());
+    assert!(find_value(slots.slot(4), b"op_0_id").is_some());
+    assert_eq!(find_value(slots.slot(0), b"_lock"), Some(b"0" as &[u8]));
+    assert_eq!(find_value(slots.slot(0), b"page_votes"), Some(b"3" as &[u8]));
+
+    let store = load_pages(&slots, &Page::ALL).unwrap();
+    for (key, value) in iter_prefix(&data[..len], b"") {
+        assert_eq!(store.get(key), Some(value));
+    }
+}
+
+#[test]
+fn test_load_reads_only_requested_pages() {
+    let (data, len) = build_paged_dao();
+    let mut slots = MemorySlots::new();
+    save_pages(&mut slots, &data[..len], &Page::ALL).unwrap();
+
+    let store = load_pages(&slots, &[Page::Proposals]).unwrap();
+    let id = store.get(b"prop_0_id").and_then(crate::foundation::abi::decode_hash);
+    assert_eq!(id, Some(crate::tests::mock_id(42)));
+    assert_eq!(store.get(b"_lock"), Some(b"0" as &[u8]));
+    assert_eq!(store.get(b"member_0"), None);
+    assert_eq!(store.get(b"vote_0_0"), None);
+    assert_eq!(store.get(b"op_count"), None);
+}
+
+#[test]
+fn test_save_writes_only_changed_pages() {
+    let (data, len) = build_paged_dao();
+    let mut slots = MemorySlots::new();
+    save_pages(&mut slots, &data[..len], &Page::ALL).unwrap();
+
+    let loaded = [Page::Members, Page::Proposals];
+    let store = load_pages(&slots, &loaded).unwrap();
+    let (new_data, new_len) = crate::governance::governor::update_proposal_field(
+        store.as_bytes(), store.len(), 0, b"_state", b"2",
+    ).unwrap();
+    save_pages(&mut slots, &new_data[..new_len], &loaded).unwrap();
+
+    assert_eq!(slots.write_count(2), 2);
+    assert_eq!(slots.write_count(0), 1);
+    assert_eq!(slots.write_count(1), 1);
+    assert_eq!(slots.write_count(3), 1);
+    assert_eq!(find_value(slots.slot(2), b"prop_0_state"), Some(b"2" as &[u8]));
+    // Untouched pages keep their contents
+    assert!(find_value(slots.slot(3), b"vote_0_0").is_some());
+}
+
+#[test]
+fn test_save_rejects_key_from_unloaded_page() {
+    let mut slots = MemorySlots::new();
+    save_pages(&mut slots, b"member_count=0;prop_0_state=1", &[Page::Members, Page::Proposals]).unwrap();
+
+    assert_eq!(
+        save_pages(&mut slots, b"member_count=1;op_count=1", &[Page::Members]),
+        Err(ERR_PAGE_NOT_LOADED),
+    );
+    // Nothing was written
+    assert_eq!(find_value(slots.slot(1), b"member_count"), Some(b"0" as &[u8]));
+    assert_eq!(slots.write_count(1), 1);
+}
+
+#[test]
+fn test_directory_relocates_page() {
+    let mut slots = MemorySlots::new();
+    slots.write_slot(0, b"page_votes=6").unwrap();
+    slots.write_slot(6, b"vote_0_0=aa:1:5").unwrap();
+
+    let store = load_pages(&slots, &[Page::Votes]).unwrap();
+    assert_eq!(store.get(b"vote_0_0"), Some(b"aa:1:5" as &[u8]));
+
+    save_pages(&mut slots, b"page_votes=6;vote_0_0=aa:1:5;vote_0_1=bb:0:3", &[Page::Votes]).unwrap();
+    assert!(find_value(slots.slot(6), b"vote_0_1").is_some());
+    assert_eq!(slots.slot(3), b"");
+}
+
+#[test]
+fn test_save_checks_every_slot_before_writing() {
+    let mut slots = MemorySlots::new();
+    save_pages(&mut slots, b"member_count=0;vote_0_0=aa:1:5", &[Page::Members, Page::Votes]).unwrap();
+
+    // Members (slot 1) changes, but the votes page points past MAX_SLOTS
+    assert_eq!(
+        save_pages(&mut slots, b"page_votes=9;member_count=1;vote_0_0=aa:1:5", &[Page::Members, Page::Votes]),
+        Err(ERR_HOST_CALL),
+    );
+    // Two pages in one slot would clobber each other
+    assert_eq!(
+        save_pages(&mut slots, b"page_votes=1;member_count=1;vote_0_0=aa:1:5", &[Page::Members, Page::Votes]),
+        Err(ERR_INVALID_ENTRY),
+    );
+    assert_eq!(find_value(slots.slot(1), b"member_count"), Some(b"0" as &[u8]));
+    assert_eq!(slots.write_count(0), 1);
+    assert_eq!(slots.write_count(1), 1);
+}
+
+#[test]
+fn test_pages_hold_more_than_one_field() {
+    // Two pages each near capacity: more than a single Data field could hold
+    let mut member_page = Store::new();
+    let mut prop_page = Store::new();
+    let filler = [b'x'; 2000];
+    member_page.set(b"member_0", &filler).unwrap();
+    member_page.set(b"member_1", &filler).unwrap();
+    prop_page.set(b"prop_0_desc", &filler).unwrap();
+    prop_page.set(b"prop_1_desc", &filler).unwrap();
+
+    let mut slots = MemorySlots::new();
+    save_pages(&mut slots, member_page.as_bytes(), &[Page::Members]).unwrap();
+    save_pages(&mut slots, prop_page.as_bytes(), &[Page::Proposals]).unwrap();
+
+    assert!(slots.slot(1).len() + slots.slot(2).len() > FIELD_CAPACITY);
+    assert_eq!(load_pages(&slots, &[Page::Members]).unwrap().get(b"member_1"), Some(&filler[..]));
+    assert_eq!(load_pages(&slots, &[Page::Proposals]).unwrap().get(b"prop_1_desc"), Some(&filler[..]));
+}
+
+#[test]
+fn test_save_rejects_page_overflow() {
+    let filler = [b'x'; 2100];
+    let mut text = [0u8; 8192];
+    let mut pos = try_write_entry(&mut text, 0, b"member_0", &filler).unwrap();
+    pos = write_separator(&mut text, pos);
+    pos = try_write_entry(&mut text, pos, b"member_1", &filler).unwrap();
+
+    let mut slots = MemorySlots::new();
+    assert_eq!(save_pages(&mut slots, &text[..pos], &[Page::Members]), Err(ERR_DATA_FULL));
+    assert_eq!(slots.write_count(0), 0);
+}
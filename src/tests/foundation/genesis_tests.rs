@@ -0,0 +1,184 @@
+use crate::foundation::config::*;
+use crate::foundation::data::*;
+use crate::foundation::genesis::*;
+use crate::foundation::migrate;
+use crate::foundation::types::{Actions, Member};
+use crate::crypto::hex::encode_hex;
+use crate::governance::{governor, votes};
+use crate::governance::settings::Settings;
+use crate::token::xrp_votes;
+use crate::tests::*;
+
+/// Spec text `admin=<alice>;` followed by `rest`.
+fn spec(rest: &[u8]) -> ([u8; 1024], usize) {
+    let mut out = [0u8; 1024];
+    out[..6].copy_from_slice(b"admin=");
+    encode_hex(&alice(), &mut out[6..46]);
+    let mut len = 46;
+    if !rest.is_empty() {
+        out[len] = b';';
+        out[len + 1..len + 1 + rest.len()].copy_from_slice(rest);
+        len += 1 + rest.len();
+    }
+    (out, len)
+}
+
+/// `member_N=<hex>:<power>:<roles>` for `account`.
+fn member_entry(index: u8, account: &[u8; ACCOUNT_ID_SIZE], power: &[u8], roles: u8) -> ([u8; 96], usize) {
+    let mut out = [0u8; 96];
+    let mut pos = 0;
+    for part in [b"member_" as &[u8], &[b'0' + index], b"="] {
+        out[pos..pos + part.len()].copy_from_slice(part);
+        pos += part.len();
+    }
+    encode_hex(account, &mut out[pos..pos + 40]);
+    pos += 40;
+    out[pos] = b':';
+    out[pos + 1..pos + 1 + power.len()].copy_from_slice(power);
+    pos += 1 + power.len();
+    out[pos] = b':';
+    let rlen = crate::foundation::parse::format_u32(roles as u32, &mut out[pos + 1..]);
+    (out, pos + 1 + rlen)
+}
+
+fn join(parts: &[&[u8]]) -> ([u8; 1024], usize) {
+    let mut out = [0u8; 1024];
+    let mut len = 0;
+    for part in parts {
+        if len > 0 {
+            out[len] = b';';
+            len += 1;
+        }
+        out[len..len + part.len()].copy_from_slice(part);
+        len += part.len();
+    }
+    (out, len)
+}
+
+#[test]
+fn test_parse_admin_only() {
+    let (s, len) = spec(b"");
+    let genesis = Genesis::parse(&s[..len]).unwrap();
+    assert_eq!(genesis.admin, alice());
+    assert_eq!(genesis.member_count, 1);
+    assert_eq!(genesis.members()[0].roles, ROLE_ADMIN);
+    assert_eq!(genesis.members()[0].voting_power, 0);
+    assert_eq!(genesis.settings, Settings::DEFAULT);
+}
+
+#[test]
+fn test_parse_members_and_settings() {
+    let (m0, l0) = member_entry(0, &bob(), b"200000000", ROLE_PROPOSER);
+    let (m1, l1) = member_entry(1, &alice(), b"100000000", ROLE_EXECUTOR);
+    let (rest, rlen) = join(&[&m0[..l0], &m1[..l1], b"cfg_quorum=10", b"cfg_voting_delay=60"]);
+    let (s, len) = spec(&rest[..rlen]);
+
+    let genesis = Genesis::parse(&s[..len]).unwrap();
+    assert_eq!(genesis.member_count, 2);
+    assert_eq!(genesis.members()[0].account, bob());
+    // The admin keeps its declared roles and gains ROLE_ADMIN
+    assert_eq!(genesis.members()[1].roles, ROLE_EXECUTOR | ROLE_ADMIN);
+    assert_eq!(genesis.settings.quorum_percentage, 10);
+    assert_eq!(genesis.settings.voting_delay, 60);
+}
+
+#[test]
+fn test_parse_rejects_bad_specs() {
+    // Missing or malformed admin
+    assert_eq!(Genesis::parse(b"cfg_quorum=10").err(), Some(ERR_INVALID_ENTRY));
+    assert_eq!(Genesis::parse(b"admin=zz").err(), Some(ERR_INVALID_ENTRY));
+
+    // Unknown key, unparseable or out-of-bounds setting, repeated setting
+    let (s, len) = spec(b"owner=1");
+    assert_eq!(Genesis::parse(&s[..len]).err(), Some(ERR_INVALID_ENTRY));
+    let (s, len) = spec(b"cfg_quorum=ten");
+    assert_eq!(Genesis::parse(&s[..len]).err(), Some(ERR_INVALID_ENTRY));
+    let (s, len) = spec(b"cfg_quorum=0");
+    assert_eq!(Genesis::parse(&s[..len]).err(), Some(ERR_BAD_CONFIG));
+    let (s, len) = spec(b"cfg_quorum=10;cfg_quorum=20");
+    assert_eq!(Genesis::parse(&s[..len]).err(), Some(ERR_INVALID_ENTRY));
+
+    // Gap in member numbering
+    let (m, l) = member_entry(1, &bob(), b"1", 0);
+    let (s, len) = spec(&m[..l]);
+    assert_eq!(Genesis::parse(&s[..len]).err(), Some(ERR_INVALID_ENTRY));
+
+    // Member power above the XRP supply
+    let (m, l) = member_entry(0, &bob(), b"100000000000000001", 0);
+    let (s, len) = spec(&m[..l]);
+    assert_eq!(Genesis::parse(&s[..len]).err(), Some(ERR_INVALID_ENTRY));
+    let (m, l) = member_entry(0, &bob(), b"100000000000000000", 0);
+    let (s, len) = spec(&m[..l]);
+    assert_eq!(Genesis::parse(&s[..len]).unwrap().members()[0].voting_power, MAX_XRP_DROPS);
+
+    // Unknown role bit
+    let (m, l) = member_entry(0, &bob(), b"1", 16);
+    let (s, len) = spec(&m[..l]);
+    assert_eq!(Genesis::parse(&s[..len]).err(), Some(ERR_BAD_CONFIG));
+    let (m, l) = member_entry(0, &bob(), b"1", ROLE_CANCELLER | ROLE_EXECUTOR);
+    let (s, len) = spec(&m[..l]);
+    assert!(Genesis::parse(&s[..len]).unwrap().members()[0].has_role(ROLE_CANCELLER));
+
+    // Same account twice
+    let (m0, l0) = member_entry(0, &bob(), b"1", 0);
+    let (m1, l1) = member_entry(1, &bob(), b"2", 0);
+    let (rest, rlen) = join(&[&m0[..l0], &m1[..l1]]);
+    let (s, len) = spec(&rest[..rlen]);
+    assert_eq!(Genesis::parse(&s[..len]).err(), Some(ERR_KEY_EXISTS));
+}
+
+#[test]
+fn test_parse_rejects_too_many_members() {
+    // Admin is appended after the declared members, so a full list overflows
+    let mut store = Store::new();
+    for i in 0..LIMITS.max_members {
+        let member = Member { account: mock_account(i as u8 + 1), voting_power: 1, roles: 0 };
+        member.store(&mut store, i).unwrap();
+    }
+    let (members, mlen) = store.commit();
+    let mut s = [0u8; DATA_CAPACITY + 64];
+    let (head, hlen) = spec(b"");
+    s[..hlen].copy_from_slice(&head[..hlen]);
+    s[hlen] = b';';
+    s[hlen + 1..hlen + 1 + mlen].copy_from_slice(&members[..mlen]);
+    assert_eq!(Genesis::parse(&s[..hlen + 1 + mlen]).err(), Some(ERR_BAD_CONFIG));
+}
+
+#[test]
+fn test_initialize_writes_genesis_state() {
+    let (m0, l0) = member_entry(0, &bob(), b"200000000", ROLE_PROPOSER | ROLE_EXECUTOR);
+    let (rest, rlen) = join(&[&m0[..l0], b"cfg_quorum=10"]);
+    let (s, len) = spec(&rest[..rlen]);
+    let genesis = Genesis::parse(&s[..len]).unwrap();
+
+    let (data, dlen) = initialize(b"", 0, &genesis, 500).unwrap();
+    let data = &data[..dlen];
+
+    assert_eq!(migrate::check(data), Ok(()));
+    assert_eq!(migrate::schema_version(data), migrate::CURRENT_SCHEMA_VERSION);
+    assert_eq!(votes::get_member_count(data), 2);
+    assert!(votes::has_role(data, &alice(), ROLE_ADMIN));
+    assert_eq!(votes::get_votes(data, &bob()), 200_000_000);
+    assert!(votes::has_role(data, &bob(), ROLE_EXECUTOR));
+    assert_eq!(Settings::load(data).quorum_percentage, 10);
+    assert_eq!(xrp_votes::get_past_votes(data, &bob(), 499), 0);
+    assert_eq!(xrp_votes::get_past_votes(data, &bob(), 500), 200_000_000);
+    assert_eq!(xrp_votes::get_past_total_supply(data, 500), votes::get_total_voting_power(data));
+    assert_invariants(data);
+
+    // The genesis state is immediately usable
+    assert!(governor::propose(data, dlen, &bob(), &mock_desc(1), &Actions::new(), 1000, 200_000_000).is_ok());
+}
+
+#[test]
+fn test_initialize_runs_once() {
+    let (s, len) = spec(b"");
+    let genesis = Genesis::parse(&s[..len]).unwrap();
+    let (data, dlen) = initialize(b"", 0, &genesis, 0).unwrap();
+
+    assert_eq!(initialize(&data[..dlen], dlen, &genesis, 0).err(), Some(ERR_ALREADY_INITIALIZED));
+
+    // Hand-built state from before the export existed is also refused
+    let (legacy, llen) = build_dao_data(&[(&alice(), 100, 7)]);
+    assert_eq!(initialize(&legacy[..llen], llen, &genesis, 0).err(), Some(ERR_ALREADY_INITIALIZED));
+}
@@ -0,0 +1,127 @@
+use crate::foundation::config::*;
+use crate::foundation::data::*;
+use crate::foundation::invariants::*;
+use crate::governance::{counting, governor};
+use crate::timelock::controller;
+use crate::tests::*;
+
+/// One Succeeded-then-Queued proposal with a vote from alice and bob.
+fn queued_dao() -> ([u8; DATA_CAPACITY], usize, u32) {
+    let (data, len) = build_dao_with_proposal(
+        &[(&alice(), 300_000_000, ROLE_PROPOSER | ROLE_EXECUTOR), (&bob(), 200_000_000, 0)],
+        42, &alice(), 100, 200, 0,
+    );
+    let (d, l) = counting::cast_vote(&data[..len], len, 0, &alice(), VOTE_FOR, 300_000_000, 150).unwrap();
+    let (d, l) = counting::cast_vote(&d[..l], l, 0, &bob(), VOTE_AGAINST, 200_000_000, 150).unwrap();
+    let (d, l, _) = controller::schedule(&d[..l], l, &mock_id(42), 300, TIMELOCK_MIN_DELAY).unwrap();
+    let (d, l) = governor::mark_queued(&d[..l], l, 0, 300 + TIMELOCK_MIN_DELAY).unwrap();
+    (d, l, 300 + TIMELOCK_MIN_DELAY)
+}
+
+fn with(data: &[u8], key: &[u8], value: &[u8]) -> ([u8; DATA_CAPACITY], usize) {
+    let mut store = Store::load(data);
+    store.set(key, value).unwrap();
+    store.commit()
+}
+
+#[test]
+fn test_consistent_state_passes() {
+    let (data, len) = build_dao_data(&[(&alice(), 100, 7), (&bob(), 50, 0)]);
+    assert_eq!(check(&data[..len]), Ok(()));
+
+    let (d, l, _) = queued_dao();
+    assert_eq!(check(&d[..l]), Ok(()));
+}
+
+#[test]
+fn test_empty_state_passes() {
+    assert_eq!(check(b""), Ok(()));
+}
+
+#[test]
+fn test_executed_operation_passes() {
+    let (d, l, ready) = queued_dao();
+    let (d, l) = controller::execute(&d[..l], l, 0, ready).unwrap();
+    assert_eq!(check(&d[..l]), Err(ERR_INVARIANT)); // proposal still Queued
+
+    let (d, l) = governor::update_proposal_field(&d[..l], l, 0, b"_state", b"7").unwrap();
+    assert_eq!(check(&d[..l]), Ok(()));
+}
+
+#[test]
+fn test_member_count_mismatch() {
+    let (data, len) = build_dao_data(&[(&alice(), 100, 7), (&bob(), 50, 0)]);
+    let (d, l) = with(&data[..len], b"member_count", b"3");
+    assert_eq!(check(&d[..l]), Err(ERR_INVARIANT));
+
+    let (d, l) = with(&data[..len], b"member_count", b"1");
+    assert_eq!(check(&d[..l]), Err(ERR_INVARIANT));
+}
+
+#[test]
+fn test_duplicate_member_account() {
+    let (data, len) = build_dao_data(&[(&alice(), 100, 7), (&alice(), 50, 0)]);
+    assert_eq!(check(&data[..len]), Err(ERR_INVARIANT));
+}
+
+#[test]
+fn test_tally_mismatch() {
+    let (d, l, _) = queued_dao();
+    let (d, l) = with(&d[..l], b"prop_0_for", b"300000001");
+    assert_eq!(check(&d[..l]), Err(ERR_INVARIANT));
+}
+
+#[test]
+fn test_quorum_above_supply() {
+    let (d, l, _) = queued_dao();
+    let (d, l) = with(&d[..l], b"prop_0_quorum", b"500000001");
+    assert_eq!(check(&d[..l]), Err(ERR_INVARIANT));
+}
+
+#[test]
+fn test_operation_for_unqueued_proposal() {
+    let (d, l, _) = queued_dao();
+    let (d, l) = with(&d[..l], b"prop_0_state", b"4");
+    assert_eq!(check(&d[..l]), Err(ERR_INVARIANT));
+
+    // An operation linked to no proposal at all
+    let (data, len) = build_dao_data(&[(&alice(), 100, 7)]);
+    let (d, l, _) = controller::schedule(&data[..len], len, &mock_id(999), 300, TIMELOCK_MIN_DELAY).unwrap();
+    assert_eq!(check(&d[..l]), Err(ERR_INVARIANT));
+}
+
+#[test]
+fn test_lock_left_set() {
+    let (data, len) = build_dao_data(&[(&alice(), 100, 7)]);
+    let (d, l) = governor::set_lock(&data[..len], len, true).unwrap();
+    assert_eq!(check(&d[..l]), Err(ERR_INVARIANT));
+
+    let (d, l) = governor::set_lock(&d[..l], l, false).unwrap();
+    assert_eq!(check(&d[..l]), Ok(()));
+}
+
+#[test]
+fn test_exec_marker_left_set() {
+    let (data, len) = build_dao_with_proposal(&[(&alice(), 100, 7)], 42, &alice(), 100, 200, 0);
+    let (d, l) = governor::set_executing(&data[..len], len, Some(&mock_id(42))).unwrap();
+    assert_eq!(check(&d[..l]), Err(ERR_INVARIANT));
+
+    let (d, l) = governor::set_lock(&d[..l], l, false).unwrap();
+    assert_eq!(check(&d[..l]), Err(ERR_INVARIANT), "_exec must be cleared too");
+
+    let (d, l) = governor::set_executing(&d[..l], l, None).unwrap();
+    assert_eq!(check(&d[..l]), Ok(()));
+}
+
+#[test]
+fn test_setting_out_of_bounds() {
+    let (data, len) = build_dao_data(&[(&alice(), 100, 7)]);
+    let (d, l) = with(&data[..len], b"cfg_quorum", b"10");
+    assert_eq!(check(&d[..l]), Ok(()));
+
+    let (d, l) = with(&d[..l], b"cfg_quorum", b"0");
+    assert_eq!(check(&d[..l]), Err(ERR_INVARIANT));
+
+    let (d, l) = with(&data[..len], b"cfg_unknown", b"1");
+    assert_eq!(check(&d[..l]), Err(ERR_INVARIANT));
+}
@@ -0,0 +1,227 @@
+use crate::foundation::config::*;
+use crate::foundation::data::*;
+use crate::foundation::migrate::*;
+use crate::tests::*;
+
+#[test]
+fn test_unversioned_data_is_version_zero() {
+    let (data, len) = build_dao_data(&[(&alice(), 100, 7)]);
+    assert_eq!(schema_version(&data[..len]), 0);
+    assert_eq!(check(&data[..len]), Err(ERR_SCHEMA_OUTDATED));
+}
+
+#[test]
+fn test_empty_data_passes_check() {
+    assert_eq!(check(b""), Ok(()));
+}
+
+#[test]
+fn test_upgrade_stamps_current_version() {
+    let (data, len) = build_dao_with_proposal(&[(&alice(), 100, 7)], 42, &alice(), 100, 200, 1);
+    let (new_data, new_len) = upgrade(&data[..len], len).unwrap();
+
+    assert_eq!(schema_version(&new_data[..new_len]), CURRENT_SCHEMA_VERSION);
+    assert_eq!(check(&new_data[..new_len]), Ok(()));
+    // v0 → v1 keeps every existing entry
+    for (key, value) in iter_prefix(&data[..len], b"") {
+        assert_eq!(find_value(&new_data[..new_len], key), Some(value));
+    }
+}
+
+#[test]
+fn test_upgrade_is_idempotent() {
+    let (data, len) = build_dao_data(&[(&alice(), 100, 7)]);
+    let (once, once_len) = upgrade(&data[..len], len).unwrap();
+    let (twice, twice_len) = upgrade(&once[..once_len], once_len).unwrap();
+    assert_eq!(&once[..once_len], &twice[..twice_len]);
+}
+
+#[test]
+fn test_upgrade_rejects_newer_schema() {
+    let data = b"schema_version=999;member_count=0";
+    assert_eq!(check(data), Err(ERR_SCHEMA_TOO_NEW));
+    assert_eq!(upgrade(data, data.len()).map(|_| ()), Err(ERR_SCHEMA_TOO_NEW));
+}
+
+#[test]
+fn test_upgrade_on_full_data_returns_data_full() {
+    let filler = [b'x'; DATA_CAPACITY - 6];
+    let mut store = Store::new();
+    store.set(b"f", &filler).unwrap();
+    let (data, len) = store.commit();
+    assert_eq!(upgrade(&data[..len], len).map(|_| ()), Err(ERR_DATA_FULL));
+}
+
+#[test]
+fn test_upgrade_near_full_v0_drops_finished_proposals() {
+    use crate::crypto::hash::legacy_id;
+    use crate::crypto::hex::encode_hex;
+    use crate::foundation::parse::format_u32;
+    use crate::foundation::types::Proposal;
+
+    // v0 layout: decimal IDs; ten proposals (the old limit, above today's
+    // MAX_PROPOSALS), all but the last executed with their ops
+    let (data, len) = build_dao_data(&[(&alice(), 100, 7), (&bob(), 100, 0)]);
+    let mut store = Store::load(&data[..len]);
+    let mut alice_hex = [0u8; 40];
+    encode_hex(&alice(), &mut alice_hex);
+    let mut vote_suffix = [b'_'; 41];
+    vote_suffix[1..].copy_from_slice(&alice_hex);
+    fn put(store: &mut Store, prefix: &[u8], i: u32, suffix: &[u8], value: &[u8]) {
+        let mut key = [0u8; 64];
+        let mut klen = build_indexed_key(prefix, i as u16, &mut key);
+        key[klen..klen + suffix.len()].copy_from_slice(suffix);
+        klen += suffix.len();
+        store.set(&key[..klen], value).unwrap();
+    }
+    let last = 9;
+    let mut id = [0u8; 10];
+    for i in 0..=last {
+        let idlen = format_u32(i + 1, &mut id);
+        let state: &[u8] = if i < last { b"7" } else { b"1" };
+        put(&mut store, b"prop_", i, b"_id", &id[..idlen]);
+        put(&mut store, b"prop_", i, b"_proposer", &alice_hex);
+        put(&mut store, b"prop_", i, b"_state", state);
+        put(&mut store, b"prop_", i, b"_desc", b"12345");
+        put(&mut store, b"vote_", i, &vote_suffix, b"1");
+        if i < last {
+            put(&mut store, b"op_", i, b"_id", b"77");
+            put(&mut store, b"op_", i, b"_prop", &id[..idlen]);
+            put(&mut store, b"op_", i, b"_state", b"3");
+        }
+    }
+    let clen = format_u32(last + 1, &mut id);
+    store.set(b"proposal_count", &id[..clen]).unwrap();
+    let clen = format_u32(last, &mut id);
+    store.set(b"op_count", &id[..clen]).unwrap();
+    let filler = [b'x'; DATA_CAPACITY];
+    store.set(b"pad", &filler[..store.available() - 40]).unwrap();
+    let (v0, v0_len) = store.commit();
+    assert!(v0_len > DATA_CAPACITY - 64);
+
+    let (up, up_len) = upgrade(&v0[..v0_len], v0_len).unwrap();
+    let up = &up[..up_len];
+    assert_eq!(check(up), Ok(()));
+    assert_eq!(find_value(up, b"proposal_count"), Some(&b"1"[..]));
+    assert_eq!(find_value(up, b"op_count"), Some(&b"0"[..]));
+    assert_eq!(Proposal::load(up, 0).unwrap().id, legacy_id(last + 1));
+    assert!(find_value(up, b"prop_1_id").is_none());
+    let mut vote_key = [0u8; 48];
+    vote_key[..6].copy_from_slice(b"vote_0");
+    vote_key[6..47].copy_from_slice(&vote_suffix);
+    assert_eq!(find_value(up, &vote_key[..47]), Some(&b"1"[..]));
+}
+
+#[test]
+fn test_upgrade_backfills_eta_for_queued_proposals() {
+    use crate::crypto::hash::legacy_id;
+    use crate::foundation::types::Proposal;
+
+    // v1 layout: decimal IDs, queued proposal without prop_0_eta
+    let mut store = Store::new();
+    store.set(SCHEMA_VERSION_KEY, b"1").unwrap();
+    store.set(b"proposal_count", b"1").unwrap();
+    store.set(b"prop_0_id", b"42").unwrap();
+    store.set(b"prop_0_state", b"5").unwrap();
+    store.set(b"op_count", b"1").unwrap();
+    store.set(b"op_0_id", b"7").unwrap();
+    store.set(b"op_0_prop", b"42").unwrap();
+    store.set(b"op_0_ready", b"173800").unwrap();
+    let (v1, v1_len) = store.commit();
+
+    let (up, up_len) = upgrade(&v1[..v1_len], v1_len).unwrap();
+    assert_eq!(schema_version(&up[..up_len]), CURRENT_SCHEMA_VERSION);
+    let proposal = Proposal::load(&up[..up_len], 0).unwrap();
+    assert_eq!(proposal.id, legacy_id(42));
+    assert_eq!(proposal.eta, 173_800);
+}
+
+#[test]
+fn test_upgrade_widens_decimal_ids() {
+    use crate::crypto::hash::legacy_id;
+    use crate::crypto::hex::encode_hex;
+    use crate::foundation::abi::decode_hash;
+
+    let mut acct = [0u8; 40];
+    encode_hex(&alice(), &mut acct);
+    let mut snap_key = *b"snap_42_0000000000000000000000000000000000000000";
+    snap_key[8..].copy_from_slice(&acct);
+
+    let mut store = Store::new();
+    store.set(SCHEMA_VERSION_KEY, b"2").unwrap();
+    store.set(b"proposal_count", b"1").unwrap();
+    store.set(b"prop_0_id", b"42").unwrap();
+    store.set(b"prop_0_desc", b"12345").unwrap();
+    store.set(b"op_count", b"1").unwrap();
+    store.set(b"op_0_id", b"7").unwrap();
+    store.set(b"op_0_prop", b"42").unwrap();
+    store.set(b"op_0_ready", b"5000").unwrap();
+    store.set(&snap_key, b"100").unwrap();
+    store.set(b"hist_9", b"7:123").unwrap();
+    store.set(b"_exec", b"42").unwrap();
+    let (v2, v2_len) = store.commit();
+
+    let (v3, v3_len) = upgrade(&v2[..v2_len], v2_len).unwrap();
+    let d = &v3[..v3_len];
+    assert_eq!(schema_version(d), CURRENT_SCHEMA_VERSION);
+    let id = |key: &[u8]| find_value(d, key).and_then(decode_hash);
+    assert_eq!(id(b"prop_0_id"), Some(legacy_id(42)));
+    assert_eq!(id(b"op_0_id"), Some(legacy_id(7)));
+    assert_eq!(id(b"op_0_prop"), Some(legacy_id(42)));
+    assert_eq!(id(b"_exec"), Some(legacy_id(42)));
+
+    // v4 widens the decimal description hash the same way
+    assert_eq!(id(b"prop_0_desc"), Some(legacy_id(12345)));
+
+    // Non-ID values are untouched; ID-keyed entries move to the hex key
+    assert_eq!(find_value(d, b"op_0_ready"), Some(b"5000" as &[u8]));
+    assert_eq!(find_value(d, &snap_key), None);
+    assert_eq!(crate::token::xrp_votes::get_snapshot_votes(d, &legacy_id(42), &alice()), 100);
+    // v8 widens the 32-bit archive digest
+    assert_eq!(crate::governance::prune::get_archived(d, &legacy_id(9)), Some((7, legacy_id(123))));
+}
+
+#[test]
+fn test_upgrade_freezes_quorum_of_existing_proposals() {
+    use crate::foundation::types::Proposal;
+
+    let (data, len) = build_dao_with_proposal(
+        &[(&alice(), 600_000_000, ROLE_PROPOSER), (&bob(), 400_000_000, 0)],
+        42, &alice(), 100, 200, 0,
+    );
+    let mut store = Store::load(&data[..len]);
+    store.set(SCHEMA_VERSION_KEY, b"4").unwrap();
+    store.remove(b"prop_0_supply");
+    store.remove(b"prop_0_quorum");
+    store.set(b"cfg_quorum", b"10").unwrap();
+    let (v4, v4_len) = store.commit();
+
+    let (up, up_len) = upgrade(&v4[..v4_len], v4_len).unwrap();
+    let proposal = Proposal::load(&up[..up_len], 0).unwrap();
+    assert_eq!((proposal.total_supply, proposal.quorum), (1_000_000_000, 100_000_000));
+}
+
+#[test]
+fn test_upgrade_seeds_vote_checkpoints() {
+    use crate::crypto::hex::encode_hex;
+    use crate::token::xrp_votes::{get_past_total_supply, get_past_votes};
+
+    // alice delegates to bob in a v5 blob, which has no checkpoints
+    let (data, len) = build_dao_data(&[(&alice(), 600, ROLE_ADMIN), (&bob(), 400, 0), (&carol(), 0, 0)]);
+    let mut store = Store::load(&data[..len]);
+    let mut key = *b"delegate_0000000000000000000000000000000000000000";
+    let mut bob_hex = [0u8; 40];
+    encode_hex(&alice(), &mut key[9..]);
+    encode_hex(&bob(), &mut bob_hex);
+    store.set(&key, &bob_hex).unwrap();
+    store.set(SCHEMA_VERSION_KEY, b"5").unwrap();
+    let (v5, v5_len) = store.commit();
+
+    let (up, up_len) = upgrade(&v5[..v5_len], v5_len).unwrap();
+    let up = &up[..up_len];
+    assert_eq!(get_past_votes(up, &alice(), 0), 0);
+    assert_eq!(get_past_votes(up, &bob(), 0), 1000);
+    assert_eq!(get_past_total_supply(up, 0), 1000);
+    // Accounts without votes need no history
+    assert_eq!(iter_prefix(up, b"ckpt_").count(), 2);
+}
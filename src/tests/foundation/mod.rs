@@ -1,2 +1,9 @@
 pub mod data_tests;
+#[cfg(feature = "compact-state")]
+pub mod codec_tests;
 pub mod parse_tests;
+pub mod abi_tests;
+pub mod migrate_tests;
+pub mod types_tests;
+pub mod invariants_tests;
+pub mod genesis_tests;
@@ -0,0 +1,273 @@
+use crate::foundation::config::*;
+use crate::foundation::data::*;
+use crate::foundation::types::*;
+use crate::governance::{counting, governor};
+use crate::timelock::{controller, operations};
+use crate::tests::*;
+
+// ═══════════════════════════════════════════════════════════════════════
+// Proposal
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_proposal_load_reads_all_fields() {
+    let (data, len) = build_dao_with_proposal(&[(&alice(), 100, 7)], 42, &alice(), 100, 200, 1);
+    let p = Proposal::load(&data[..len], 0).unwrap();
+
+    assert_eq!(p.id, mock_id(42));
+    assert_eq!(p.proposer, alice());
+    assert_eq!((p.vote_start, p.vote_end), (100, 200));
+    assert_eq!(p.state, PROPOSAL_STATE_ACTIVE);
+    assert_eq!(p.total_votes(), 0);
+    assert_eq!(p.eta, 0);
+}
+
+#[test]
+fn test_proposal_load_missing_is_none() {
+    let (data, len) = build_dao_data(&[(&alice(), 100, 7)]);
+    assert!(Proposal::load(&data[..len], 0).is_none());
+}
+
+#[test]
+fn test_proposal_store_round_trip() {
+    let mut p = Proposal::new();
+    p.id = mock_id(7);
+    p.proposer = bob();
+    p.vote_start = 10;
+    p.vote_end = 20;
+    p.state = PROPOSAL_STATE_QUEUED;
+    p.for_votes = 5_000_000_000;
+    p.against_votes = 3;
+    p.abstain_votes = 1;
+    p.eta = 999;
+    p.description_hash = mock_desc(0xDEAD);
+    p.vote_extension = 3600;
+
+    let mut store = Store::new();
+    p.store(&mut store, 12).unwrap();
+    let q = Proposal::load(store.as_bytes(), 12).unwrap();
+
+    assert_eq!(q.id, p.id);
+    assert_eq!(q.proposer, p.proposer);
+    assert_eq!((q.vote_start, q.vote_end, q.state), (10, 20, PROPOSAL_STATE_QUEUED));
+    assert_eq!((q.for_votes, q.against_votes, q.abstain_votes), (5_000_000_000, 3, 1));
+    assert_eq!(q.eta, 999);
+    assert_eq!(q.description_hash, mock_desc(0xDEAD));
+    assert_eq!(q.vote_extension, 3600);
+}
+
+#[test]
+fn test_proposal_eta_only_stored_when_set() {
+    let mut p = Proposal::new();
+    p.id = mock_id(1);
+
+    let mut store = Store::new();
+    p.store(&mut store, 0).unwrap();
+    assert!(store.get(b"prop_0_eta").is_none());
+
+    p.eta = 500;
+    p.store(&mut store, 0).unwrap();
+    assert_eq!(store.get(b"prop_0_eta"), Some(&b"500"[..]));
+
+    p.eta = 0;
+    p.store(&mut store, 0).unwrap();
+    assert!(store.get(b"prop_0_eta").is_none());
+}
+
+#[test]
+fn test_propose_populates_description_hash() {
+    let (data, len) = build_dao_data(&[(&alice(), 200_000_000, ROLE_PROPOSER)]);
+    let (d, l, id) = governor::propose(&data[..len], len, &alice(), &mock_desc(4242), &Actions::new(), 1000, 200_000_000).unwrap();
+
+    let p = Proposal::load(&d[..l], 0).unwrap();
+    assert_eq!(p.id, id);
+    assert_eq!(p.description_hash, mock_desc(4242));
+    assert_eq!(p.vote_start, 1000 + VOTING_DELAY);
+}
+
+#[test]
+fn test_mark_queued_sets_eta() {
+    let (data, len) = build_dao_with_proposal(&[(&alice(), 100, 7)], 42, &alice(), 100, 200, 4);
+    let (d, l) = governor::mark_queued(&data[..len], len, 0, 5000).unwrap();
+
+    let p = Proposal::load(&d[..l], 0).unwrap();
+    assert_eq!(p.state, PROPOSAL_STATE_QUEUED);
+    assert_eq!(p.eta, 5000);
+    assert_eq!(governor::mark_queued(&data[..len], len, 3, 5000).err(), Some(ERR_PROPOSAL_NOT_FOUND));
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Member
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_member_load_and_find() {
+    let (data, len) = build_dao_data(&[
+        (&alice(), 100, ROLE_ADMIN),
+        (&bob(), 250, ROLE_PROPOSER | ROLE_EXECUTOR),
+    ]);
+
+    let m = Member::load(&data[..len], 1).unwrap();
+    assert_eq!(m.account, bob());
+    assert_eq!(m.voting_power, 250);
+    assert!(m.has_role(ROLE_EXECUTOR));
+
+    let (idx, found) = Member::find(&data[..len], &alice()).unwrap();
+    assert_eq!(idx, 0);
+    assert_eq!(found.voting_power, 100);
+    assert!(Member::find(&data[..len], &carol()).is_none());
+}
+
+#[test]
+fn test_member_store_matches_record_format() {
+    let m = Member { account: alice(), voting_power: 300, roles: ROLE_ADMIN | ROLE_PROPOSER };
+    let mut store = Store::new();
+    m.store(&mut store, 3).unwrap();
+
+    let val = store.get(b"member_3").unwrap();
+    assert_eq!(&val[40..], b":300:5");
+    let back = Member::load(store.as_bytes(), 3).unwrap();
+    assert_eq!(back.account, alice());
+
+    // Roles are decimal, so the canceller bit takes two digits
+    let m = Member { roles: ROLE_CANCELLER | ROLE_ADMIN | ROLE_PROPOSER, ..m };
+    m.store(&mut store, 3).unwrap();
+    assert_eq!(&store.get(b"member_3").unwrap()[40..], b":300:13");
+    assert!(Member::load(store.as_bytes(), 3).unwrap().has_role(ROLE_CANCELLER));
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// TimelockOp
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_timelock_op_load_after_schedule() {
+    let (data, len) = build_dao_data(&[(&alice(), 100, ROLE_ADMIN)]);
+    let (d, l, op_id) = controller::schedule(&data[..len], len, &mock_id(77), 1000, TIMELOCK_MIN_DELAY).unwrap();
+
+    let op = TimelockOp::load(&d[..l], 0).unwrap();
+    assert_eq!(op.id, op_id);
+    assert_eq!(op.proposal_id, mock_id(77));
+    assert_eq!(op.ready_at, 1000 + TIMELOCK_MIN_DELAY);
+    assert_eq!(op.state, OP_STATE_PENDING);
+    assert_eq!(op.predecessor, [0u8; HASH_SIZE]);
+    assert!(find_value(&d[..l], b"op_0_predecessor").is_none());
+}
+
+#[test]
+fn test_timelock_op_predecessor_round_trip() {
+    let (data, len) = build_dao_data(&[(&alice(), 100, ROLE_ADMIN)]);
+    let (d, l, op1) = controller::schedule(&data[..len], len, &mock_id(1), 1000, TIMELOCK_MIN_DELAY).unwrap();
+    let (d, l, _) = operations::schedule_with_predecessor(&d[..l], l, &mock_id(2), &op1, 1000, TIMELOCK_MIN_DELAY).unwrap();
+
+    assert_eq!(TimelockOp::load(&d[..l], 1).unwrap().predecessor, op1);
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// VoteRecord
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_vote_record_load_after_cast() {
+    let (data, len) = build_dao_with_proposal(
+        &[(&alice(), 100, 7), (&bob(), 50, 0)], 42, &alice(), 100, 200, 1,
+    );
+    let (d, l) = counting::cast_vote(&data[..len], len, 0, &bob(), VOTE_AGAINST, 50, 150).unwrap();
+
+    let v = VoteRecord::load(&d[..l], 0, 0).unwrap();
+    assert_eq!(v.voter, bob());
+    assert_eq!(v.proposal_id, mock_id(42));
+    assert_eq!(v.support, VOTE_AGAINST);
+    assert_eq!(v.weight, 50);
+    assert!(VoteRecord::load(&d[..l], 0, 1).is_none());
+}
+
+#[test]
+fn test_vote_record_rejects_bad_support() {
+    let mut store = Store::new();
+    let v = VoteRecord { voter: carol(), proposal_id: [0u8; HASH_SIZE], support: 9, weight: 1 };
+    v.store(&mut store, 0, 0).unwrap();
+    assert!(VoteRecord::load(store.as_bytes(), 0, 0).is_none());
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Actions
+// ═══════════════════════════════════════════════════════════════════════
+
+fn sample_actions() -> Actions {
+    let mut pay = Action::new();
+    pay.target = bob();
+    pay.amount = 25_000_000;
+    let mut set = Action::new();
+    set.target = carol();
+    set.tx_type = 20;
+    set.payload[..3].copy_from_slice(&[0xde, 0xad, 0x01]);
+    set.payload_len = 3;
+
+    let mut actions = Actions::new();
+    actions.push(pay).unwrap();
+    actions.push(set).unwrap();
+    actions
+}
+
+#[test]
+fn test_actions_store_round_trip() {
+    let actions = sample_actions();
+    let mut store = Store::new();
+    actions.store(&mut store, 2).unwrap();
+    assert_eq!(store.get(b"prop_2_act_count"), Some(&b"2"[..]));
+
+    let loaded = Actions::load(store.as_bytes(), 2).unwrap();
+    assert_eq!(loaded.count, 2);
+    for (a, b) in loaded.as_slice().iter().zip(actions.as_slice()) {
+        assert_eq!((a.target, a.amount, a.tx_type, a.payload()), (b.target, b.amount, b.tx_type, b.payload()));
+    }
+
+    // No entries means no actions; a missing or bad entry means None
+    assert_eq!(Actions::load(store.as_bytes(), 0).unwrap().count, 0);
+    store.remove(b"prop_2_act_1");
+    assert!(Actions::load(store.as_bytes(), 2).is_none());
+    store.set(b"prop_2_act_1", b"zz:1:0:").unwrap();
+    assert!(Actions::load(store.as_bytes(), 2).is_none());
+}
+
+#[test]
+fn test_action_encode_matches_abi() {
+    let action = sample_actions().items[1];
+    let mut text = [0u8; ACTION_TEXT_SIZE];
+    let len = action.encode(&mut text);
+    let decoded = crate::foundation::abi::decode_action(&text[..len]).unwrap();
+    assert_eq!(decoded.payload(), action.payload());
+    assert_eq!(decoded.tx_type, 20);
+
+    // The longest action fits
+    let mut max = Action::new();
+    max.amount = u64::MAX;
+    max.tx_type = u16::MAX;
+    max.payload_len = MAX_ACTION_PAYLOAD as u8;
+    assert_eq!(max.encode(&mut text), ACTION_TEXT_SIZE);
+}
+
+#[test]
+fn test_action_result_round_trip() {
+    let mut store = Store::new();
+    Actions::store_result(&mut store, 3, 0, SUCCESS).unwrap();
+    Actions::store_result(&mut store, 3, 1, ERR_EMIT_UNSUPPORTED).unwrap();
+    assert_eq!(store.get(b"prop_3_res_1"), Some(&b"-33"[..]));
+
+    assert_eq!(Actions::result(store.as_bytes(), 3, 0), Some(SUCCESS));
+    assert_eq!(Actions::result(store.as_bytes(), 3, 1), Some(ERR_EMIT_UNSUPPORTED));
+    assert_eq!(Actions::result(store.as_bytes(), 3, 2), None);
+}
+
+#[test]
+fn test_propose_stores_actions() {
+    let (data, len) = build_dao_data(&[(&alice(), 200_000_000, ROLE_PROPOSER)]);
+    let actions = sample_actions();
+    let (d, l, id) = governor::propose(&data[..len], len, &alice(), &mock_desc(4242), &actions, 1000, 200_000_000).unwrap();
+
+    assert_eq!(Actions::load(&d[..l], 0).unwrap().count, 2);
+    let (_, _, bare_id) = governor::propose(&data[..len], len, &alice(), &mock_desc(4242), &Actions::new(), 1000, 200_000_000).unwrap();
+    assert_ne!(id, bare_id);
+    assert_invariants(&d[..l]);
+}
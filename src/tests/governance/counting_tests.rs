@@ -19,7 +19,7 @@ fn test_cast_vote_for() {
     );
 
     let result = cast_vote(
-        &data[..len], len, 0, &bob(), VOTE_FOR, 100_000_000, 2000, 300_000_000,
+        &data[..len], len, 0, &bob(), VOTE_FOR, 100_000_000, 2000,
     );
     assert!(result.is_ok());
 
@@ -41,7 +41,7 @@ fn test_cast_vote_against() {
     );
 
     let result = cast_vote(
-        &data[..len], len, 0, &bob(), VOTE_AGAINST, 100_000_000, 2000, 300_000_000,
+        &data[..len], len, 0, &bob(), VOTE_AGAINST, 100_000_000, 2000,
     );
     assert!(result.is_ok());
 
@@ -62,7 +62,7 @@ fn test_cast_vote_abstain() {
     );
 
     let result = cast_vote(
-        &data[..len], len, 0, &bob(), VOTE_ABSTAIN, 100_000_000, 2000, 300_000_000,
+        &data[..len], len, 0, &bob(), VOTE_ABSTAIN, 100_000_000, 2000,
     );
     assert!(result.is_ok());
 
@@ -78,7 +78,7 @@ fn test_cast_vote_invalid_support() {
         &members, 42, &alice(), 1000, 260000, 0,
     );
 
-    let result = cast_vote(&data[..len], len, 0, &alice(), 3, 200_000_000, 2000, 200_000_000);
+    let result = cast_vote(&data[..len], len, 0, &alice(), 3, 200_000_000, 2000);
     assert_eq!(result, Err(ERR_INVALID_VOTE));
 }
 
@@ -94,7 +94,7 @@ fn test_cast_vote_not_active() {
     );
 
     let result = cast_vote(
-        &data[..len], len, 0, &bob(), VOTE_FOR, 100_000_000, 1000, 300_000_000,
+        &data[..len], len, 0, &bob(), VOTE_FOR, 100_000_000, 1000,
     );
     assert_eq!(result, Err(ERR_PROPOSAL_NOT_ACTIVE));
 }
@@ -111,12 +111,12 @@ fn test_double_vote_rejected() {
 
     // First vote succeeds
     let (data1, len1) = cast_vote(
-        &data[..len], len, 0, &bob(), VOTE_FOR, 100_000_000, 2000, 300_000_000,
+        &data[..len], len, 0, &bob(), VOTE_FOR, 100_000_000, 2000,
     ).unwrap();
 
     // Second vote fails
     let result = cast_vote(
-        &data1[..len1], len1, 0, &bob(), VOTE_AGAINST, 100_000_000, 2500, 300_000_000,
+        &data1[..len1], len1, 0, &bob(), VOTE_AGAINST, 100_000_000, 2500,
     );
     assert_eq!(result, Err(ERR_ALREADY_VOTED));
 }
@@ -133,21 +133,22 @@ fn test_multiple_voters() {
     );
 
     let (data1, len1) = cast_vote(
-        &data[..len], len, 0, &alice(), VOTE_FOR, 200_000_000, 2000, 450_000_000,
+        &data[..len], len, 0, &alice(), VOTE_FOR, 200_000_000, 2000,
     ).unwrap();
 
     let (data2, len2) = cast_vote(
-        &data1[..len1], len1, 0, &bob(), VOTE_AGAINST, 100_000_000, 2100, 450_000_000,
+        &data1[..len1], len1, 0, &bob(), VOTE_AGAINST, 100_000_000, 2100,
     ).unwrap();
 
     let (data3, len3) = cast_vote(
-        &data2[..len2], len2, 0, &carol(), VOTE_FOR, 150_000_000, 2200, 450_000_000,
+        &data2[..len2], len2, 0, &carol(), VOTE_FOR, 150_000_000, 2200,
     ).unwrap();
 
     let (for_v, against_v, abstain_v) = proposal_votes(&data3[..len3], 0);
     assert_eq!(for_v, 350_000_000); // alice + carol
     assert_eq!(against_v, 100_000_000); // bob
     assert_eq!(abstain_v, 0);
+    assert_invariants(&data3[..len3]);
 }
 
 // ═══════════════════════════════════════════════════════════════════════
@@ -175,7 +176,7 @@ fn test_has_voted_true_after_vote() {
     );
 
     let (new_data, new_len) = cast_vote(
-        &data[..len], len, 0, &bob(), VOTE_FOR, 100_000_000, 2000, 300_000_000,
+        &data[..len], len, 0, &bob(), VOTE_FOR, 100_000_000, 2000,
     ).unwrap();
 
     assert!(has_voted(&new_data[..new_len], 0, &bob()));
@@ -196,7 +197,7 @@ fn test_quorum_not_reached() {
         &members, 42, &alice(), 1000, 260000, 0,
     );
     // No votes cast, quorum needs 4% of 300M = 12M
-    assert!(!quorum_reached(&data[..len], 0, 300_000_000));
+    assert!(!quorum_reached(&data[..len], 0));
 }
 
 #[test]
@@ -211,10 +212,10 @@ fn test_quorum_reached_with_for_votes() {
 
     // Cast enough votes to meet quorum (12M needed from 300M total)
     let (voted, vlen) = cast_vote(
-        &data[..len], len, 0, &bob(), VOTE_FOR, 100_000_000, 2000, 300_000_000,
+        &data[..len], len, 0, &bob(), VOTE_FOR, 100_000_000, 2000,
     ).unwrap();
 
-    assert!(quorum_reached(&voted[..vlen], 0, 300_000_000));
+    assert!(quorum_reached(&voted[..vlen], 0));
 }
 
 #[test]
@@ -229,10 +230,10 @@ fn test_quorum_reached_with_abstain() {
 
     // Abstain votes count toward quorum
     let (voted, vlen) = cast_vote(
-        &data[..len], len, 0, &bob(), VOTE_ABSTAIN, 100_000_000, 2000, 300_000_000,
+        &data[..len], len, 0, &bob(), VOTE_ABSTAIN, 100_000_000, 2000,
     ).unwrap();
 
-    assert!(quorum_reached(&voted[..vlen], 0, 300_000_000));
+    assert!(quorum_reached(&voted[..vlen], 0));
 }
 
 #[test]
@@ -246,7 +247,7 @@ fn test_vote_succeeded() {
     );
 
     let (voted, vlen) = cast_vote(
-        &data[..len], len, 0, &alice(), VOTE_FOR, 200_000_000, 2000, 300_000_000,
+        &data[..len], len, 0, &alice(), VOTE_FOR, 200_000_000, 2000,
     ).unwrap();
 
     assert!(vote_succeeded(&voted[..vlen], 0));
@@ -263,11 +264,11 @@ fn test_vote_defeated() {
     );
 
     let (v1, l1) = cast_vote(
-        &data[..len], len, 0, &alice(), VOTE_FOR, 200_000_000, 2000, 500_000_000,
+        &data[..len], len, 0, &alice(), VOTE_FOR, 200_000_000, 2000,
     ).unwrap();
 
     let (v2, l2) = cast_vote(
-        &v1[..l1], l1, 0, &bob(), VOTE_AGAINST, 300_000_000, 2100, 500_000_000,
+        &v1[..l1], l1, 0, &bob(), VOTE_AGAINST, 300_000_000, 2100,
     ).unwrap();
 
     assert!(!vote_succeeded(&v2[..l2], 0));
@@ -288,7 +289,7 @@ fn test_get_vote_details() {
     );
 
     let (voted, vlen) = cast_vote(
-        &data[..len], len, 0, &bob(), VOTE_FOR, 100_000_000, 2000, 300_000_000,
+        &data[..len], len, 0, &bob(), VOTE_FOR, 100_000_000, 2000,
     ).unwrap();
 
     let vote = get_vote(&voted[..vlen], 0, &bob());
@@ -308,6 +309,73 @@ fn test_get_vote_not_found() {
     assert!(get_vote(&data[..len], 0, &bob()).is_none());
 }
 
+#[test]
+fn test_votes_beyond_max_members_are_recorded() {
+    // Vote records are no longer capped at MAX_MEMBERS per proposal
+    const N: usize = MAX_MEMBERS + 5;
+    let voters: [[u8; ACCOUNT_ID_SIZE]; N] = core::array::from_fn(|i| mock_account(i as u8 + 1));
+    let members: [(&[u8; ACCOUNT_ID_SIZE], u64, u8); N] = core::array::from_fn(|i| (&voters[i], 1, 0));
+    let (data, len) = build_dao_with_proposal(&members, 42, &voters[0], 1000, 2000, 0);
+
+    let mut d = [0u8; crate::foundation::data::DATA_CAPACITY];
+    d[..len].copy_from_slice(&data[..len]);
+    let mut l = len;
+    for voter in &voters {
+        let (nd, nl) = cast_vote(&d[..l], l, 0, voter, VOTE_FOR, 1, 1500).unwrap();
+        d[..nl].copy_from_slice(&nd[..nl]);
+        l = nl;
+    }
+
+    let last = &voters[N - 1];
+    assert!(has_voted(&d[..l], 0, last));
+    assert_eq!(get_vote(&d[..l], 0, last), Some((VOTE_FOR, 1)));
+    assert_eq!(
+        cast_vote(&d[..l], l, 0, last, VOTE_FOR, 1, 1500).err(),
+        Some(ERR_ALREADY_VOTED),
+    );
+    assert_eq!(proposal_votes(&d[..l], 0), (N as u64, 0, 0));
+    assert_invariants(&d[..l]);
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Late quorum — mirrors GovernorPreventLateQuorum
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_late_quorum_extends_voting() {
+    use crate::foundation::types::Proposal;
+
+    let members = [(&alice(), 200_000_000u64, ROLE_PROPOSER), (&bob(), 100_000_000u64, 0u8)];
+    let (data, len) = build_dao_with_proposal(&members, 42, &alice(), 1000, 100_000, 0);
+
+    // Below quorum: no extension even inside the window
+    let (d, l) = cast_vote(&data[..len], len, 0, &bob(), VOTE_AGAINST, 100_000_000, 99_000).unwrap();
+    assert_eq!(Proposal::load(&d[..l], 0).unwrap().vote_end, 100_000);
+
+    // Reaching quorum an hour before the end leaves a full extension to react
+    let (d, l) = cast_vote(&d[..l], l, 0, &alice(), VOTE_FOR, 200_000_000, 99_000).unwrap();
+    let p = Proposal::load(&d[..l], 0).unwrap();
+    assert_eq!(p.vote_end, 99_000 + LATE_QUORUM_EXTENSION);
+    assert_eq!(p.vote_extension, 99_000 + LATE_QUORUM_EXTENSION - 100_000);
+    assert_eq!(governor::get_proposal_state(&d[..l], 0, 100_001), PROPOSAL_STATE_ACTIVE);
+    assert_eq!(governor::get_proposal_state(&d[..l], 0, p.vote_end + 1), PROPOSAL_STATE_SUCCEEDED);
+
+    // Quorum is only reached once, so later votes do not extend again
+    let (d, l) = cast_vote(&d[..l], l, 0, &carol(), VOTE_FOR, 1, p.vote_end).unwrap();
+    assert_eq!(Proposal::load(&d[..l], 0).unwrap().vote_end, p.vote_end);
+}
+
+#[test]
+fn test_early_quorum_does_not_extend() {
+    let members = [(&alice(), 200_000_000u64, ROLE_PROPOSER)];
+    let (data, len) = build_dao_with_proposal(&members, 42, &alice(), 1000, 260_000, 0);
+    let (d, l) = cast_vote(&data[..len], len, 0, &alice(), VOTE_FOR, 200_000_000, 2000).unwrap();
+
+    let p = crate::foundation::types::Proposal::load(&d[..l], 0).unwrap();
+    assert_eq!((p.vote_end, p.vote_extension), (260_000, 0));
+    assert!(crate::foundation::data::find_value(&d[..l], b"prop_0_ext").is_none());
+}
+
 // ═══════════════════════════════════════════════════════════════════════
 // Full lifecycle: propose → vote → state check
 // ═══════════════════════════════════════════════════════════════════════
@@ -319,26 +387,27 @@ fn test_proposal_succeeds_after_voting() {
         (&bob(), 100_000_000u64, 0u8),
         (&carol(), 150_000_000u64, 0u8),
     ];
-    let total_vp = 450_000_000u64;
     let (data, len) = build_dao_with_proposal(
         &members, 42, &alice(), 1000, 2000, 0,
     );
 
     // Alice votes FOR (200M), Bob votes AGAINST (100M)
     let (d1, l1) = cast_vote(
-        &data[..len], len, 0, &alice(), VOTE_FOR, 200_000_000, 1500, total_vp,
+        &data[..len], len, 0, &alice(), VOTE_FOR, 200_000_000, 1500,
     ).unwrap();
 
     let (d2, l2) = cast_vote(
-        &d1[..l1], l1, 0, &bob(), VOTE_AGAINST, 100_000_000, 1600, total_vp,
+        &d1[..l1], l1, 0, &bob(), VOTE_AGAINST, 100_000_000, 1600,
     ).unwrap();
 
     // Carol votes FOR (150M)
     let (d3, l3) = cast_vote(
-        &d2[..l2], l2, 0, &carol(), VOTE_FOR, 150_000_000, 1700, total_vp,
+        &d2[..l2], l2, 0, &carol(), VOTE_FOR, 150_000_000, 1700,
     ).unwrap();
 
-    // After voting ends (time > 2000)
-    let state = governor::get_proposal_state(&d3[..l3], 0, 3000, total_vp);
+    // Alice's vote reached quorum late, so voting ends a day after it
+    let state = governor::get_proposal_state(&d3[..l3], 0, 3000);
+    assert_eq!(state, PROPOSAL_STATE_ACTIVE);
+    let state = governor::get_proposal_state(&d3[..l3], 0, 1500 + LATE_QUORUM_EXTENSION + 1);
     assert_eq!(state, PROPOSAL_STATE_SUCCEEDED);
 }
@@ -1,4 +1,5 @@
 use crate::foundation::config::*;
+use crate::foundation::types::Actions;
 use crate::governance::governor::*;
 use crate::tests::*;
 
@@ -15,17 +16,18 @@ fn test_propose_success() {
 
     let result = propose(
         &data[..len], len, &alice(),
-        12345, 1000, 200_000_000,
+        &mock_desc(12345), &Actions::new(), 1000, 200_000_000,
     );
     assert!(result.is_ok());
 
     let (new_data, new_len, prop_id) = result.unwrap();
-    assert!(prop_id != 0);
+    assert!(prop_id != [0u8; HASH_SIZE]);
 
     // Verify proposal was stored
-    let idx = find_proposal_by_id(&new_data[..new_len], prop_id);
+    let idx = find_proposal_by_id(&new_data[..new_len], &prop_id);
     assert!(idx.is_ok());
     assert_eq!(idx.unwrap(), 0);
+    assert_invariants(&new_data[..new_len]);
 }
 
 #[test]
@@ -36,7 +38,7 @@ fn test_propose_below_threshold() {
 
     let result = propose(
         &data[..len], len, &alice(),
-        12345, 1000, 50_000_000,
+        &mock_desc(12345), &Actions::new(), 1000, 50_000_000,
     );
     assert_eq!(result, Err(ERR_BELOW_THRESHOLD));
 }
@@ -49,30 +51,43 @@ fn test_propose_at_exact_threshold() {
 
     let result = propose(
         &data[..len], len, &alice(),
-        12345, 1000, PROPOSAL_THRESHOLD,
+        &mock_desc(12345), &Actions::new(), 1000, PROPOSAL_THRESHOLD,
     );
     assert!(result.is_ok());
 }
 
+#[test]
+fn test_propose_near_max_time_overflows() {
+    let (data, len) = build_dao_data(&[(&alice(), PROPOSAL_THRESHOLD, ROLE_PROPOSER)]);
+    let result = propose(
+        &data[..len], len, &alice(),
+        &mock_desc(12345), &Actions::new(), u32::MAX - VOTING_DELAY, PROPOSAL_THRESHOLD,
+    );
+    assert_eq!(result, Err(ERR_OVERFLOW));
+}
+
 #[test]
 fn test_propose_multiple() {
-    let (data, len) = build_dao_data(&[
+    let (base, mut len) = build_dao_data(&[
         (&alice(), 500_000_000, ROLE_PROPOSER | ROLE_ADMIN),
     ]);
-
-    // First proposal
-    let (data1, len1, id1) = propose(
-        &data[..len], len, &alice(), 111, 1000, 500_000_000,
-    ).unwrap();
-
-    // Second proposal
-    let (data2, len2, id2) = propose(
-        &data1[..len1], len1, &alice(), 222, 2000, 500_000_000,
-    ).unwrap();
-
-    assert_ne!(id1, id2);
-    assert!(find_proposal_by_id(&data2[..len2], id1).is_ok());
-    assert!(find_proposal_by_id(&data2[..len2], id2).is_ok());
+    let mut data = [0u8; crate::foundation::data::DATA_CAPACITY];
+    data[..len].copy_from_slice(&base[..len]);
+
+    // Fill every proposal slot; each gets its own ID
+    let mut ids = [[0u8; HASH_SIZE]; MAX_PROPOSALS];
+    for (i, id) in ids.iter_mut().enumerate() {
+        (data, len, *id) = propose(
+            &data[..len], len, &alice(), &mock_desc(i as u32), &Actions::new(), 1000 + i as u32, 500_000_000,
+        ).unwrap();
+    }
+
+    for (i, id) in ids.iter().enumerate() {
+        assert!(ids[..i].iter().all(|other| other != id));
+        assert_eq!(find_proposal_by_id(&data[..len], id), Ok(i as u16));
+    }
+    let result = propose(&data[..len], len, &alice(), &mock_desc(99), &Actions::new(), 2000, 500_000_000);
+    assert_eq!(result.err(), Some(ERR_MAX_PROPOSALS));
 }
 
 // ═══════════════════════════════════════════════════════════════════════
@@ -92,7 +107,7 @@ fn test_state_pending_before_vote_start() {
         0, // stored state = Pending
     );
 
-    let state = get_proposal_state(&data[..len], 0, 1000, 300_000_000);
+    let state = get_proposal_state(&data[..len], 0, 1000);
     assert_eq!(state, PROPOSAL_STATE_PENDING);
 }
 
@@ -106,7 +121,7 @@ fn test_state_active_during_voting() {
         &members, 42, &alice(), 1000, 260000, 0,
     );
 
-    let state = get_proposal_state(&data[..len], 0, 5000, 300_000_000);
+    let state = get_proposal_state(&data[..len], 0, 5000);
     assert_eq!(state, PROPOSAL_STATE_ACTIVE);
 }
 
@@ -121,7 +136,7 @@ fn test_state_defeated_no_quorum() {
     );
 
     // After voting ends, no votes cast → no quorum → defeated
-    let state = get_proposal_state(&data[..len], 0, 3000, 300_000_000);
+    let state = get_proposal_state(&data[..len], 0, 3000);
     assert_eq!(state, PROPOSAL_STATE_DEFEATED);
 }
 
@@ -136,7 +151,7 @@ fn test_state_canceled_overrides_time() {
     );
 
     // Even though time says Active, stored Canceled wins
-    let state = get_proposal_state(&data[..len], 0, 5000, 200_000_000);
+    let state = get_proposal_state(&data[..len], 0, 5000);
     assert_eq!(state, PROPOSAL_STATE_CANCELED);
 }
 
@@ -148,7 +163,7 @@ fn test_state_executed_overrides() {
         PROPOSAL_STATE_EXECUTED,
     );
 
-    let state = get_proposal_state(&data[..len], 0, 5000, 200_000_000);
+    let state = get_proposal_state(&data[..len], 0, 5000);
     assert_eq!(state, PROPOSAL_STATE_EXECUTED);
 }
 
@@ -166,11 +181,11 @@ fn test_cancel_by_proposer() {
         &members, 42, &alice(), 5000, 265000, 0,
     );
 
-    let result = cancel_proposal(&data[..len], len, 0, &alice(), 1000, 200_000_000);
+    let result = cancel_proposal(&data[..len], len, 0, &alice(), 1000);
     assert!(result.is_ok());
 
     let (new_data, new_len) = result.unwrap();
-    let state = get_proposal_state(&new_data[..new_len], 0, 1000, 200_000_000);
+    let state = get_proposal_state(&new_data[..new_len], 0, 1000);
     assert_eq!(state, PROPOSAL_STATE_CANCELED);
 }
 
@@ -184,7 +199,7 @@ fn test_cancel_by_non_proposer_fails() {
         &members, 42, &alice(), 5000, 265000, 0,
     );
 
-    let result = cancel_proposal(&data[..len], len, 0, &bob(), 1000, 300_000_000);
+    let result = cancel_proposal(&data[..len], len, 0, &bob(), 1000);
     assert_eq!(result, Err(ERR_NOT_PROPOSER));
 }
 
@@ -198,10 +213,58 @@ fn test_cancel_active_proposal_fails() {
         &members, 42, &alice(), 1000, 260000, 0,
     );
 
-    let result = cancel_proposal(&data[..len], len, 0, &alice(), 2000, 200_000_000);
+    let result = cancel_proposal(&data[..len], len, 0, &alice(), 2000);
     assert_eq!(result, Err(ERR_PROPOSAL_NOT_ACTIVE));
 }
 
+#[test]
+fn test_canceller_cancels_through_succeeded() {
+    let members = [
+        (&alice(), 200_000_000u64, ROLE_PROPOSER),
+        (&carol(), 0u64, ROLE_CANCELLER),
+    ];
+    let (data, len) = build_dao_with_proposal(&members, 42, &alice(), 1000, 2000, 0);
+    let (voted, vlen) = crate::governance::counting::cast_vote(
+        &data[..len], len, 0, &alice(), VOTE_FOR, 200_000_000, 1500,
+    ).unwrap();
+    let ended = 1500 + LATE_QUORUM_EXTENSION + 1;
+
+    for (d, time) in [(&data[..len], 500), (&data[..len], 1500), (&voted[..vlen], ended)] {
+        let (new_data, new_len) = cancel_proposal(d, d.len(), 0, &carol(), time).unwrap();
+        assert_eq!(get_proposal_state(&new_data[..new_len], 0, time), PROPOSAL_STATE_CANCELED);
+    }
+
+    // Nothing left to stop once the vote is lost or the proposal is canceled
+    let (canceled, clen) = cancel_proposal(&data[..len], len, 0, &carol(), 500).unwrap();
+    assert_eq!(cancel_proposal(&canceled[..clen], clen, 0, &carol(), 500), Err(ERR_PROPOSAL_NOT_ACTIVE));
+    assert_eq!(cancel_proposal(&data[..len], len, 0, &carol(), 3000), Err(ERR_PROPOSAL_NOT_ACTIVE));
+}
+
+#[test]
+fn test_canceller_cancels_queued_operation() {
+    use crate::foundation::types::TimelockOp;
+    use crate::timelock::controller;
+
+    let members = [
+        (&alice(), 200_000_000u64, ROLE_PROPOSER | ROLE_EXECUTOR),
+        (&carol(), 0u64, ROLE_CANCELLER),
+    ];
+    let (data, len) = build_dao_with_proposal(&members, 42, &alice(), 1000, 2000, 4);
+    let (d, l, _) = controller::schedule(&data[..len], len, &mock_id(42), 3000, TIMELOCK_MIN_DELAY).unwrap();
+    let (d, l) = mark_queued(&d[..l], l, 0, 3000 + TIMELOCK_MIN_DELAY).unwrap();
+
+    // The proposer cannot stop a queued proposal; the canceller can
+    assert_eq!(cancel_proposal(&d[..l], l, 0, &alice(), 4000), Err(ERR_PROPOSAL_NOT_ACTIVE));
+    let (c, cl) = cancel_proposal(&d[..l], l, 0, &carol(), 4000).unwrap();
+    assert_eq!(get_proposal_state(&c[..cl], 0, 4000), PROPOSAL_STATE_CANCELED);
+    assert_eq!(TimelockOp::load(&c[..cl], 0).unwrap().state, OP_STATE_UNSET);
+    assert_invariants(&c[..cl]);
+
+    // An operation past its grace period can no longer be canceled
+    let expired = 3000 + TIMELOCK_MIN_DELAY + TIMELOCK_GRACE_PERIOD + 1;
+    assert_eq!(cancel_proposal(&d[..l], l, 0, &carol(), expired), Err(ERR_OP_NOT_READY));
+}
+
 // ═══════════════════════════════════════════════════════════════════════
 // Helper function tests
 // ═══════════════════════════════════════════════════════════════════════
@@ -219,5 +282,168 @@ fn test_build_prop_key() {
 #[test]
 fn test_find_proposal_not_found() {
     let (data, len) = build_dao_data(&[(&alice(), 200_000_000, 0)]);
-    assert_eq!(find_proposal_by_id(&data[..len], 999), Err(ERR_PROPOSAL_NOT_FOUND));
+    assert_eq!(find_proposal_by_id(&data[..len], &mock_id(999)), Err(ERR_PROPOSAL_NOT_FOUND));
+}
+
+#[test]
+fn test_verify_description() {
+    let (data, len) = build_dao_data(&[(&alice(), 200_000_000, ROLE_PROPOSER)]);
+    let desc_hash = crate::crypto::hash::hash_description(b"Fund the grants pool");
+    let (d, l, id) = propose(&data[..len], len, &alice(), &desc_hash, &Actions::new(), 1000, 200_000_000).unwrap();
+
+    assert_eq!(verify_description(&d[..l], &id, b"Fund the grants pool"), Ok(()));
+    assert_eq!(verify_description(&d[..l], &id, b"Fund the grants pool!"), Err(ERR_DESCRIPTION_MISMATCH));
+    assert_eq!(verify_description(&d[..l], &mock_id(1), b"Fund the grants pool"), Err(ERR_PROPOSAL_NOT_FOUND));
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Data field capacity
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_propose_on_full_data_returns_data_full() {
+    use crate::foundation::data::*;
+
+    let (data, len) = build_dao_data(&[
+        (&alice(), 200_000_000, ROLE_PROPOSER | ROLE_ADMIN),
+    ]);
+
+    // Pad the blob so only a few bytes remain free
+    let mut store = Store::load(&data[..len]);
+    let pad = [b'p'; DATA_CAPACITY];
+    store.set(b"pad", &pad[..store.available() - 5]).unwrap();
+    let (full, full_len) = store.commit();
+
+    let result = propose(&full[..full_len], full_len, &alice(), &mock_desc(12345), &Actions::new(), 1000, 200_000_000);
+    assert_eq!(result.err(), Some(ERR_DATA_FULL));
+}
+
+/// The largest state the limits allow must fit the Data field: every
+/// member votes on every proposal, each proposal executes a full batch,
+/// every vote history holds MAX_CHECKPOINTS entries and the archive is
+/// full, with every amount and setting at its widest.
+#[test]
+fn test_full_dao_fits_data_field() {
+    use crate::foundation::data::*;
+    use crate::foundation::genesis::{initialize, Genesis};
+    use crate::foundation::types::{Action, Member, Proposal};
+    use crate::governance::{counting, prune::prune, settings::Settings, votes};
+    use crate::timelock::controller;
+    use crate::token::xrp_votes;
+
+    // Every amount and setting at its widest. Round numbers encode
+    // shorter under compact-state, so stay one below each maximum.
+    let power = MAX_XRP_DROPS / MAX_MEMBERS as u64 - 1;
+    let settings = Settings {
+        voting_delay: MAX_VOTING_DELAY - 1,
+        voting_period: MAX_VOTING_PERIOD - 1,
+        proposal_threshold: power,
+        quorum_percentage: MAX_QUORUM_PERCENTAGE,
+        timelock_min_delay: MAX_TIMELOCK_DELAY - 1,
+        timelock_grace_period: MAX_GRACE_PERIOD - 1,
+        late_quorum_window: MAX_LATE_QUORUM_WINDOW - 1,
+        late_quorum_extension: MAX_LATE_QUORUM_EXTENSION - 1,
+    };
+    let mut genesis = Genesis {
+        admin: mock_account(1),
+        members: [Member::new(); MAX_MEMBERS],
+        member_count: MAX_MEMBERS as u16,
+        settings,
+    };
+    for (i, member) in genesis.members.iter_mut().enumerate() {
+        *member = Member { account: mock_account(i as u8 + 1), voting_power: power, roles: ALL_ROLES };
+    }
+    let start = u32::MAX - 30_000_000;
+    let (mut data, mut len) = initialize(&[], 0, &genesis, start).unwrap();
+
+    // Full archive history: MAX_ARCHIVED digests plus the folded root
+    for i in 0..=MAX_ARCHIVED as u32 {
+        (data, len, _) = propose(&data[..len], len, &mock_account(1), &mock_desc(1000 + i), &Actions::new(), start, power).unwrap();
+        (data, len) = cancel_proposal(&data[..len], len, 0, &mock_account(1), start).unwrap();
+        (data, len, _) = prune(&data[..len], len, start).unwrap();
+    }
+
+    // Every proposal carries a full batch of payments, each to a new
+    // account and with a full payload
+    let mut ids = [[0u8; HASH_SIZE]; MAX_PROPOSALS];
+    for (j, id) in ids.iter_mut().enumerate() {
+        let mut actions = Actions::new();
+        for k in 0..MAX_BATCH_OPS {
+            actions.push(Action {
+                target: mock_account(255 - (j * MAX_BATCH_OPS + k) as u8),
+                amount: MAX_XRP_DROPS - 1,
+                payload: [0xff; MAX_ACTION_PAYLOAD],
+                payload_len: MAX_ACTION_PAYLOAD as u8,
+                ..Action::new()
+            }).unwrap();
+        }
+        (data, len, *id) = propose(&data[..len], len, &mock_account(1), &mock_desc(j as u32), &actions, start + 1, power).unwrap();
+    }
+
+    // Each member delegates to the next, then every vote history and the
+    // total supply churn to MAX_CHECKPOINTS entries while the proposals
+    // are Pending
+    for i in 0..MAX_MEMBERS {
+        let to = mock_account(((i + 1) % MAX_MEMBERS) as u8 + 1);
+        (data, len) = xrp_votes::delegate(&data[..len], len, &mock_account(i as u8 + 1), &to, start + 2).unwrap();
+    }
+    for round in 0..MAX_CHECKPOINTS {
+        let churned = power - (MAX_CHECKPOINTS - 1 - round) as u64;
+        for i in 0..MAX_MEMBERS {
+            (data, len) = votes::set_member(&data[..len], len, &mock_account(i as u8 + 1), churned, ALL_ROLES, start + 3 + round as u32).unwrap();
+        }
+    }
+
+    // Every member votes on every proposal in its last second; the last
+    // vote reaches quorum and extends the vote by the most it can
+    let voting = start + MAX_VOTING_DELAY + MAX_VOTING_PERIOD - 1;
+    for (j, id) in ids.iter().enumerate() {
+        for i in 0..MAX_MEMBERS {
+            let weight = xrp_votes::get_proposal_votes(&data[..len], id, &mock_account(i as u8 + 1));
+            (data, len) = counting::cast_vote(&data[..len], len, j as u16, &mock_account(i as u8 + 1), VOTE_FOR, weight, voting).unwrap();
+        }
+    }
+
+    // Queue and execute them all, recording a result per action
+    let queued = voting + MAX_LATE_QUORUM_EXTENSION;
+    let ready = queued + MAX_TIMELOCK_DELAY - 1;
+    for (j, id) in ids.iter().enumerate() {
+        assert_eq!(get_proposal_state(&data[..len], j as u16, queued), PROPOSAL_STATE_SUCCEEDED);
+        (data, len, _) = controller::schedule(&data[..len], len, id, queued, MAX_TIMELOCK_DELAY - 1).unwrap();
+        (data, len) = mark_queued(&data[..len], len, j as u16, ready).unwrap();
+    }
+    for (j, id) in ids.iter().enumerate() {
+        let op = controller::find_operation_by_proposal(&data[..len], id).unwrap();
+        (data, len) = controller::execute(&data[..len], len, op, ready).unwrap();
+        let mut store = Store::load(&data[..len]);
+        let mut proposal = Proposal::load(&data[..len], j as u16).unwrap();
+        proposal.state = PROPOSAL_STATE_EXECUTED;
+        proposal.store(&mut store, j as u16).unwrap();
+        for k in 0..MAX_BATCH_OPS {
+            Actions::store_result(&mut store, j as u16, k as u16, i32::MIN).unwrap();
+        }
+        (data, len) = store.commit();
+    }
+
+    // Execution briefly adds the lock and `_exec` marker on top
+    let mut store = Store::load(&data[..len]);
+    store.set(b"_lock", b"1").unwrap();
+    let mut marker = [0u8; HASH_SIZE * 2];
+    crate::crypto::hex::encode_hex(&ids[0], &mut marker);
+    store.set(b"_exec", &marker).unwrap();
+    #[cfg(not(feature = "compact-state"))]
+    assert!(store.len() <= FIELD_CAPACITY);
+    #[cfg(feature = "compact-state")]
+    {
+        // Only the encoded form fits: the text is about twice the field
+        assert!(store.len() > FIELD_CAPACITY);
+        let mut blob = [0u8; FIELD_CAPACITY];
+        assert!(crate::foundation::codec::encode(store.as_bytes(), &mut blob).is_ok());
+    }
+
+    let ckpt_total = find_value(&data[..len], b"ckpt_total").unwrap();
+    assert_eq!(ckpt_total.split(|&b| b == b',').count(), MAX_CHECKPOINTS);
+    assert_invariants(&data[..len]);
+    let result = propose(&data[..len], len, &mock_account(1), &mock_desc(99), &Actions::new(), ready, power);
+    assert_eq!(result.err(), Some(ERR_MAX_PROPOSALS));
 }
@@ -2,4 +2,6 @@ pub mod governor_tests;
 pub mod counting_tests;
 pub mod votes_tests;
 pub mod security_tests;
+pub mod prune_tests;
 
+pub mod settings_tests;
@@ -0,0 +1,318 @@
+use crate::foundation::config::*;
+use crate::foundation::data::*;
+use crate::foundation::types::Actions;
+use crate::governance::prune::*;
+use crate::governance::{counting, governor, signatures};
+use crate::timelock::{controller, operations};
+use crate::token::xrp_votes;
+use crate::crypto::hash::{hash_archive, hash_archive_root};
+use crate::tests::*;
+
+fn base_dao() -> ([u8; 4096], usize) {
+    build_dao_data(&[
+        (&alice(), 500_000_000, ROLE_PROPOSER | ROLE_EXECUTOR | ROLE_ADMIN),
+        (&bob(), 300_000_000, ROLE_PROPOSER),
+        (&carol(), 200_000_000, ROLE_PROPOSER),
+    ])
+}
+
+/// Queue proposal `index` (must be Succeeded) at `now`. Returns the op index.
+fn queue(data: &[u8], len: usize, index: u16, now: u32) -> ([u8; DATA_CAPACITY], usize, u16) {
+    let id = proposal_id_at(data, index);
+    let (d, l, _) = controller::schedule(&data[..len], len, &id, now, TIMELOCK_MIN_DELAY).unwrap();
+    let (d, l) = governor::update_proposal_field(&d[..l], l, index, b"_state", b"5").unwrap();
+    let op = controller::find_operation_by_proposal(&d[..l], &id).unwrap();
+    (d, l, op)
+}
+
+fn proposal_id_at(data: &[u8], index: u16) -> [u8; HASH_SIZE] {
+    let mut key = [0u8; 32];
+    let klen = governor::build_prop_key(b"prop_", index, b"_id", &mut key);
+    crate::foundation::abi::decode_hash(find_value(data, &key[..klen]).unwrap()).unwrap()
+}
+
+/// Propose past this build's proposal limit, as state written under larger
+/// `LIMITS` can hold: the proposal is made in a fresh `base_dao` and stored
+/// at the next index of `data`.
+fn propose_past_limit(
+    data: &[u8],
+    len: usize,
+    proposer: &[u8; ACCOUNT_ID_SIZE],
+    seed: u32,
+    now: u32,
+    power: u64,
+) -> ([u8; DATA_CAPACITY], usize, [u8; HASH_SIZE]) {
+    use crate::foundation::types::Proposal;
+
+    let (base, base_len) = base_dao();
+    let (fresh, fresh_len, id) = governor::propose(&base[..base_len], base_len, proposer, &mock_desc(seed), &Actions::new(), now, power).unwrap();
+    let count = governor::read_count(&data[..len], b"proposal_count");
+    let mut store = Store::load(&data[..len]);
+    governor::write_count(&mut store, b"proposal_count", count + 1).unwrap();
+    Proposal::load(&fresh[..fresh_len], 0).unwrap().store(&mut store, count).unwrap();
+    Actions::new().store(&mut store, count).unwrap();
+    let (d, l) = store.commit();
+    (d, l, id)
+}
+
+/// p0 canceled, p1 executed (with op, snapshot and sig intent), p2 active
+/// with one vote. Returns (data, len, now, [p0, p1, p2] ids).
+fn build_mixed_dao() -> ([u8; DATA_CAPACITY], usize, u32, [[u8; HASH_SIZE]; 3]) {
+    let (data, len) = base_dao();
+    let (d, l, p0) = governor::propose(&data[..len], len, &alice(), &mock_desc(1), &Actions::new(), 1000, 500_000_000).unwrap();
+    let (d, l, p1) = propose_past_limit(&d, l, &alice(), 2, 1000, 500_000_000);
+    let (d, l) = governor::cancel_proposal(&d[..l], l, 0, &alice(), 1000).unwrap();
+
+    let (d, l) = xrp_votes::snapshot_voting_power(&d[..l], l, &p1, &alice()).unwrap();
+    let (d, l) = signatures::record_sig_vote_intent(&d[..l], l, &p1, 1, &bob()).unwrap();
+    let (d, l) = counting::cast_vote(&d[..l], l, 1, &alice(), 1, 500_000_000, 1400).unwrap();
+    let (d, l) = counting::cast_vote(&d[..l], l, 1, &bob(), 1, 300_000_000, 1400).unwrap();
+
+    let t_queue = 1300 + VOTING_PERIOD + 1;
+    let (d, l, op) = queue(&d, l, 1, t_queue);
+    let t_exec = t_queue + TIMELOCK_MIN_DELAY;
+    let (d, l) = controller::execute(&d[..l], l, op, t_exec).unwrap();
+    let (d, l) = governor::update_proposal_field(&d[..l], l, 1, b"_state", b"7").unwrap();
+
+    let (d, l, p2) = propose_past_limit(&d, l, &bob(), 3, t_exec, 300_000_000);
+    let now = t_exec + VOTING_DELAY + 100;
+    let (d, l) = counting::cast_vote(&d[..l], l, 2, &carol(), 0, 200_000_000, now).unwrap();
+
+    (d, l, now, [p0, p1, p2])
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// prune() tests
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_prune_removes_finished_and_renumbers() {
+    let (data, len, now, [p0, p1, p2]) = build_mixed_dao();
+    assert_invariants(&data[..len]);
+    let (new_data, new_len, pruned) = prune(&data[..len], len, now).unwrap();
+    let d = &new_data[..new_len];
+    assert_invariants(d);
+
+    assert_eq!(pruned, 2);
+    assert_eq!(find_value(d, b"proposal_count"), Some(b"1" as &[u8]));
+    assert_eq!(find_value(d, b"op_count"), Some(b"0" as &[u8]));
+
+    // Survivor moved to index 0 with its vote
+    assert_eq!(governor::find_proposal_by_id(d, &p2), Ok(0));
+    assert_eq!(governor::find_proposal_by_id(d, &p0), Err(ERR_PROPOSAL_NOT_FOUND));
+    assert_eq!(governor::find_proposal_by_id(d, &p1), Err(ERR_PROPOSAL_NOT_FOUND));
+    assert!(counting::has_voted(d, 0, &carol()));
+    assert_eq!(find_value(d, b"prop_1_id"), None);
+    assert_eq!(find_value(d, b"prop_2_id"), None);
+    assert_eq!(find_value(d, b"vote_1_0"), None);
+    assert_eq!(find_value(d, b"op_0_id"), None);
+
+    // Per-id entries of pruned proposals are gone
+    assert_eq!(iter_prefix(d, b"snap_").count(), 0);
+    assert_eq!(iter_prefix(d, b"sigvote_").count(), 0);
+
+    // Members untouched
+    assert_eq!(find_value(d, b"member_count"), Some(b"3" as &[u8]));
+    assert_eq!(get_archived(d, &p0).map(|(s, _)| s), Some(PROPOSAL_STATE_CANCELED));
+    assert_eq!(get_archived(d, &p1).map(|(s, _)| s), Some(PROPOSAL_STATE_EXECUTED));
+    assert_eq!(get_archived(d, &p2), None);
+}
+
+#[test]
+fn test_prune_digest_matches_final_result() {
+    let (data, len, now, [_, p1, _]) = build_mixed_dao();
+    let (new_data, new_len, _) = prune(&data[..len], len, now).unwrap();
+
+    let (state, digest) = get_archived(&new_data[..new_len], &p1).unwrap();
+    let expected = hash_archive(&p1, &alice(), &mock_desc(2), state, [800_000_000, 0, 0]);
+    assert_eq!(digest, expected);
+
+    // A different tally does not verify
+    let forged = hash_archive(&p1, &alice(), &mock_desc(2), state, [800_000_001, 0, 0]);
+    assert_ne!(digest, forged);
+}
+
+#[test]
+fn test_prune_folds_old_digests_into_root() {
+    let (base, mut len) = base_dao();
+    let mut data = [0u8; DATA_CAPACITY];
+    data[..len].copy_from_slice(&base[..len]);
+
+    // Archive one proposal per round, MAX_ARCHIVED + 2 rounds
+    let mut ids = [[0u8; HASH_SIZE]; MAX_ARCHIVED + 2];
+    let mut digests = [[0u8; HASH_SIZE]; MAX_ARCHIVED + 2];
+    for (round, id) in ids.iter_mut().enumerate() {
+        let (d, l, p) = governor::propose(&data[..len], len, &alice(), &mock_desc(round as u32), &Actions::new(), 1000, 500_000_000).unwrap();
+        let (d, l) = governor::cancel_proposal(&d[..l], l, 0, &alice(), 1000).unwrap();
+        let (d, l, pruned) = prune(&d[..l], l, 1001).unwrap();
+        assert_eq!(pruned, 1);
+        digests[round] = get_archived(&d[..l], &p).unwrap().1;
+        *id = p;
+        data[..l].copy_from_slice(&d[..l]);
+        len = l;
+    }
+    let d = &data[..len];
+
+    // The two oldest are folded, in order; the rest stay by ID
+    assert_eq!(get_archived(d, &ids[0]), None);
+    assert_eq!(get_archived(d, &ids[1]), None);
+    for id in &ids[2..] {
+        assert!(get_archived(d, id).is_some());
+    }
+    let root = hash_archive_root(&hash_archive_root(&[0u8; HASH_SIZE], &digests[0]), &digests[1]);
+    assert_eq!(get_archive_root(d), Some((2, root)));
+    assert_eq!(iter_prefix(d, b"hist_").count(), MAX_ARCHIVED + 1);
+}
+
+#[test]
+fn test_prune_with_nothing_finished_is_a_no_op() {
+    let (data, len) = base_dao();
+    let (d, l, _) = governor::propose(&data[..len], len, &alice(), &mock_desc(1), &Actions::new(), 1000, 500_000_000).unwrap();
+
+    let (new_data, new_len, pruned) = prune(&d[..l], l, 1400).unwrap();
+    assert_eq!(pruned, 0);
+    assert_eq!(&new_data[..new_len], &d[..l]);
+}
+
+#[test]
+fn test_prune_compacts_checkpoints_to_oldest_open_proposal() {
+    let (data, len) = base_dao();
+    let mut store = Store::load(&data[..len]);
+    xrp_votes::checkpoint_votes(&mut store, &bob(), 0).unwrap();
+    let (d, l) = store.commit();
+    // Bob: 300 XRP from 0, delegated away at 500, back at 2000
+    let (d, l) = xrp_votes::delegate(&d[..l], l, &bob(), &alice(), 500).unwrap();
+    let (d, l, _) = governor::propose(&d[..l], l, &alice(), &mock_desc(1), &Actions::new(), 1000, 500_000_000).unwrap();
+    let (d, l) = xrp_votes::delegate(&d[..l], l, &bob(), &bob(), 2000).unwrap();
+    let vote_start = 1000 + VOTING_DELAY;
+
+    // While p0 is open, lookups from its vote_start on still resolve
+    let (open, open_len, pruned) = prune(&d[..l], l, vote_start + 1).unwrap();
+    assert_eq!(pruned, 0);
+    let open = &open[..open_len];
+    assert_eq!(xrp_votes::get_past_votes(open, &bob(), 0), 0);
+    assert_eq!(xrp_votes::get_past_votes(open, &bob(), vote_start), 0);
+    assert_eq!(xrp_votes::get_past_votes(open, &bob(), 2000), 300_000_000);
+
+    // Once it is pruned only the checkpoint in force now remains
+    let (closed, closed_len, pruned) = prune(open, open_len, vote_start + VOTING_PERIOD + 1).unwrap();
+    assert_eq!(pruned, 1);
+    let mut key = [0u8; 45];
+    key[..5].copy_from_slice(b"ckpt_");
+    crate::crypto::hex::encode_hex(&bob(), &mut key[5..]);
+    assert_eq!(find_value(&closed[..closed_len], &key), Some(&b"2000:300000000"[..]));
+}
+
+#[test]
+fn test_prune_frees_proposal_slots() {
+    let (base, mut len) = base_dao();
+    let mut data = [0u8; DATA_CAPACITY];
+    data[..len].copy_from_slice(&base[..len]);
+    for i in 0..LIMITS.max_proposals {
+        let (d, l, _) = governor::propose(&data[..len], len, &alice(), &mock_desc(i as u32), &Actions::new(), 1000, 500_000_000).unwrap();
+        let (d, l) = governor::cancel_proposal(&d[..l], l, i, &alice(), 1000).unwrap();
+        data[..l].copy_from_slice(&d[..l]);
+        len = l;
+    }
+    assert_eq!(
+        governor::propose(&data[..len], len, &alice(), &mock_desc(99), &Actions::new(), 1000, 500_000_000).map(|_| ()),
+        Err(ERR_MAX_PROPOSALS),
+    );
+
+    let (d, l, pruned) = prune(&data[..len], len, 1001).unwrap();
+    assert_eq!(pruned as usize, MAX_PROPOSALS);
+    assert!(governor::propose(&d[..l], l, &alice(), &mock_desc(99), &Actions::new(), 1001, 500_000_000).is_ok());
+}
+
+#[test]
+fn test_prune_legacy_state_above_max_proposals() {
+    use crate::foundation::types::Proposal;
+
+    // Ten proposals, the limit before LIMITS were lowered: nine canceled,
+    // the last still Pending
+    let (base, len) = base_dao();
+    let mut store = Store::load(&base[..len]);
+    for i in 0..10u16 {
+        let state = if i < 9 { PROPOSAL_STATE_CANCELED } else { PROPOSAL_STATE_PENDING };
+        let proposal = Proposal { id: mock_id(i as u32 + 1), proposer: alice(), state, vote_start: 5000, vote_end: 6000, ..Proposal::new() };
+        proposal.store(&mut store, i).unwrap();
+    }
+    governor::write_count(&mut store, b"proposal_count", 10).unwrap();
+    let (data, len) = store.commit();
+
+    let (d, l, pruned) = prune(&data[..len], len, 1000).unwrap();
+    assert_eq!(pruned, 9);
+    assert_eq!(governor::read_count(&d[..l], b"proposal_count"), 1);
+    assert_eq!(Proposal::load(&d[..l], 0).unwrap().id, mock_id(10));
+    assert_eq!(get_archived(&d[..l], &mock_id(9)).map(|(state, _)| state), Some(PROPOSAL_STATE_CANCELED));
+    assert_eq!(get_archive_root(&d[..l]).map(|(count, _)| count), Some(9 - MAX_ARCHIVED as u32));
+}
+
+#[test]
+fn test_prune_defeated_after_voting_ends() {
+    let (data, len) = base_dao();
+    let (d, l, id) = governor::propose(&data[..len], len, &alice(), &mock_desc(1), &Actions::new(), 1000, 500_000_000).unwrap();
+
+    // Still active: kept
+    let (_, _, pruned) = prune(&d[..l], l, 1400).unwrap();
+    assert_eq!(pruned, 0);
+
+    // No votes → Defeated once voting ends
+    let after = 1300 + VOTING_PERIOD + 1;
+    let (nd, nl, pruned) = prune(&d[..l], l, after).unwrap();
+    assert_eq!(pruned, 1);
+    assert_eq!(get_archived(&nd[..nl], &id).map(|(s, _)| s), Some(PROPOSAL_STATE_DEFEATED));
+}
+
+#[test]
+fn test_prune_archives_queued_proposal_with_expired_op_as_expired() {
+    let (data, len) = base_dao();
+    let (d, l, id) = governor::propose(&data[..len], len, &alice(), &mock_desc(1), &Actions::new(), 1000, 500_000_000).unwrap();
+    let (d, l) = counting::cast_vote(&d[..l], l, 0, &alice(), 1, 500_000_000, 1400).unwrap();
+    let t_queue = 1300 + VOTING_PERIOD + 1;
+    let (d, l, _) = queue(&d, l, 0, t_queue);
+
+    // Within the grace period the queued proposal stays
+    let ready = t_queue + TIMELOCK_MIN_DELAY;
+    let (_, _, pruned) = prune(&d[..l], l, ready).unwrap();
+    assert_eq!(pruned, 0);
+
+    let expired = ready + TIMELOCK_GRACE_PERIOD + 1;
+    let (nd, nl, pruned) = prune(&d[..l], l, expired).unwrap();
+    assert_eq!(pruned, 1);
+    assert_eq!(get_archived(&nd[..nl], &id).map(|(s, _)| s), Some(PROPOSAL_STATE_EXPIRED));
+    assert_eq!(find_value(&nd[..nl], b"op_count"), Some(b"0" as &[u8]));
+}
+
+#[test]
+fn test_prune_keeps_predecessor_of_live_op() {
+    let (data, len) = base_dao();
+    let (d, l, _) = governor::propose(&data[..len], len, &alice(), &mock_desc(1), &Actions::new(), 1000, 500_000_000).unwrap();
+    let (d, l, p1) = propose_past_limit(&d, l, &alice(), 2, 1000, 500_000_000);
+    let (d, l) = counting::cast_vote(&d[..l], l, 0, &alice(), 1, 500_000_000, 1400).unwrap();
+    let (d, l) = counting::cast_vote(&d[..l], l, 1, &alice(), 1, 500_000_000, 1400).unwrap();
+
+    // p0 queued and executed; p1 queued with p0's op as predecessor
+    let t_queue = 1300 + VOTING_PERIOD + 1;
+    let (d, l, op0) = queue(&d, l, 0, t_queue);
+    let mut key = [0u8; 32];
+    let klen = governor::build_prop_key(b"op_", op0, b"_id", &mut key);
+    let op0_id = crate::foundation::abi::decode_hash(find_value(&d[..l], &key[..klen]).unwrap()).unwrap();
+    let (d, l, _) = operations::schedule_with_predecessor(&d[..l], l, &p1, &op0_id, t_queue, TIMELOCK_MIN_DELAY).unwrap();
+    let (d, l) = governor::update_proposal_field(&d[..l], l, 1, b"_state", b"5").unwrap();
+    let t_exec = t_queue + TIMELOCK_MIN_DELAY;
+    let (d, l) = controller::execute(&d[..l], l, op0, t_exec).unwrap();
+    let (d, l) = governor::update_proposal_field(&d[..l], l, 0, b"_state", b"7").unwrap();
+
+    assert_invariants(&d[..l]);
+    let (_, _, pruned) = prune(&d[..l], l, t_exec).unwrap();
+    assert_eq!(pruned, 0);
+
+    // Once the dependent op is executed too, both go
+    let (d, l) = operations::execute_with_predecessor_check(&d[..l], l, 1, t_exec).unwrap();
+    let (d, l) = governor::update_proposal_field(&d[..l], l, 1, b"_state", b"7").unwrap();
+    let (nd, nl, pruned) = prune(&d[..l], l, t_exec).unwrap();
+    assert_eq!(pruned, 2);
+    assert_eq!(find_value(&nd[..nl], b"proposal_count"), Some(b"0" as &[u8]));
+    assert_invariants(&nd[..nl]);
+}
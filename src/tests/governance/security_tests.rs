@@ -11,6 +11,7 @@
 
 use crate::foundation::config::*;
 use crate::foundation::data::*;
+use crate::foundation::types::{Action, Actions};
 use crate::governance::governor::*;
 use crate::governance::counting;
 use crate::governance::votes;
@@ -26,47 +27,87 @@ use crate::tests::*;
 #[test]
 fn test_fix1_proposal_id_is_content_bound() {
     // Same proposer, different descriptions should produce different IDs
-    let id1 = hash::hash_proposal(&alice(), 111, 1000, 0);
-    let id2 = hash::hash_proposal(&alice(), 222, 1000, 0);
+    let id1 = hash::hash_proposal(&alice(), &mock_desc(111), &[], 1000, 0);
+    let id2 = hash::hash_proposal(&alice(), &mock_desc(222), &[], 1000, 0);
     assert_ne!(id1, id2, "Different descriptions must produce different IDs");
 }
 
 #[test]
 fn test_fix1_proposal_id_includes_proposer() {
     // Same description, different proposers should differ
-    let id1 = hash::hash_proposal(&alice(), 111, 1000, 0);
-    let id2 = hash::hash_proposal(&bob(), 111, 1000, 0);
+    let id1 = hash::hash_proposal(&alice(), &mock_desc(111), &[], 1000, 0);
+    let id2 = hash::hash_proposal(&bob(), &mock_desc(111), &[], 1000, 0);
     assert_ne!(id1, id2, "Different proposers must produce different IDs");
 }
 
 #[test]
 fn test_fix1_proposal_id_includes_time() {
-    let id1 = hash::hash_proposal(&alice(), 111, 1000, 0);
-    let id2 = hash::hash_proposal(&alice(), 111, 2000, 0);
+    let id1 = hash::hash_proposal(&alice(), &mock_desc(111), &[], 1000, 0);
+    let id2 = hash::hash_proposal(&alice(), &mock_desc(111), &[], 2000, 0);
     assert_ne!(id1, id2, "Different times must produce different IDs");
 }
 
 #[test]
 fn test_fix1_proposal_id_includes_nonce() {
-    let id1 = hash::hash_proposal(&alice(), 111, 1000, 0);
-    let id2 = hash::hash_proposal(&alice(), 111, 1000, 1);
+    let id1 = hash::hash_proposal(&alice(), &mock_desc(111), &[], 1000, 0);
+    let id2 = hash::hash_proposal(&alice(), &mock_desc(111), &[], 1000, 1);
     assert_ne!(id1, id2, "Different nonces must produce different IDs");
 }
 
+#[test]
+fn test_fix1_proposal_id_includes_actions() {
+    let mut pay = Action::new();
+    pay.target = bob();
+    pay.amount = 1_000_000;
+    let base = hash::hash_proposal(&alice(), &mock_desc(111), &[pay], 1000, 0);
+    assert_ne!(base, hash::hash_proposal(&alice(), &mock_desc(111), &[], 1000, 0));
+
+    // Every field of an action is committed to
+    let mut other = pay;
+    other.target = carol();
+    assert_ne!(base, hash::hash_proposal(&alice(), &mock_desc(111), &[other], 1000, 0));
+    other = pay;
+    other.amount += 1;
+    assert_ne!(base, hash::hash_proposal(&alice(), &mock_desc(111), &[other], 1000, 0));
+    other = pay;
+    other.tx_type = 20;
+    assert_ne!(base, hash::hash_proposal(&alice(), &mock_desc(111), &[other], 1000, 0));
+    other = pay;
+    other.payload_len = 1;
+    assert_ne!(base, hash::hash_proposal(&alice(), &mock_desc(111), &[other], 1000, 0));
+    assert_ne!(base, hash::hash_proposal(&alice(), &mock_desc(111), &[pay, pay], 1000, 0));
+}
+
 #[test]
 fn test_fix1_proposal_id_nonzero() {
-    // ID must always be non-zero (we OR with 1)
+    // A zero ID means "none" (e.g. no predecessor), so IDs must never be zero
     for seed in 0u8..255 {
         let account = mock_account(seed);
-        let id = hash::hash_proposal(&account, seed as u32, seed as u32, seed);
-        assert_ne!(id, 0, "Proposal ID must never be zero");
+        let id = hash::hash_proposal(&account, &mock_desc(seed as u32), &[], seed as u32, seed as u16);
+        assert_ne!(id, [0u8; HASH_SIZE], "Proposal ID must never be zero");
     }
 }
 
+#[test]
+fn test_fix1_proposal_id_is_prefixed_sha512_half() {
+    let mut preimage = [0u8; 4 + 20 + 32 + 4 + 4 + 2];
+    preimage[..4].copy_from_slice(b"DPR\0");
+    preimage[4..24].copy_from_slice(&alice());
+    preimage[24..56].copy_from_slice(&mock_desc(111));
+    preimage[60..64].copy_from_slice(&1000u32.to_be_bytes());
+    preimage[64..].copy_from_slice(&3u16.to_be_bytes());
+    let id = hash::hash_proposal(&alice(), &mock_desc(111), &[], 1000, 3);
+    assert_eq!(id, crate::crypto::sha512::sha512_half(&preimage));
+
+    // The prefix separates proposal IDs from operation IDs over the same bytes
+    preimage[..4].copy_from_slice(&hash::HASH_PREFIX_OPERATION);
+    assert_ne!(id, crate::crypto::sha512::sha512_half(&preimage));
+}
+
 #[test]
 fn test_fix1_proposal_id_deterministic() {
-    let id1 = hash::hash_proposal(&alice(), 111, 1000, 0);
-    let id2 = hash::hash_proposal(&alice(), 111, 1000, 0);
+    let id1 = hash::hash_proposal(&alice(), &mock_desc(111), &[], 1000, 0);
+    let id2 = hash::hash_proposal(&alice(), &mock_desc(111), &[], 1000, 0);
     assert_eq!(id1, id2, "Same inputs must produce same ID");
 }
 
@@ -134,7 +175,7 @@ fn test_fix2_lock_toggle_idempotent() {
 #[test]
 fn test_fix4_vote_message_format() {
     let mut msg = [0u8; 128];
-    let len = signatures::build_vote_message(42, VOTE_FOR, &alice(), &mut msg);
+    let len = signatures::build_vote_message(&mock_id(42), VOTE_FOR, &alice(), &mut msg);
     assert!(len > 0, "Message should have non-zero length");
 
     // Message should start with domain prefix
@@ -144,9 +185,9 @@ fn test_fix4_vote_message_format() {
 #[test]
 fn test_fix4_vote_message_differs_by_proposal() {
     let mut msg1 = [0u8; 128];
-    let len1 = signatures::build_vote_message(42, VOTE_FOR, &alice(), &mut msg1);
+    let len1 = signatures::build_vote_message(&mock_id(42), VOTE_FOR, &alice(), &mut msg1);
     let mut msg2 = [0u8; 128];
-    let len2 = signatures::build_vote_message(99, VOTE_FOR, &alice(), &mut msg2);
+    let len2 = signatures::build_vote_message(&mock_id(99), VOTE_FOR, &alice(), &mut msg2);
 
     assert_ne!(&msg1[..len1], &msg2[..len2], "Different proposals must produce different messages");
 }
@@ -154,9 +195,9 @@ fn test_fix4_vote_message_differs_by_proposal() {
 #[test]
 fn test_fix4_vote_message_differs_by_support() {
     let mut msg1 = [0u8; 128];
-    let len1 = signatures::build_vote_message(42, VOTE_FOR, &alice(), &mut msg1);
+    let len1 = signatures::build_vote_message(&mock_id(42), VOTE_FOR, &alice(), &mut msg1);
     let mut msg2 = [0u8; 128];
-    let len2 = signatures::build_vote_message(42, VOTE_AGAINST, &alice(), &mut msg2);
+    let len2 = signatures::build_vote_message(&mock_id(42), VOTE_AGAINST, &alice(), &mut msg2);
 
     assert_ne!(&msg1[..len1], &msg2[..len2], "Different support must produce different messages");
 }
@@ -164,41 +205,41 @@ fn test_fix4_vote_message_differs_by_support() {
 #[test]
 fn test_fix4_vote_message_differs_by_voter() {
     let mut msg1 = [0u8; 128];
-    let len1 = signatures::build_vote_message(42, VOTE_FOR, &alice(), &mut msg1);
+    let len1 = signatures::build_vote_message(&mock_id(42), VOTE_FOR, &alice(), &mut msg1);
     let mut msg2 = [0u8; 128];
-    let len2 = signatures::build_vote_message(42, VOTE_FOR, &bob(), &mut msg2);
+    let len2 = signatures::build_vote_message(&mock_id(42), VOTE_FOR, &bob(), &mut msg2);
 
     assert_ne!(&msg1[..len1], &msg2[..len2], "Different voters must produce different messages");
 }
 
 #[test]
 fn test_fix4_validate_rejects_invalid_support() {
-    assert!(!signatures::validate_vote_message(42, 3, &alice()), "Support > 2 must fail");
-    assert!(!signatures::validate_vote_message(42, 255, &alice()), "Support 255 must fail");
+    assert!(!signatures::validate_vote_message(&mock_id(42), 3, &alice()), "Support > 2 must fail");
+    assert!(!signatures::validate_vote_message(&mock_id(42), 255, &alice()), "Support 255 must fail");
 }
 
 #[test]
 fn test_fix4_validate_rejects_zero_proposal() {
-    assert!(!signatures::validate_vote_message(0, VOTE_FOR, &alice()), "Zero proposal must fail");
+    assert!(!signatures::validate_vote_message(&mock_id(0), VOTE_FOR, &alice()), "Zero proposal must fail");
 }
 
 #[test]
 fn test_fix4_validate_rejects_zero_voter() {
     let zero = [0u8; ACCOUNT_ID_SIZE];
-    assert!(!signatures::validate_vote_message(42, VOTE_FOR, &zero), "Zero voter must fail");
+    assert!(!signatures::validate_vote_message(&mock_id(42), VOTE_FOR, &zero), "Zero voter must fail");
 }
 
 #[test]
 fn test_fix4_validate_accepts_valid_input() {
-    assert!(signatures::validate_vote_message(42, VOTE_FOR, &alice()));
-    assert!(signatures::validate_vote_message(42, VOTE_AGAINST, &bob()));
-    assert!(signatures::validate_vote_message(42, VOTE_ABSTAIN, &carol()));
+    assert!(signatures::validate_vote_message(&mock_id(42), VOTE_FOR, &alice()));
+    assert!(signatures::validate_vote_message(&mock_id(42), VOTE_AGAINST, &bob()));
+    assert!(signatures::validate_vote_message(&mock_id(42), VOTE_ABSTAIN, &carol()));
 }
 
 #[test]
 fn test_fix4_record_sig_vote_intent() {
     let (data, len) = build_dao_data(&[(&alice(), 200_000_000, 0)]);
-    let result = signatures::record_sig_vote_intent(&data[..len], len, 42, VOTE_FOR, &alice());
+    let result = signatures::record_sig_vote_intent(&data[..len], len, &mock_id(42), VOTE_FOR, &alice());
     assert!(result.is_ok());
 
     let (new_data, new_len) = result.unwrap();
@@ -226,7 +267,7 @@ fn test_fix5_self_register_new_member() {
     ]);
 
     // Bob self-registers with 0 power, no roles
-    let result = votes::set_member(&data[..len], len, &bob(), SELF_REGISTER_INITIAL_POWER, 0);
+    let result = votes::set_member(&data[..len], len, &bob(), SELF_REGISTER_INITIAL_POWER, 0, 0);
     assert!(result.is_ok());
 
     let (new_data, new_len) = result.unwrap();
@@ -244,10 +285,10 @@ fn test_fix5_self_register_then_admin_grants_power() {
     ]);
 
     // Bob self-registers
-    let (d1, l1) = votes::set_member(&data[..len], len, &bob(), 0, 0).unwrap();
+    let (d1, l1) = votes::set_member(&data[..len], len, &bob(), 0, 0, 0).unwrap();
 
     // Admin (alice) grants Bob voting power
-    let (d2, l2) = votes::set_member(&d1[..l1], l1, &bob(), 100_000_000, ROLE_PROPOSER).unwrap();
+    let (d2, l2) = votes::set_member(&d1[..l1], l1, &bob(), 100_000_000, ROLE_PROPOSER, 0).unwrap();
 
     let bob_votes = votes::get_votes(&d2[..l2], &bob());
     assert_eq!(bob_votes, 100_000_000);
@@ -264,10 +305,10 @@ fn test_fix5_member_count_preserved() {
 
     assert_eq!(votes::get_member_count(&data[..len]), 1);
 
-    let (d1, l1) = votes::set_member(&data[..len], len, &bob(), 0, 0).unwrap();
+    let (d1, l1) = votes::set_member(&data[..len], len, &bob(), 0, 0, 0).unwrap();
     assert_eq!(votes::get_member_count(&d1[..l1]), 2);
 
-    let (d2, l2) = votes::set_member(&d1[..l1], l1, &carol(), 0, 0).unwrap();
+    let (d2, l2) = votes::set_member(&d1[..l1], l1, &carol(), 0, 0, 0).unwrap();
     assert_eq!(votes::get_member_count(&d2[..l2]), 3);
 }
 
@@ -287,7 +328,7 @@ fn test_fix6_vote_tally_overflow_returns_error() {
 
     // First cast a huge vote
     let result = counting::cast_vote(
-        &data[..len], len, 0, &bob(), VOTE_FOR, u64::MAX - 1, 5000, u64::MAX,
+        &data[..len], len, 0, &bob(), VOTE_FOR, u64::MAX - 1, 5000,
     );
     assert!(result.is_ok(), "First vote should succeed");
 
@@ -295,12 +336,13 @@ fn test_fix6_vote_tally_overflow_returns_error() {
 
     // Second vote would overflow u64
     let result2 = counting::cast_vote(
-        &d1[..l1], l1, 0, &alice(), VOTE_FOR, u64::MAX, 5000, u64::MAX,
+        &d1[..l1], l1, 0, &alice(), VOTE_FOR, u64::MAX, 5000,
     );
     assert_eq!(result2, Err(ERR_OVERFLOW), "Overflow should return ERR_OVERFLOW");
 }
 
 #[test]
+#[allow(clippy::absurd_extreme_comparisons)]
 fn test_fix6_saturating_total_voting_power() {
     // Build data with near-max voting powers
     let (data, len) = build_dao_data(&[
@@ -348,6 +390,23 @@ fn test_fix7_build_prop_key_triple_digit() {
     assert_eq!(&buf[..len], b"prop_255_id");
 }
 
+#[test]
+fn test_fix7_build_prop_key_beyond_u8() {
+    let mut buf = [0u8; 48];
+    let len = build_prop_key(b"prop_", 256, b"_id", &mut buf);
+    assert_eq!(&buf[..len], b"prop_256_id");
+    let len = build_prop_key(b"vote_", u16::MAX, b"_", &mut buf);
+    assert_eq!(&buf[..len], b"vote_65535_");
+}
+
+#[test]
+fn test_fix7_read_count_beyond_u8() {
+    assert_eq!(read_count(b"op_count=300", b"op_count"), 300);
+    assert_eq!(read_count(b"op_count=65535", b"op_count"), u16::MAX);
+    // Out of range reads as zero rather than wrapping
+    assert_eq!(read_count(b"op_count=65536", b"op_count"), 0);
+}
+
 #[test]
 fn test_fix7_format_u8_range() {
     let mut buf = [0u8; 3];
@@ -373,7 +432,7 @@ fn test_fix7_member_key_multi_digit() {
     let mut pos = 0;
 
     // Write member_count=12 (multi-digit)
-    pos = write_entry(&mut data, pos, b"member_count", b"12");
+    pos = try_write_entry(&mut data, pos, b"member_count", b"12").unwrap();
 
     // Verify the count is read correctly
     let count = votes::get_member_count(&data[..pos]);
@@ -389,7 +448,7 @@ fn test_fix8_operation_pending_before_ready() {
     let (data, len) = build_dao_data(&[(&alice(), 200_000_000, ROLE_ADMIN)]);
 
     let (d1, l1, _op_id) = controller::schedule(
-        &data[..len], len, 42, 1000, TIMELOCK_MIN_DELAY,
+        &data[..len], len, &mock_id(42), 1000, TIMELOCK_MIN_DELAY,
     ).unwrap();
 
     // Before ready_at (1000 + 172800 = 173800)
@@ -402,7 +461,7 @@ fn test_fix8_operation_ready_within_grace() {
     let (data, len) = build_dao_data(&[(&alice(), 200_000_000, ROLE_ADMIN)]);
 
     let (d1, l1, _op_id) = controller::schedule(
-        &data[..len], len, 42, 1000, TIMELOCK_MIN_DELAY,
+        &data[..len], len, &mock_id(42), 1000, TIMELOCK_MIN_DELAY,
     ).unwrap();
 
     let ready_at = 1000 + TIMELOCK_MIN_DELAY;
@@ -420,7 +479,7 @@ fn test_fix8_operation_expired_after_grace() {
     let (data, len) = build_dao_data(&[(&alice(), 200_000_000, ROLE_ADMIN)]);
 
     let (d1, l1, _op_id) = controller::schedule(
-        &data[..len], len, 42, 1000, TIMELOCK_MIN_DELAY,
+        &data[..len], len, &mock_id(42), 1000, TIMELOCK_MIN_DELAY,
     ).unwrap();
 
     let ready_at = 1000 + TIMELOCK_MIN_DELAY;
@@ -436,7 +495,7 @@ fn test_fix8_execute_expired_operation_fails() {
     let (data, len) = build_dao_data(&[(&alice(), 200_000_000, ROLE_ADMIN)]);
 
     let (d1, l1, _op_id) = controller::schedule(
-        &data[..len], len, 42, 1000, TIMELOCK_MIN_DELAY,
+        &data[..len], len, &mock_id(42), 1000, TIMELOCK_MIN_DELAY,
     ).unwrap();
 
     let ready_at = 1000 + TIMELOCK_MIN_DELAY;
@@ -451,7 +510,7 @@ fn test_fix8_execute_within_grace_succeeds() {
     let (data, len) = build_dao_data(&[(&alice(), 200_000_000, ROLE_ADMIN)]);
 
     let (d1, l1, _op_id) = controller::schedule(
-        &data[..len], len, 42, 1000, TIMELOCK_MIN_DELAY,
+        &data[..len], len, &mock_id(42), 1000, TIMELOCK_MIN_DELAY,
     ).unwrap();
 
     let ready_at = 1000 + TIMELOCK_MIN_DELAY;
@@ -481,36 +540,35 @@ fn test_full_lifecycle_with_all_fixes() {
     let (data, len) = build_dao_data(&[
         (&alice(), 500_000_000, ROLE_PROPOSER | ROLE_ADMIN | ROLE_EXECUTOR),
         (&bob(), 300_000_000, 0),
-        (&carol(), 200_000_000, 0),
     ]);
     let total_vp = votes::get_total_voting_power(&data[..len]);
-    assert_eq!(total_vp, 1_000_000_000);
+    assert_eq!(total_vp, 800_000_000);
 
-    // 2. Fix #5: Self-register new member (dave)
-    let (d1, l1) = votes::set_member(&data[..len], len, &dave(), 0, 0).unwrap();
-    assert_eq!(votes::get_member_count(&d1[..l1]), 4);
+    // 2. Fix #5: Self-register new member (carol)
+    let (d1, l1) = votes::set_member(&data[..len], len, &carol(), 0, 0, 0).unwrap();
+    assert_eq!(votes::get_member_count(&d1[..l1]), 3);
 
     // 3. Fix #1: Propose (ID is cryptographic)
     let (d2, l2, prop_id) = propose(
-        &d1[..l1], l1, &alice(), 12345, 1000, 500_000_000,
+        &d1[..l1], l1, &alice(), &mock_desc(12345), &Actions::new(), 1000, 500_000_000,
     ).unwrap();
-    assert!(prop_id != 0);
+    assert!(prop_id != [0u8; HASH_SIZE]);
 
     // 4. Fix #7: Multi-digit key lookup works
-    let idx = find_proposal_by_id(&d2[..l2], prop_id).unwrap();
+    let idx = find_proposal_by_id(&d2[..l2], &prop_id).unwrap();
     assert_eq!(idx, 0);
 
     // 5. Advance time to Active
-    let state = get_proposal_state(&d2[..l2], 0, 2000, total_vp);
+    let state = get_proposal_state(&d2[..l2], 0, 2000);
     assert_eq!(state, PROPOSAL_STATE_ACTIVE);
 
     // 6. Fix #6: Cast votes with overflow protection
     let (d3, l3) = counting::cast_vote(
-        &d2[..l2], l2, 0, &alice(), VOTE_FOR, 500_000_000, 2000, total_vp,
+        &d2[..l2], l2, 0, &alice(), VOTE_FOR, 500_000_000, 2000,
     ).unwrap();
 
     let (d4, l4) = counting::cast_vote(
-        &d3[..l3], l3, 0, &bob(), VOTE_FOR, 300_000_000, 2000, total_vp,
+        &d3[..l3], l3, 0, &bob(), VOTE_FOR, 300_000_000, 2000,
     ).unwrap();
 
     // 7. Verify vote tallies
@@ -518,14 +576,15 @@ fn test_full_lifecycle_with_all_fixes() {
     assert_eq!(f, 800_000_000);
     assert_eq!(a, 0);
     assert_eq!(ab, 0);
+    assert_invariants(&d4[..l4]);
 
     // 8. Advance past voting end → Succeeded
-    let state = get_proposal_state(&d4[..l4], 0, 300_000, total_vp);
+    let state = get_proposal_state(&d4[..l4], 0, 300_000);
     assert_eq!(state, PROPOSAL_STATE_SUCCEEDED);
 
     // 9. Fix #8: Schedule in timelock with grace period
     let (d5, l5, _op_id) = controller::schedule(
-        &d4[..l4], l4, prop_id, 300_000, TIMELOCK_MIN_DELAY,
+        &d4[..l4], l4, &prop_id, 300_000, TIMELOCK_MIN_DELAY,
     ).unwrap();
 
     let ready_at = 300_000 + TIMELOCK_MIN_DELAY;
@@ -550,7 +609,7 @@ fn test_full_lifecycle_with_all_fixes() {
     // Expired after grace
     // (schedule another to test expiry)
     let (d6, l6, _) = controller::schedule(
-        &d5[..l5], l5, prop_id + 1, 300_000, TIMELOCK_MIN_DELAY,
+        &d5[..l5], l5, &mock_id(1), 300_000, TIMELOCK_MIN_DELAY,
     ).unwrap();
     let expired_time = ready_at + TIMELOCK_GRACE_PERIOD + 100;
     assert!(controller::is_operation_expired(&d6[..l6], 1, expired_time));
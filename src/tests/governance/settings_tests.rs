@@ -0,0 +1,264 @@
+use crate::foundation::config::*;
+use crate::foundation::data::*;
+use crate::foundation::types::{Action, Actions};
+use crate::governance::settings::*;
+use crate::governance::{governor, counting};
+use crate::governance::prune::prune;
+use crate::timelock::controller;
+use crate::tests::*;
+
+const TOTAL_VP: u64 = 1_000_000_000;
+
+/// A DAO with one proposal (id 42) whose execution is in progress.
+fn executing_dao() -> ([u8; DATA_CAPACITY], usize) {
+    let (data, len) = build_dao_with_proposal(
+        &[(&alice(), 500_000_000, ROLE_PROPOSER | ROLE_EXECUTOR)],
+        42, &alice(), 100, 200, PROPOSAL_STATE_QUEUED,
+    );
+    governor::set_executing(&data[..len], len, Some(&mock_id(42))).unwrap()
+}
+
+/// Apply a setting from inside an execution, then leave it.
+fn with_setting(data: &[u8], param: u8, value: u64) -> ([u8; DATA_CAPACITY], usize) {
+    let (d, l) = governor::set_executing(data, data.len(), Some(&mock_id(42))).unwrap();
+    let (d, l) = set_setting(&d[..l], l, param, value).unwrap();
+    governor::set_executing(&d[..l], l, None).unwrap()
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Loading
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_defaults_when_absent() {
+    let (data, len) = build_dao_data(&[(&alice(), 100, 7)]);
+    assert_eq!(Settings::load(&data[..len]), Settings::DEFAULT);
+    assert_eq!(get_setting(&data[..len], SETTING_VOTING_DELAY), Ok(VOTING_DELAY as u64));
+    assert_eq!(get_setting(&data[..len], SETTING_PROPOSAL_THRESHOLD), Ok(PROPOSAL_THRESHOLD));
+    assert_eq!(get_setting(&data[..len], SETTING_TIMELOCK_GRACE_PERIOD), Ok(TIMELOCK_GRACE_PERIOD as u64));
+    assert_eq!(get_setting(&data[..len], SETTING_LATE_QUORUM_WINDOW), Ok(LATE_QUORUM_WINDOW as u64));
+    assert_eq!(get_setting(&data[..len], 8), Err(ERR_BAD_CONFIG));
+}
+
+#[test]
+fn test_load_overrides_and_ignores_bad_values() {
+    let mut store = Store::new();
+    store.set(b"cfg_voting_period", b"7200").unwrap();
+    store.set(b"cfg_quorum", b"0").unwrap(); // below MIN_QUORUM_PERCENTAGE
+    store.set(b"cfg_min_delay", b"abc").unwrap();
+    let (data, len) = store.commit();
+
+    let s = Settings::load(&data[..len]);
+    assert_eq!(s.voting_period, 7200);
+    assert_eq!(s.quorum_percentage, QUORUM_PERCENTAGE);
+    assert_eq!(s.timelock_min_delay, TIMELOCK_MIN_DELAY);
+}
+
+#[test]
+fn test_store_round_trip_omits_defaults() {
+    let mut s = Settings::DEFAULT;
+    s.quorum_percentage = 20;
+    s.proposal_threshold = 5_000_000_000;
+
+    let mut store = Store::new();
+    store.set(b"cfg_voting_delay", b"60").unwrap();
+    s.store(&mut store).unwrap();
+    let (data, len) = store.commit();
+
+    assert_eq!(Settings::load(&data[..len]), s);
+    assert_eq!(find_value(&data[..len], b"cfg_voting_delay"), None);
+    assert_eq!(find_value(&data[..len], b"cfg_quorum"), Some(b"20" as &[u8]));
+}
+
+#[test]
+fn test_default_settings_are_valid() {
+    assert_eq!(Settings::DEFAULT.validate(), Ok(()));
+
+    let mut s = Settings::DEFAULT;
+    s.timelock_min_delay = 0;
+    assert_eq!(s.validate(), Err(ERR_BAD_CONFIG));
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// onlyGovernance
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_set_setting_outside_execution_rejected() {
+    let (data, len) = build_dao_with_proposal(&[(&alice(), 100, 7)], 42, &alice(), 100, 200, 0);
+    assert_eq!(set_setting(&data[..len], len, SETTING_QUORUM_PERCENTAGE, 10), Err(ERR_NOT_GOVERNANCE));
+
+    // The reentrancy lock alone is not enough
+    let (d, l) = governor::set_lock(&data[..len], len, true).unwrap();
+    assert_eq!(set_setting(&d[..l], l, SETTING_QUORUM_PERCENTAGE, 10), Err(ERR_NOT_GOVERNANCE));
+
+    // _exec must name an existing proposal
+    let (d, l) = governor::set_executing(&data[..len], len, Some(&mock_id(7))).unwrap();
+    assert_eq!(set_setting(&d[..l], l, SETTING_QUORUM_PERCENTAGE, 10), Err(ERR_NOT_GOVERNANCE));
+}
+
+#[test]
+fn test_set_setting_during_execution() {
+    let (data, len) = executing_dao();
+    let (d, l) = set_setting(&data[..len], len, SETTING_QUORUM_PERCENTAGE, 10).unwrap();
+    assert_eq!(get_setting(&d[..l], SETTING_QUORUM_PERCENTAGE), Ok(10));
+
+    let (d, l) = governor::set_executing(&d[..l], l, None).unwrap();
+    assert_eq!(Settings::load(&d[..l]).quorum_percentage, 10);
+    assert_invariants(&d[..l]);
+}
+
+#[test]
+fn test_set_setting_bounds() {
+    let (data, len) = executing_dao();
+    let cases: [(u8, u64, u64); 8] = [
+        (SETTING_VOTING_DELAY, MIN_VOTING_DELAY as u64, MAX_VOTING_DELAY as u64),
+        (SETTING_VOTING_PERIOD, MIN_VOTING_PERIOD as u64, MAX_VOTING_PERIOD as u64),
+        (SETTING_PROPOSAL_THRESHOLD, MIN_PROPOSAL_THRESHOLD, MAX_PROPOSAL_THRESHOLD),
+        (SETTING_QUORUM_PERCENTAGE, MIN_QUORUM_PERCENTAGE as u64, MAX_QUORUM_PERCENTAGE as u64),
+        (SETTING_TIMELOCK_MIN_DELAY, MIN_TIMELOCK_DELAY as u64, MAX_TIMELOCK_DELAY as u64),
+        (SETTING_TIMELOCK_GRACE_PERIOD, MIN_GRACE_PERIOD as u64, MAX_GRACE_PERIOD as u64),
+        (SETTING_LATE_QUORUM_WINDOW, MIN_LATE_QUORUM_WINDOW as u64, MAX_LATE_QUORUM_WINDOW as u64),
+        (SETTING_LATE_QUORUM_EXTENSION, MIN_LATE_QUORUM_EXTENSION as u64, MAX_LATE_QUORUM_EXTENSION as u64),
+    ];
+
+    for (param, min, max) in cases {
+        assert!(set_setting(&data[..len], len, param, min).is_ok());
+        assert!(set_setting(&data[..len], len, param, max).is_ok());
+        assert_eq!(set_setting(&data[..len], len, param, max + 1), Err(ERR_BAD_CONFIG));
+        if min > 0 {
+            assert_eq!(set_setting(&data[..len], len, param, min - 1), Err(ERR_BAD_CONFIG));
+        }
+    }
+    assert_eq!(set_setting(&data[..len], len, 8, 1), Err(ERR_BAD_CONFIG));
+}
+
+/// An `ACTION_SET_SETTING` action with `payload` and `value`.
+fn setting_action(payload: &[u8], value: u64) -> Action {
+    let mut action = Action { amount: value, tx_type: ACTION_SET_SETTING, ..Action::new() };
+    action.payload[..payload.len()].copy_from_slice(payload);
+    action.payload_len = payload.len() as u8;
+    action
+}
+
+#[test]
+fn test_apply_action_only_during_execution() {
+    let action = setting_action(&[SETTING_QUORUM_PERCENTAGE], 10);
+    let (data, len) = executing_dao();
+    let (d, l) = apply_action(&data[..len], len, &action).unwrap();
+    assert_eq!(get_setting(&d[..l], SETTING_QUORUM_PERCENTAGE), Ok(10));
+
+    let (d, l) = governor::set_executing(&data[..len], len, None).unwrap();
+    assert_eq!(apply_action(&d[..l], l, &action), Err(ERR_NOT_GOVERNANCE));
+}
+
+#[test]
+fn test_propose_rejects_bad_setting_action() {
+    let (data, len) = build_dao_data(&[(&alice(), 500_000_000, ROLE_PROPOSER)]);
+    let bad = [
+        setting_action(&[], 10),
+        setting_action(&[SETTING_QUORUM_PERCENTAGE, 0], 10),
+        setting_action(&[8], 10),
+        setting_action(&[SETTING_QUORUM_PERCENTAGE], 0),
+    ];
+    for action in bad {
+        let mut actions = Actions::new();
+        actions.push(action).unwrap();
+        assert_eq!(
+            governor::propose(&data[..len], len, &alice(), &mock_desc(1), &actions, 1000, 500_000_000),
+            Err(ERR_BAD_CONFIG),
+        );
+    }
+
+    let mut actions = Actions::new();
+    actions.push(setting_action(&[SETTING_QUORUM_PERCENTAGE], 10)).unwrap();
+    assert!(governor::propose(&data[..len], len, &alice(), &mock_desc(1), &actions, 1000, 500_000_000).is_ok());
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Settings take effect
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_propose_uses_stored_settings() {
+    let (data, len) = build_dao_with_proposal(
+        &[(&alice(), 50_000_000, ROLE_PROPOSER)],
+        42, &alice(), 100, 200, PROPOSAL_STATE_EXECUTED,
+    );
+    assert_eq!(
+        governor::propose(&data[..len], len, &alice(), &mock_desc(1), &Actions::new(), 1000, 50_000_000),
+        Err(ERR_BELOW_THRESHOLD),
+    );
+
+    let (d, l) = with_setting(&data[..len], SETTING_PROPOSAL_THRESHOLD, 50_000_000);
+    let (d, l) = with_setting(&d[..l], SETTING_VOTING_DELAY, 60);
+    let (d, l) = with_setting(&d[..l], SETTING_VOTING_PERIOD, 7200);
+    // Free the executing proposal's slot
+    let (d, l, _) = prune(&d[..l], l, 1000).unwrap();
+
+    let (d, l, prop_id) = governor::propose(&d[..l], l, &alice(), &mock_desc(1), &Actions::new(), 1000, 50_000_000).unwrap();
+    let idx = governor::find_proposal_by_id(&d[..l], &prop_id).unwrap();
+    let p = crate::foundation::types::Proposal::load(&d[..l], idx).unwrap();
+    assert_eq!(p.vote_start, 1060);
+    assert_eq!(p.vote_end, 1060 + 7200);
+}
+
+#[test]
+fn test_quorum_setting_applies_to_new_proposals() {
+    let (data, len) = build_dao_with_proposal(
+        &[(&alice(), 50_000_000, ROLE_PROPOSER), (&bob(), 950_000_000, 0)],
+        42, &alice(), 100, 200, 0,
+    );
+    // 5% of supply votes for: passes the default 4% quorum (late, so
+    // voting runs on for the extension)
+    let (d, l) = counting::cast_vote(&data[..len], len, 0, &alice(), VOTE_FOR, 50_000_000, 150).unwrap();
+    let ended = 150 + LATE_QUORUM_EXTENSION + 1;
+    assert_eq!(governor::get_proposal_state(&d[..l], 0, ended), PROPOSAL_STATE_SUCCEEDED);
+    assert!(counting::quorum_reached(&d[..l], 0));
+
+    // Raising the quorum leaves the existing proposal's frozen quorum alone
+    let (d, l) = with_setting(&d[..l], SETTING_QUORUM_PERCENTAGE, 10);
+    assert_eq!(governor::get_proposal_state(&d[..l], 0, ended), PROPOSAL_STATE_SUCCEEDED);
+    assert!(counting::quorum_reached(&d[..l], 0));
+
+    // Once its slot is free, a new proposal freezes the raised quorum
+    let (d, l) = governor::update_proposal_field(&d[..l], l, 0, b"_state", b"7").unwrap();
+    let (d, l, _) = prune(&d[..l], l, ended).unwrap();
+    let (d, l, _) = governor::propose(&d[..l], l, &alice(), &mock_desc(1), &Actions::new(), 300, PROPOSAL_THRESHOLD).unwrap();
+    let p = crate::foundation::types::Proposal::load(&d[..l], 0).unwrap();
+    assert_eq!((p.total_supply, p.quorum), (TOTAL_VP, TOTAL_VP / 10));
+}
+
+#[test]
+fn test_timelock_settings() {
+    let (data, len) = build_dao_with_proposal(
+        &[(&alice(), 500_000_000, ROLE_PROPOSER | ROLE_EXECUTOR)],
+        42, &alice(), 100, 200, 0,
+    );
+    let (d, l) = with_setting(&data[..len], SETTING_TIMELOCK_MIN_DELAY, 3_600);
+    let (d, l) = with_setting(&d[..l], SETTING_TIMELOCK_GRACE_PERIOD, 86_400);
+
+    // A delay below the default but above the override is accepted
+    let (d, l, _) = controller::schedule(&d[..l], l, &mock_id(42), 1000, 3_600).unwrap();
+    assert_eq!(controller::get_operation_state(&d[..l], 0, 1000 + 3_600), OP_STATE_READY);
+    assert_eq!(controller::get_operation_state(&d[..l], 0, 1000 + 3_600 + 86_400), OP_STATE_READY);
+    assert_eq!(controller::get_operation_state(&d[..l], 0, 1000 + 3_600 + 86_401), OP_STATE_EXPIRED);
+
+    let (d2, l2) = with_setting(&data[..len], SETTING_TIMELOCK_MIN_DELAY, 7_200);
+    assert_eq!(controller::schedule(&d2[..l2], l2, &mock_id(42), 1000, 3_600).map(|r| r.2), Err(ERR_TOO_EARLY));
+}
+
+#[test]
+fn test_late_quorum_settings() {
+    let (data, len) = build_dao_with_proposal(&[(&alice(), 200_000_000, ROLE_PROPOSER)], 42, &alice(), 100, 10_000, 0);
+
+    // A zero window turns the extension off
+    let (d, l) = with_setting(&data[..len], SETTING_LATE_QUORUM_WINDOW, 0);
+    let (d, l) = counting::cast_vote(&d[..l], l, 0, &alice(), VOTE_FOR, 200_000_000, 9_999).unwrap();
+    assert_eq!(governor::get_proposal_state(&d[..l], 0, 10_001), PROPOSAL_STATE_SUCCEEDED);
+
+    // A longer extension keeps voting open for that long
+    let (d, l) = with_setting(&data[..len], SETTING_LATE_QUORUM_EXTENSION, 3 * 86_400);
+    let (d, l) = counting::cast_vote(&d[..l], l, 0, &alice(), VOTE_FOR, 200_000_000, 9_999).unwrap();
+    assert_eq!(governor::get_proposal_state(&d[..l], 0, 9_999 + 3 * 86_400), PROPOSAL_STATE_ACTIVE);
+}
@@ -128,12 +128,13 @@ fn test_set_member_new() {
     let (data, len) = build_dao_data(&[(&alice(), 200_000_000, ROLE_ADMIN)]);
 
     let (new_data, new_len) = set_member(
-        &data[..len], len, &bob(), 100_000_000, ROLE_EXECUTOR,
+        &data[..len], len, &bob(), 100_000_000, ROLE_EXECUTOR, 0,
     ).unwrap();
 
     assert_eq!(get_votes(&new_data[..new_len], &bob()), 100_000_000);
     assert!(has_role(&new_data[..new_len], &bob(), ROLE_EXECUTOR));
     assert_eq!(get_member_count(&new_data[..new_len]), 2);
+    assert_invariants(&new_data[..new_len]);
 }
 
 #[test]
@@ -145,12 +146,13 @@ fn test_set_member_update_existing() {
 
     // Update bob's voting power
     let (new_data, new_len) = set_member(
-        &data[..len], len, &bob(), 500_000_000, ROLE_EXECUTOR,
+        &data[..len], len, &bob(), 500_000_000, ROLE_EXECUTOR, 0,
     ).unwrap();
 
     assert_eq!(get_votes(&new_data[..new_len], &bob()), 500_000_000);
     assert!(has_role(&new_data[..new_len], &bob(), ROLE_EXECUTOR));
     assert_eq!(get_member_count(&new_data[..new_len]), 2); // count unchanged
+    assert_invariants(&new_data[..new_len]);
 }
 
 #[test]
@@ -162,3 +164,176 @@ fn test_member_count() {
     ]);
     assert_eq!(get_member_count(&data[..len]), 3);
 }
+
+#[test]
+fn test_set_member_on_full_data_returns_data_full() {
+    use crate::foundation::data::*;
+
+    let (data, len) = build_dao_data(&[(&alice(), 200_000_000, ROLE_ADMIN)]);
+    let mut store = Store::load(&data[..len]);
+    let pad = [b'p'; DATA_CAPACITY];
+    store.set(b"pad", &pad[..store.available() - 5]).unwrap();
+    let (full, full_len) = store.commit();
+
+    let result = set_member(&full[..full_len], full_len, &bob(), 100, 0, 0);
+    assert_eq!(result.err(), Some(ERR_DATA_FULL));
+    // Existing members are untouched
+    assert_eq!(get_votes(&full[..full_len], &alice()), 200_000_000);
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// remove_member() tests
+// ═══════════════════════════════════════════════════════════════════════
+
+#[test]
+fn test_remove_member_compacts_indices() {
+    let (data, len) = build_dao_data(&[
+        (&alice(), 100, ROLE_ADMIN),
+        (&bob(), 200, 0),
+        (&carol(), 300, ROLE_EXECUTOR),
+        (&dave(), 400, 0),
+    ]);
+
+    let (new_data, new_len) = remove_member(&data[..len], len, &bob(), 0).unwrap();
+    let d = &new_data[..new_len];
+
+    assert_eq!(get_member_count(d), 3);
+    assert_eq!(get_votes(d, &bob()), 0);
+    assert_eq!(get_total_voting_power(d), 800);
+    // Order is kept: carol and dave shift down one slot
+    let accounts: [_; 3] = core::array::from_fn(|i| {
+        crate::foundation::types::Member::load(d, i as u16).unwrap().account
+    });
+    assert_eq!(accounts, [alice(), carol(), dave()]);
+    assert!(crate::foundation::data::find_value(d, b"member_3").is_none());
+    assert!(has_role(d, &carol(), ROLE_EXECUTOR));
+    assert_invariants(d);
+}
+
+#[test]
+fn test_remove_last_member_slot() {
+    let (data, len) = build_dao_data(&[(&alice(), 100, ROLE_ADMIN), (&bob(), 200, 0)]);
+    let (new_data, new_len) = remove_member(&data[..len], len, &bob(), 0).unwrap();
+    assert_eq!(get_member_count(&new_data[..new_len]), 1);
+    assert_invariants(&new_data[..new_len]);
+}
+
+#[test]
+fn test_remove_non_member() {
+    let (data, len) = build_dao_data(&[(&alice(), 100, ROLE_ADMIN)]);
+    assert_eq!(remove_member(&data[..len], len, &eve(), 0), Err(ERR_WRONG_ACCOUNT));
+}
+
+#[test]
+fn test_remove_last_admin_rejected() {
+    let (data, len) = build_dao_data(&[(&alice(), 100, ROLE_ADMIN), (&bob(), 200, 0)]);
+    assert_eq!(remove_member(&data[..len], len, &alice(), 0), Err(ERR_BAD_CONFIG));
+
+    let (data, len) = grant_role(&data[..len], len, &bob(), ROLE_ADMIN).unwrap();
+    let (new_data, new_len) = remove_member(&data[..len], len, &alice(), 0).unwrap();
+    assert!(has_role(&new_data[..new_len], &bob(), ROLE_ADMIN));
+}
+
+#[test]
+fn test_remove_member_with_many_delegators() {
+    use crate::token::xrp_votes::{get_delegate, get_effective_votes};
+
+    // A 20-member DAO from before LIMITS were lowered, where everyone
+    // else delegates to member 1
+    let accounts: [_; 20] = core::array::from_fn(|i| mock_account(i as u8 + 1));
+    let members: [_; 20] = core::array::from_fn(|i| (&accounts[i], 10u64, if i == 0 { ROLE_ADMIN } else { 0 }));
+    let (data, len) = build_dao_data(&members);
+    let mut store = crate::foundation::data::Store::load(&data[..len]);
+    let mut key = *b"delegate_0000000000000000000000000000000000000000";
+    let mut target = [0u8; 40];
+    crate::crypto::hex::encode_hex(&accounts[1], &mut target);
+    for account in accounts.iter().filter(|a| **a != accounts[1]) {
+        crate::crypto::hex::encode_hex(account, &mut key[9..]);
+        store.set(&key, &target).unwrap();
+    }
+    let (data, len) = store.commit();
+    assert_eq!(get_effective_votes(&data[..len], &accounts[1]), 200);
+
+    let (new_data, new_len) = remove_member(&data[..len], len, &accounts[1], 0).unwrap();
+    let d = &new_data[..new_len];
+    assert_eq!(get_member_count(d), 19);
+    for account in accounts.iter().filter(|a| **a != accounts[1]) {
+        assert_eq!(get_delegate(d, account), *account);
+        assert_eq!(get_effective_votes(d, account), 10);
+    }
+}
+
+#[test]
+fn test_remove_member_clears_delegations() {
+    use crate::token::xrp_votes::{delegate, get_delegate, get_effective_votes};
+
+    let (data, len) = build_dao_data(&[
+        (&alice(), 100, ROLE_ADMIN),
+        (&bob(), 200, 0),
+        (&carol(), 300, 0),
+    ]);
+    // bob delegates to carol, alice delegates to bob
+    let (d, l) = delegate(&data[..len], len, &bob(), &carol(), 0).unwrap();
+    let (d, l) = delegate(&d[..l], l, &alice(), &bob(), 0).unwrap();
+
+    let (d, l) = remove_member(&d[..l], l, &bob(), 0).unwrap();
+    let d = &d[..l];
+    assert!(crate::foundation::data::iter_prefix(d, b"delegate_").next().is_none());
+    assert_eq!(get_delegate(d, &alice()), alice());
+    assert_eq!(get_effective_votes(d, &alice()), 100);
+    assert_eq!(get_effective_votes(d, &carol()), 300);
+}
+
+#[test]
+fn test_member_changes_snapshot_open_proposals() {
+    use crate::token::xrp_votes::{delegate, get_effective_votes, get_proposal_votes};
+
+    let (data, len) = build_dao_with_proposal(
+        &[(&alice(), 100, ROLE_ADMIN), (&bob(), 200, 0), (&carol(), 300, 0)],
+        42, &alice(), 1000, 2000, 0,
+    );
+    let (d, l) = delegate(&data[..len], len, &alice(), &bob(), 1500).unwrap();
+
+    // Power change, removal and a new member all keep the creation-time weights
+    let (d, l) = set_member(&d[..l], l, &bob(), 900, 0, 1500).unwrap();
+    let (d, l) = remove_member(&d[..l], l, &bob(), 1500).unwrap();
+    let (d, l) = set_member(&d[..l], l, &dave(), 400, 0, 1500).unwrap();
+    let d = &d[..l];
+    assert_eq!(get_effective_votes(d, &alice()), 100);
+    assert_eq!(get_proposal_votes(d, &mock_id(42), &alice()), 100);
+    assert_eq!(get_proposal_votes(d, &mock_id(42), &bob()), 200);
+    assert_eq!(get_proposal_votes(d, &mock_id(42), &carol()), 300);
+    assert_eq!(get_proposal_votes(d, &mock_id(42), &dave()), 0);
+
+    // A role-only change writes no snapshot
+    let (data, len) = build_dao_with_proposal(&[(&alice(), 100, ROLE_ADMIN)], 42, &alice(), 1000, 2000, 0);
+    let (d, l) = set_member(&data[..len], len, &alice(), 100, ROLE_ADMIN | ROLE_PROPOSER, 1500).unwrap();
+    assert!(crate::foundation::data::iter_prefix(&d[..l], b"snap_").next().is_none());
+}
+
+#[test]
+fn test_member_changes_checkpoint_votes() {
+    use crate::token::xrp_votes::{delegate, get_past_total_supply, get_past_votes};
+
+    let (data, len) = build_dao_data(&[(&alice(), 100, ROLE_ADMIN), (&bob(), 200, 0)]);
+    let (d, l) = delegate(&data[..len], len, &alice(), &bob(), 1000).unwrap();
+    let (d, l) = set_member(&d[..l], l, &alice(), 150, ROLE_ADMIN, 2000).unwrap();
+    let (d, l) = set_member(&d[..l], l, &carol(), 50, 0, 2500).unwrap();
+    let (d, l) = remove_member(&d[..l], l, &bob(), 3000).unwrap();
+    let d = &d[..l];
+
+    // alice's power sits with bob until bob is removed
+    assert_eq!(get_past_votes(d, &bob(), 1999), 300);
+    assert_eq!(get_past_votes(d, &bob(), 2000), 350);
+    assert_eq!(get_past_votes(d, &bob(), 3000), 0);
+    assert_eq!(get_past_votes(d, &alice(), 2999), 0);
+    assert_eq!(get_past_votes(d, &alice(), 3000), 150);
+    assert_eq!(get_past_votes(d, &carol(), 2500), 50);
+    assert_eq!(get_past_total_supply(d, 2000), 350);
+    assert_eq!(get_past_total_supply(d, 2500), 400);
+    assert_eq!(get_past_total_supply(d, 3000), 200);
+
+    // A role-only change writes no checkpoint
+    let (d, l) = set_member(&data[..len], len, &alice(), 100, ROLE_ADMIN | ROLE_PROPOSER, 1500).unwrap();
+    assert!(crate::foundation::data::iter_prefix(&d[..l], b"ckpt_").next().is_none());
+}
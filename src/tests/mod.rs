@@ -2,16 +2,18 @@
 //!
 //! Organized to mirror the source structure:
 //!   tests/foundation/  — data, parse tests
-//!   tests/crypto/      — hex tests
+//!   tests/crypto/      — hex, sha512, ID hashing tests
 //!   tests/governance/  — governor, counting, votes tests
 //!   tests/timelock/    — controller, operations tests
 //!   tests/token/       — xrp_votes tests
+//!   tests/exports/     — end-to-end export tests on MockHost
 
 pub mod foundation;
 pub mod crypto;
 pub mod governance;
 pub mod timelock;
 pub mod token;
+pub mod exports;
 
 use crate::foundation::config::*;
 use crate::foundation::data::*;
@@ -30,6 +32,16 @@ pub fn mock_account(seed: u8) -> [u8; ACCOUNT_ID_SIZE] {
     account
 }
 
+/// Create a mock 256-bit proposal/operation ID ending in `seed` (big-endian).
+pub fn mock_id(seed: u32) -> [u8; HASH_SIZE] {
+    crate::crypto::hash::legacy_id(seed)
+}
+
+/// Create a mock description hash: the hash of `seed`'s big-endian bytes.
+pub fn mock_desc(seed: u32) -> [u8; HASH_SIZE] {
+    crate::crypto::hash::hash_description(&seed.to_be_bytes())
+}
+
 /// Create an Alice account (proposer/admin)
 pub fn alice() -> [u8; ACCOUNT_ID_SIZE] { mock_account(0xAA) }
 
@@ -56,7 +68,7 @@ pub fn build_dao_data(
     // member_count (multi-digit safe)
     let mut count_buf = [0u8; 3];
     let count_len = crate::governance::governor::format_u8(members.len() as u8, &mut count_buf);
-    pos = write_entry(&mut data, pos, b"member_count", &count_buf[..count_len]);
+    pos = try_write_entry(&mut data, pos, b"member_count", &count_buf[..count_len]).unwrap();
 
     for (i, (account, power, roles)) in members.iter().enumerate() {
         pos = write_separator(&mut data, pos);
@@ -81,16 +93,15 @@ pub fn build_dao_data(
         vpos += plen;
         val[vpos] = b':';
         vpos += 1;
-        val[vpos] = b'0' + roles;
-        vpos += 1;
+        vpos += crate::governance::governor::format_u8(*roles, &mut val[vpos..]);
 
-        pos = write_entry(&mut data, pos, &key[..klen], &val[..vpos]);
+        pos = try_write_entry(&mut data, pos, &key[..klen], &val[..vpos]).unwrap();
     }
 
     (data, pos)
 }
 
-/// Build DAO data with a proposal already created.
+/// Build DAO data with a proposal already created; its ID is `mock_id(proposal_id)`.
 pub fn build_dao_with_proposal(
     members: &[(&[u8; ACCOUNT_ID_SIZE], u64, u8)],
     proposal_id: u32,
@@ -107,7 +118,7 @@ pub fn build_dao_with_proposal(
 
     // proposal_count=1
     pos = write_separator(&mut data, pos);
-    pos = write_entry(&mut data, pos, b"proposal_count", b"1");
+    pos = try_write_entry(&mut data, pos, b"proposal_count", b"1").unwrap();
 
     let mut key_buf = [0u8; 32];
     let mut val_buf = [0u8; 20];
@@ -115,46 +126,61 @@ pub fn build_dao_with_proposal(
     // prop_0_id
     pos = write_separator(&mut data, pos);
     let klen = build_prop_key(b"prop_", 0, b"_id", &mut key_buf);
-    let vlen = crate::foundation::parse::format_u32(proposal_id, &mut val_buf);
-    pos = write_entry(&mut data, pos, &key_buf[..klen], &val_buf[..vlen]);
+    let mut id_hex = [0u8; HASH_SIZE * 2];
+    encode_hex(&mock_id(proposal_id), &mut id_hex);
+    pos = try_write_entry(&mut data, pos, &key_buf[..klen], &id_hex).unwrap();
 
     // prop_0_proposer
     pos = write_separator(&mut data, pos);
     let klen = build_prop_key(b"prop_", 0, b"_proposer", &mut key_buf);
     let mut hex_buf = [0u8; 40];
     encode_hex(proposer, &mut hex_buf);
-    pos = write_entry(&mut data, pos, &key_buf[..klen], &hex_buf);
+    pos = try_write_entry(&mut data, pos, &key_buf[..klen], &hex_buf).unwrap();
 
     // prop_0_state
     pos = write_separator(&mut data, pos);
     let klen = build_prop_key(b"prop_", 0, b"_state", &mut key_buf);
     let state_val = [b'0' + state];
-    pos = write_entry(&mut data, pos, &key_buf[..klen], &state_val);
+    pos = try_write_entry(&mut data, pos, &key_buf[..klen], &state_val).unwrap();
 
     // prop_0_start
     pos = write_separator(&mut data, pos);
     let klen = build_prop_key(b"prop_", 0, b"_start", &mut key_buf);
     let vlen = crate::foundation::parse::format_u32(vote_start, &mut val_buf);
-    pos = write_entry(&mut data, pos, &key_buf[..klen], &val_buf[..vlen]);
+    pos = try_write_entry(&mut data, pos, &key_buf[..klen], &val_buf[..vlen]).unwrap();
 
     // prop_0_end
     pos = write_separator(&mut data, pos);
     let klen = build_prop_key(b"prop_", 0, b"_end", &mut key_buf);
     let vlen = crate::foundation::parse::format_u32(vote_end, &mut val_buf);
-    pos = write_entry(&mut data, pos, &key_buf[..klen], &val_buf[..vlen]);
+    pos = try_write_entry(&mut data, pos, &key_buf[..klen], &val_buf[..vlen]).unwrap();
 
     // prop_0_for=0, prop_0_against=0, prop_0_abstain=0
     for suffix in [b"_for" as &[u8], b"_against", b"_abstain"] {
         pos = write_separator(&mut data, pos);
         let klen = build_prop_key(b"prop_", 0, suffix, &mut key_buf);
-        pos = write_entry(&mut data, pos, &key_buf[..klen], b"0");
+        pos = try_write_entry(&mut data, pos, &key_buf[..klen], b"0").unwrap();
     }
 
     // prop_0_desc
     pos = write_separator(&mut data, pos);
     let klen = build_prop_key(b"prop_", 0, b"_desc", &mut key_buf);
-    let vlen = crate::foundation::parse::format_u32(12345, &mut val_buf);
-    pos = write_entry(&mut data, pos, &key_buf[..klen], &val_buf[..vlen]);
+    encode_hex(&mock_desc(12345), &mut id_hex);
+    pos = try_write_entry(&mut data, pos, &key_buf[..klen], &id_hex).unwrap();
+
+    // prop_0_supply and prop_0_quorum, frozen from the members at the default 4%
+    let total = members.iter().fold(0u64, |sum, m| sum.saturating_add(m.1));
+    for (suffix, value) in [(b"_supply" as &[u8], total), (b"_quorum", crate::governance::votes::quorum(total))] {
+        pos = write_separator(&mut data, pos);
+        let klen = build_prop_key(b"prop_", 0, suffix, &mut key_buf);
+        let vlen = crate::governance::governor::format_u64(value, &mut val_buf);
+        pos = try_write_entry(&mut data, pos, &key_buf[..klen], &val_buf[..vlen]).unwrap();
+    }
 
     (data, pos)
 }
+
+/// Assert that `data` satisfies every `foundation::invariants` check.
+pub fn assert_invariants(data: &[u8]) {
+    assert_eq!(crate::foundation::invariants::check(data), Ok(()), "state invariant violated");
+}
@@ -1,11 +1,13 @@
 use crate::foundation::config::*;
 use crate::foundation::data::*;
 use crate::foundation::parse::*;
+use crate::crypto::hex::encode_hex;
 use crate::timelock::controller::*;
 use crate::governance::governor::build_prop_key;
 use crate::tests::*;
 
-/// Helper: build DAO data with a timelock operation already scheduled.
+/// Helper: build DAO data with a timelock operation already scheduled;
+/// its IDs are `mock_id(proposal_id)` and `mock_id(op_id)`.
 fn build_dao_with_operation(
     members: &[(&[u8; ACCOUNT_ID_SIZE], u64, u8)],
     proposal_id: u32,
@@ -23,31 +25,32 @@ fn build_dao_with_operation(
 
     // op_count=1
     pos = write_separator(&mut data, pos);
-    pos = write_entry(&mut data, pos, b"op_count", b"1");
+    pos = try_write_entry(&mut data, pos, b"op_count", b"1").unwrap();
 
     // op_0_id
     pos = write_separator(&mut data, pos);
     let klen = build_prop_key(b"op_", 0, b"_id", &mut key_buf);
-    let vlen = format_u32(op_id, &mut val_buf);
-    pos = write_entry(&mut data, pos, &key_buf[..klen], &val_buf[..vlen]);
+    let mut id_hex = [0u8; HASH_SIZE * 2];
+    encode_hex(&mock_id(op_id), &mut id_hex);
+    pos = try_write_entry(&mut data, pos, &key_buf[..klen], &id_hex).unwrap();
 
     // op_0_prop
     pos = write_separator(&mut data, pos);
     let klen = build_prop_key(b"op_", 0, b"_prop", &mut key_buf);
-    let vlen = format_u32(proposal_id, &mut val_buf);
-    pos = write_entry(&mut data, pos, &key_buf[..klen], &val_buf[..vlen]);
+    encode_hex(&mock_id(proposal_id), &mut id_hex);
+    pos = try_write_entry(&mut data, pos, &key_buf[..klen], &id_hex).unwrap();
 
     // op_0_ready
     pos = write_separator(&mut data, pos);
     let klen = build_prop_key(b"op_", 0, b"_ready", &mut key_buf);
     let vlen = format_u32(ready_at, &mut val_buf);
-    pos = write_entry(&mut data, pos, &key_buf[..klen], &val_buf[..vlen]);
+    pos = try_write_entry(&mut data, pos, &key_buf[..klen], &val_buf[..vlen]).unwrap();
 
     // op_0_state
     pos = write_separator(&mut data, pos);
     let klen = build_prop_key(b"op_", 0, b"_state", &mut key_buf);
     let state_val = [b'0' + op_state];
-    pos = write_entry(&mut data, pos, &key_buf[..klen], &state_val);
+    pos = try_write_entry(&mut data, pos, &key_buf[..klen], &state_val).unwrap();
 
     (data, pos)
 }
@@ -62,14 +65,14 @@ fn test_schedule_success() {
         (&alice(), 200_000_000, ROLE_PROPOSER | ROLE_ADMIN),
     ]);
 
-    let result = schedule(&data[..len], len, 42, 1000, TIMELOCK_MIN_DELAY);
+    let result = schedule(&data[..len], len, &mock_id(42), 1000, TIMELOCK_MIN_DELAY);
     assert!(result.is_ok());
 
     let (new_data, new_len, op_id) = result.unwrap();
-    assert!(op_id != 0);
+    assert!(op_id != [0u8; HASH_SIZE]);
 
     // Verify operation was stored
-    let found = find_operation_by_proposal(&new_data[..new_len], 42);
+    let found = find_operation_by_proposal(&new_data[..new_len], &mock_id(42));
     assert!(found.is_ok());
 }
 
@@ -77,19 +80,27 @@ fn test_schedule_success() {
 fn test_schedule_delay_too_short() {
     let (data, len) = build_dao_data(&[(&alice(), 200_000_000, ROLE_ADMIN)]);
 
-    let result = schedule(&data[..len], len, 42, 1000, TIMELOCK_MIN_DELAY - 1);
+    let result = schedule(&data[..len], len, &mock_id(42), 1000, TIMELOCK_MIN_DELAY - 1);
     assert_eq!(result, Err(ERR_TOO_EARLY));
 }
 
+#[test]
+fn test_schedule_near_max_time_overflows() {
+    let (data, len) = build_dao_data(&[(&alice(), 200_000_000, ROLE_ADMIN)]);
+
+    let result = schedule(&data[..len], len, &mock_id(42), u32::MAX - 10, TIMELOCK_MIN_DELAY);
+    assert_eq!(result, Err(ERR_OVERFLOW));
+}
+
 #[test]
 fn test_schedule_duplicate_rejected() {
     let (data, len) = build_dao_data(&[(&alice(), 200_000_000, ROLE_ADMIN)]);
 
     // First schedule succeeds
-    let (data1, len1, _) = schedule(&data[..len], len, 42, 1000, TIMELOCK_MIN_DELAY).unwrap();
+    let (data1, len1, _) = schedule(&data[..len], len, &mock_id(42), 1000, TIMELOCK_MIN_DELAY).unwrap();
 
     // Second schedule for same proposal fails
-    let result = schedule(&data1[..len1], len1, 42, 2000, TIMELOCK_MIN_DELAY);
+    let result = schedule(&data1[..len1], len1, &mock_id(42), 2000, TIMELOCK_MIN_DELAY);
     assert_eq!(result, Err(ERR_OP_ALREADY_QUEUED));
 }
 
@@ -99,7 +110,7 @@ fn test_schedule_sets_correct_ready_time() {
 
     let current_time = 1000;
     let (new_data, new_len, _) = schedule(
-        &data[..len], len, 42, current_time, TIMELOCK_MIN_DELAY,
+        &data[..len], len, &mock_id(42), current_time, TIMELOCK_MIN_DELAY,
     ).unwrap();
 
     let expected_ready = current_time + TIMELOCK_MIN_DELAY;
@@ -245,13 +256,13 @@ fn test_find_operation_found() {
         42, 99, 200_000, OP_STATE_PENDING,
     );
 
-    assert_eq!(find_operation_by_proposal(&data[..len], 42), Ok(0));
+    assert_eq!(find_operation_by_proposal(&data[..len], &mock_id(42)), Ok(0));
 }
 
 #[test]
 fn test_find_operation_not_found() {
     let (data, len) = build_dao_data(&[(&alice(), 200_000_000, 0)]);
-    assert_eq!(find_operation_by_proposal(&data[..len], 999), Err(ERR_PROPOSAL_NOT_FOUND));
+    assert_eq!(find_operation_by_proposal(&data[..len], &mock_id(999)), Err(ERR_PROPOSAL_NOT_FOUND));
 }
 
 // ═══════════════════════════════════════════════════════════════════════
@@ -267,10 +278,10 @@ fn test_full_timelock_lifecycle() {
     // Schedule
     let schedule_time = 1000;
     let (d1, l1, _op_id) = schedule(
-        &data[..len], len, 42, schedule_time, TIMELOCK_MIN_DELAY,
+        &data[..len], len, &mock_id(42), schedule_time, TIMELOCK_MIN_DELAY,
     ).unwrap();
 
-    let op_idx = find_operation_by_proposal(&d1[..l1], 42).unwrap();
+    let op_idx = find_operation_by_proposal(&d1[..l1], &mock_id(42)).unwrap();
     assert!(is_operation_pending(&d1[..l1], op_idx, schedule_time + 1000));
 
     // Try execute too early — fails
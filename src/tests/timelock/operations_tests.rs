@@ -15,12 +15,12 @@ fn test_schedule_with_predecessor() {
 
     // Schedule first operation
     let (d1, l1, op1_id) = controller::schedule(
-        &data[..len], len, 100, 1000, TIMELOCK_MIN_DELAY,
+        &data[..len], len, &mock_id(100), 1000, TIMELOCK_MIN_DELAY,
     ).unwrap();
 
     // Schedule second with predecessor
     let result = schedule_with_predecessor(
-        &d1[..l1], l1, 200, op1_id, 2000, TIMELOCK_MIN_DELAY,
+        &d1[..l1], l1, &mock_id(200), &op1_id, 2000, TIMELOCK_MIN_DELAY,
     );
     assert!(result.is_ok());
 
@@ -37,11 +37,11 @@ fn test_predecessor_zero_means_none() {
 
     // Schedule without predecessor — predecessor field won't exist
     let (d1, l1, _) = controller::schedule(
-        &data[..len], len, 100, 1000, TIMELOCK_MIN_DELAY,
+        &data[..len], len, &mock_id(100), 1000, TIMELOCK_MIN_DELAY,
     ).unwrap();
 
     let pred = get_predecessor(&d1[..l1], 0);
-    assert_eq!(pred, 0); // no predecessor
+    assert_eq!(pred, [0u8; HASH_SIZE]); // no predecessor
 }
 
 // ═══════════════════════════════════════════════════════════════════════
@@ -56,12 +56,12 @@ fn test_execute_blocked_by_predecessor() {
 
     // Schedule op1
     let (d1, l1, op1_id) = controller::schedule(
-        &data[..len], len, 100, 1000, TIMELOCK_MIN_DELAY,
+        &data[..len], len, &mock_id(100), 1000, TIMELOCK_MIN_DELAY,
     ).unwrap();
 
     // Schedule op2 with op1 as predecessor
     let (d2, l2, _op2_id) = schedule_with_predecessor(
-        &d1[..l1], l1, 200, op1_id, 2000, TIMELOCK_MIN_DELAY,
+        &d1[..l1], l1, &mock_id(200), &op1_id, 2000, TIMELOCK_MIN_DELAY,
     ).unwrap();
 
     // Try to execute op2 while op1 is still pending (not done)
@@ -78,12 +78,12 @@ fn test_execute_after_predecessor_done() {
 
     // Schedule op1
     let (d1, l1, op1_id) = controller::schedule(
-        &data[..len], len, 100, 1000, TIMELOCK_MIN_DELAY,
+        &data[..len], len, &mock_id(100), 1000, TIMELOCK_MIN_DELAY,
     ).unwrap();
 
     // Schedule op2 with predecessor
     let (d2, l2, _) = schedule_with_predecessor(
-        &d1[..l1], l1, 200, op1_id, 2000, TIMELOCK_MIN_DELAY,
+        &d1[..l1], l1, &mock_id(200), &op1_id, 2000, TIMELOCK_MIN_DELAY,
     ).unwrap();
 
     // Execute op1 first
@@ -103,7 +103,7 @@ fn test_execute_no_predecessor_works() {
     ]);
 
     let (d1, l1, _) = controller::schedule(
-        &data[..len], len, 100, 1000, TIMELOCK_MIN_DELAY,
+        &data[..len], len, &mock_id(100), 1000, TIMELOCK_MIN_DELAY,
     ).unwrap();
 
     // No predecessor → execute directly
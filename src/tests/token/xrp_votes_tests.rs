@@ -13,7 +13,7 @@ fn test_delegate_to_another() {
         (&bob(), 100_000_000, 0),
     ]);
 
-    let (new_data, new_len) = delegate(&data[..len], len, &alice(), &bob()).unwrap();
+    let (new_data, new_len) = delegate(&data[..len], len, &alice(), &bob(), 0).unwrap();
     let d = get_delegate(&new_data[..new_len], &alice());
     assert_eq!(d, bob());
 }
@@ -26,14 +26,28 @@ fn test_self_delegation_clears() {
     ]);
 
     // First delegate to bob
-    let (d1, l1) = delegate(&data[..len], len, &alice(), &bob()).unwrap();
+    let (d1, l1) = delegate(&data[..len], len, &alice(), &bob(), 0).unwrap();
     assert_eq!(get_delegate(&d1[..l1], &alice()), bob());
 
     // Self-delegate to remove
-    let (d2, l2) = delegate(&d1[..l1], l1, &alice(), &alice()).unwrap();
+    let (d2, l2) = delegate(&d1[..l1], l1, &alice(), &alice(), 0).unwrap();
     assert_eq!(get_delegate(&d2[..l2], &alice()), alice()); // back to self
 }
 
+#[test]
+fn test_delegation_requires_members() {
+    let (data, len) = build_dao_data(&[
+        (&alice(), 200_000_000, ROLE_PROPOSER),
+        (&bob(), 100_000_000, 0),
+    ]);
+
+    // Outsiders can neither delegate nor receive a delegation
+    assert_eq!(delegate(&data[..len], len, &eve(), &alice(), 0).err(), Some(ERR_WRONG_ACCOUNT));
+    assert_eq!(delegate(&data[..len], len, &eve(), &eve(), 0).err(), Some(ERR_WRONG_ACCOUNT));
+    assert_eq!(delegate(&data[..len], len, &alice(), &eve(), 0).err(), Some(ERR_WRONG_ACCOUNT));
+    assert!(delegate(&data[..len], len, &alice(), &alice(), 0).is_ok());
+}
+
 #[test]
 fn test_default_self_delegation() {
     let (data, len) = build_dao_data(&[(&alice(), 200_000_000, 0)]);
@@ -65,7 +79,7 @@ fn test_effective_votes_with_delegation() {
     ]);
 
     // Alice delegates to Bob
-    let (d1, l1) = delegate(&data[..len], len, &alice(), &bob()).unwrap();
+    let (d1, l1) = delegate(&data[..len], len, &alice(), &bob(), 0).unwrap();
 
     // Bob now has his own power + alice's delegated power
     let bob_votes = get_effective_votes(&d1[..l1], &bob());
@@ -91,8 +105,8 @@ fn test_effective_votes_multiple_delegations() {
     ]);
 
     // Alice and Carol both delegate to Bob
-    let (d1, l1) = delegate(&data[..len], len, &alice(), &bob()).unwrap();
-    let (d2, l2) = delegate(&d1[..l1], l1, &carol(), &bob()).unwrap();
+    let (d1, l1) = delegate(&data[..len], len, &alice(), &bob(), 0).unwrap();
+    let (d2, l2) = delegate(&d1[..l1], l1, &carol(), &bob(), 0).unwrap();
 
     let bob_votes = get_effective_votes(&d2[..l2], &bob());
     // bob's own (100M) + alice's (200M) + carol's (150M)
@@ -110,10 +124,10 @@ fn test_snapshot_voting_power() {
         (&bob(), 100_000_000, 0),
     ]);
 
-    let prop_id = 42;
-    let (d1, l1) = snapshot_voting_power(&data[..len], len, prop_id, &alice()).unwrap();
+    let prop_id = mock_id(42);
+    let (d1, l1) = snapshot_voting_power(&data[..len], len, &prop_id, &alice()).unwrap();
 
-    let snapped = get_snapshot_votes(&d1[..l1], prop_id, &alice());
+    let snapped = get_snapshot_votes(&d1[..l1], &prop_id, &alice());
     assert_eq!(snapped, 200_000_000);
 }
 
@@ -121,7 +135,7 @@ fn test_snapshot_voting_power() {
 fn test_snapshot_not_found() {
     let (data, len) = build_dao_data(&[(&alice(), 200_000_000, 0)]);
     // No snapshot taken → returns 0
-    assert_eq!(get_snapshot_votes(&data[..len], 999, &alice()), 0);
+    assert_eq!(get_snapshot_votes(&data[..len], &mock_id(999), &alice()), 0);
 }
 
 #[test]
@@ -130,12 +144,12 @@ fn test_snapshot_different_proposals() {
         (&alice(), 200_000_000, 0),
     ]);
 
-    let (d1, l1) = snapshot_voting_power(&data[..len], len, 1, &alice()).unwrap();
-    let (d2, l2) = snapshot_voting_power(&d1[..l1], l1, 2, &alice()).unwrap();
+    let (d1, l1) = snapshot_voting_power(&data[..len], len, &mock_id(1), &alice()).unwrap();
+    let (d2, l2) = snapshot_voting_power(&d1[..l1], l1, &mock_id(2), &alice()).unwrap();
 
     // Both snapshots preserved
-    assert_eq!(get_snapshot_votes(&d2[..l2], 1, &alice()), 200_000_000);
-    assert_eq!(get_snapshot_votes(&d2[..l2], 2, &alice()), 200_000_000);
+    assert_eq!(get_snapshot_votes(&d2[..l2], &mock_id(1), &alice()), 200_000_000);
+    assert_eq!(get_snapshot_votes(&d2[..l2], &mock_id(2), &alice()), 200_000_000);
 }
 
 #[test]
@@ -146,10 +160,166 @@ fn test_snapshot_with_delegation() {
     ]);
 
     // Alice delegates to Bob
-    let (d1, l1) = delegate(&data[..len], len, &alice(), &bob()).unwrap();
+    let (d1, l1) = delegate(&data[..len], len, &alice(), &bob(), 0).unwrap();
 
     // Snapshot Bob's effective power (should include delegation)
-    let (d2, l2) = snapshot_voting_power(&d1[..l1], l1, 42, &bob()).unwrap();
-    let snapped = get_snapshot_votes(&d2[..l2], 42, &bob());
+    let (d2, l2) = snapshot_voting_power(&d1[..l1], l1, &mock_id(42), &bob()).unwrap();
+    let snapped = get_snapshot_votes(&d2[..l2], &mock_id(42), &bob());
     assert_eq!(snapped, 300_000_000); // own + delegated
 }
+
+#[test]
+fn test_delegation_snapshots_open_proposals_only() {
+    let (data, len) = build_dao_with_proposal(
+        &[(&alice(), 200_000_000, 0), (&bob(), 100_000_000, 0)],
+        42, &alice(), 1000, 2000, 0,
+    );
+
+    // While the proposal is open, both sides keep their creation-time votes
+    let (d, l) = delegate(&data[..len], len, &alice(), &bob(), 1500).unwrap();
+    assert_eq!(get_effective_votes(&d[..l], &bob()), 300_000_000);
+    assert_eq!(get_proposal_votes(&d[..l], &mock_id(42), &alice()), 200_000_000);
+    assert_eq!(get_proposal_votes(&d[..l], &mock_id(42), &bob()), 100_000_000);
+
+    // The first snapshot wins; undoing the delegation changes nothing
+    let (d, l) = delegate(&d[..l], l, &alice(), &alice(), 1600).unwrap();
+    let (d, l) = delegate(&d[..l], l, &alice(), &bob(), 1700).unwrap();
+    assert_eq!(get_snapshot_votes(&d[..l], &mock_id(42), &alice()), 200_000_000);
+    assert_eq!(get_snapshot_votes(&d[..l], &mock_id(42), &bob()), 100_000_000);
+
+    // Once voting has ended nothing is recorded
+    let (d, l) = delegate(&data[..len], len, &alice(), &bob(), 2001).unwrap();
+    assert!(crate::foundation::data::iter_prefix(&d[..l], b"snap_").next().is_none());
+    assert_eq!(get_proposal_votes(&d[..l], &mock_id(42), &bob()), 300_000_000);
+}
+
+// ═══════════════════════════════════════════════════════════════════════
+// Checkpoint tests — mirrors ERC20Votes.getPastVotes()
+// ═══════════════════════════════════════════════════════════════════════
+
+/// Alice (200 XRP) and Bob (100 XRP) with histories starting at time 0.
+fn checkpointed_dao() -> ([u8; crate::foundation::data::DATA_CAPACITY], usize) {
+    let (data, len) = build_dao_data(&[(&alice(), 200_000_000, 0), (&bob(), 100_000_000, 0)]);
+    let mut store = crate::foundation::data::Store::load(&data[..len]);
+    checkpoint_votes(&mut store, &alice(), 0).unwrap();
+    checkpoint_votes(&mut store, &bob(), 0).unwrap();
+    checkpoint_total_supply(&mut store, 0).unwrap();
+    store.commit()
+}
+
+#[test]
+fn test_past_votes_follow_delegation_history() {
+    let (data, len) = checkpointed_dao();
+    let (d, l) = delegate(&data[..len], len, &alice(), &bob(), 1000).unwrap();
+    let (d, l) = delegate(&d[..l], l, &alice(), &alice(), 2000).unwrap();
+    let d = &d[..l];
+
+    assert_eq!(crate::foundation::data::find_value(d, b"ckpt_total"), Some(&b"0:300000000"[..]));
+    for (time, alice_votes, bob_votes) in [
+        (0, 200_000_000, 100_000_000),
+        (999, 200_000_000, 100_000_000),
+        (1000, 0, 300_000_000),
+        (1999, 0, 300_000_000),
+        (2000, 200_000_000, 100_000_000),
+        (u32::MAX, 200_000_000, 100_000_000),
+    ] {
+        assert_eq!(get_past_votes(d, &alice(), time), alice_votes, "alice at {time}");
+        assert_eq!(get_past_votes(d, &bob(), time), bob_votes, "bob at {time}");
+        assert_eq!(get_past_total_supply(d, time), 300_000_000);
+    }
+    // Before the first checkpoint, or without one, there are no votes
+    assert_eq!(get_past_votes(d, &carol(), 5000), 0);
+}
+
+#[test]
+fn test_checkpoint_at_same_time_replaces_last() {
+    let (data, len) = checkpointed_dao();
+    let (d, l) = delegate(&data[..len], len, &alice(), &bob(), 1000).unwrap();
+    let (d, l) = delegate(&d[..l], l, &alice(), &alice(), 1000).unwrap();
+    let d = &d[..l];
+
+    // Delegating and undoing it in one ledger leaves no trace
+    let mut key = [0u8; 45];
+    key[..5].copy_from_slice(b"ckpt_");
+    crate::crypto::hex::encode_hex(&bob(), &mut key[5..]);
+    assert_eq!(crate::foundation::data::find_value(d, &key), Some(&b"0:100000000"[..]));
+    assert_eq!(get_past_votes(d, &alice(), 1000), 200_000_000);
+}
+
+#[test]
+fn test_past_votes_binary_search_capped_history() {
+    let (data, len) = checkpointed_dao();
+    let (mut d, mut l) = (data, len);
+    let mut len_at_half = 0;
+    for step in 1..=40u32 {
+        let to = if step % 2 == 1 { bob() } else { alice() };
+        (d, l) = delegate(&d[..l], l, &alice(), &to, 10_000 + step * 100).unwrap();
+        if step == 20 {
+            len_at_half = l;
+        }
+    }
+    let d = &d[..l];
+
+    // Repeated delegation stops growing the blob once histories are full
+    assert_eq!(l, len_at_half);
+
+    // The last MAX_CHECKPOINTS changes are still searchable
+    let oldest_kept = 40 - MAX_CHECKPOINTS as u32 + 1;
+    for step in oldest_kept..=40u32 {
+        let bob_votes = if step % 2 == 1 { 300_000_000 } else { 100_000_000 };
+        let time = 10_000 + step * 100;
+        assert_eq!(get_past_votes(d, &bob(), time), bob_votes);
+        assert_eq!(get_past_votes(d, &bob(), time + 99), bob_votes);
+        assert_eq!(get_past_votes(d, &alice(), time + 50), 300_000_000 - bob_votes);
+    }
+    // Anything older was dropped
+    assert_eq!(get_past_votes(d, &bob(), 10_000 + (oldest_kept - 1) * 100), 0);
+}
+
+#[test]
+fn test_delegation_churn_cannot_crowd_out_votes() {
+    use crate::foundation::data::{check_fits, DATA_CAPACITY};
+    use crate::foundation::types::Actions;
+    use crate::governance::{counting, governor};
+
+    // State written under larger LIMITS: more members than this build
+    // allows, and open proposals none of them has voted on yet
+    const MEMBERS: usize = if cfg!(feature = "compact-state") { 40 } else { 12 };
+    let accounts: [[u8; ACCOUNT_ID_SIZE]; MEMBERS] = core::array::from_fn(|i| mock_account(i as u8 + 1));
+    let members: [(&[u8; ACCOUNT_ID_SIZE], u64, u8); MEMBERS] =
+        core::array::from_fn(|i| (&accounts[i], 100_000_000, ROLE_PROPOSER));
+    let (base, mut len) = build_dao_data(&members);
+    let mut data = [0u8; DATA_CAPACITY];
+    data[..len].copy_from_slice(&base[..len]);
+    let mut ids = [[0u8; HASH_SIZE]; MAX_PROPOSALS];
+    for (i, id) in ids.iter_mut().enumerate() {
+        (data, len, *id) = governor::propose(&data[..len], len, &accounts[0], &mock_desc(i as u32), &Actions::new(), 1000, 100_000_000).unwrap();
+    }
+
+    // The last member hops its delegation from member to member while the
+    // proposals are Pending, piling up snapshots and checkpoints, until a
+    // hop would leave no room for the votes
+    let attacker = accounts[MEMBERS - 1];
+    let mut refused = None;
+    for step in 0..250u32 {
+        let to = &accounts[step as usize % (MEMBERS - 1)];
+        match delegate(&data[..len], len, &attacker, to, 1001 + step) {
+            Ok((d, l)) => (data, len) = (d, l),
+            Err(code) => {
+                refused = Some(code);
+                break;
+            }
+        }
+    }
+    assert_eq!(refused, Some(ERR_DATA_FULL));
+
+    // Everyone can still vote on everything, and the result is storable
+    let voting = 1000 + VOTING_DELAY + 1;
+    for (j, id) in ids.iter().enumerate() {
+        for account in &accounts {
+            let weight = get_proposal_votes(&data[..len], id, account);
+            (data, len) = counting::cast_vote(&data[..len], len, j as u16, account, VOTE_FOR, weight, voting).unwrap();
+        }
+    }
+    assert_eq!(check_fits(&data[..len]), Ok(()));
+}
@@ -10,12 +10,12 @@
 //! can be executed.
 //!
 //! Data format:
-//!   op_N_predecessor=<op_id>    (0 means no predecessor)
+//!   op_N_predecessor=<op_id hex64>    (absent means no predecessor)
 
 use crate::foundation::config::*;
 use crate::foundation::data::*;
-use crate::foundation::parse::*;
-use crate::governance::governor::build_prop_key;
+use crate::foundation::types::TimelockOp;
+use crate::governance::governor::read_count;
 use crate::timelock::controller;
 
 /// Schedule an operation with a predecessor dependency.
@@ -23,30 +23,24 @@ use crate::timelock::controller;
 pub fn schedule_with_predecessor(
     data: &[u8],
     data_len: usize,
-    proposal_id: u32,
-    predecessor_op_id: u32,
+    proposal_id: &[u8; HASH_SIZE],
+    predecessor_op_id: &[u8; HASH_SIZE],
     current_time: u32,
     delay: u32,
-) -> Result<([u8; 4096], usize, u32), i32> {
+) -> Result<([u8; DATA_CAPACITY], usize, [u8; HASH_SIZE]), i32> {
     // Schedule the base operation
-    let (mut new_data, mut pos, op_id) =
+    let (new_data, pos, op_id) =
         controller::schedule(data, data_len, proposal_id, current_time, delay)?;
 
-    // Append predecessor reference
-    let op_count = find_value(&new_data[..pos], b"op_count")
-        .and_then(parse_u8_digit)
-        .unwrap_or(0);
+    // Record the predecessor on the just-added operation
+    let op_index = read_count(&new_data[..pos], b"op_count") - 1;
+    let mut op = TimelockOp::load(&new_data[..pos], op_index).ok_or(ERR_PROPOSAL_NOT_FOUND)?;
+    op.predecessor = *predecessor_op_id;
 
-    let op_index = op_count - 1; // just-added operation
-
-    let mut key_buf = [0u8; 32];
-    let klen = build_prop_key(b"op_", op_index, b"_predecessor", &mut key_buf);
-    let mut val_buf = [0u8; 10];
-    let vlen = format_u32(predecessor_op_id, &mut val_buf);
-
-    if pos > 0 { pos = crate::foundation::data::write_separator(&mut new_data, pos); }
-    pos = crate::foundation::data::write_entry(&mut new_data, pos, &key_buf[..klen], &val_buf[..vlen]);
+    let mut store = Store::load(&new_data[..pos]);
+    op.store(&mut store, op_index)?;
 
+    let (new_data, pos) = store.commit();
     Ok((new_data, pos, op_id))
 }
 
@@ -55,21 +49,13 @@ pub fn schedule_with_predecessor(
 pub fn execute_with_predecessor_check(
     data: &[u8],
     data_len: usize,
-    operation_index: u8,
+    operation_index: u16,
     current_time: u32,
-) -> Result<([u8; 4096], usize), i32> {
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
     // Check predecessor is done (if any)
-    let mut key_buf = [0u8; 32];
-    let klen = build_prop_key(b"op_", operation_index, b"_predecessor", &mut key_buf);
-
-    if let Some(pred_val) = find_value(data, &key_buf[..klen]) {
-        let pred_id = parse_u32(pred_val).unwrap_or(0);
-        if pred_id != 0 {
-            // Find predecessor operation and check it's Done
-            if !is_predecessor_done(data, pred_id, current_time) {
-                return Err(ERR_OP_NOT_READY);
-            }
-        }
+    let pred_id = get_predecessor(data, operation_index);
+    if pred_id != [0u8; HASH_SIZE] && !is_predecessor_done(data, &pred_id, current_time) {
+        return Err(ERR_OP_NOT_READY);
     }
 
     // Delegate to base execute
@@ -77,32 +63,17 @@ pub fn execute_with_predecessor_check(
 }
 
 /// Check if a predecessor operation (by op_id) is in Done state.
-fn is_predecessor_done(data: &[u8], predecessor_op_id: u32, _current_time: u32) -> bool {
-    let op_count = find_value(data, b"op_count")
-        .and_then(parse_u8_digit)
-        .unwrap_or(0);
+fn is_predecessor_done(data: &[u8], predecessor_op_id: &[u8; HASH_SIZE], _current_time: u32) -> bool {
+    let op_count = read_count(data, b"op_count");
 
-    let mut key_buf = [0u8; 32];
-    let mut id_buf = [0u8; 10];
-    let id_len = format_u32(predecessor_op_id, &mut id_buf);
-
-    for i in 0..op_count {
-        let klen = build_prop_key(b"op_", i, b"_id", &mut key_buf);
-        if let Some(stored_id) = find_value(data, &key_buf[..klen]) {
-            if stored_id == &id_buf[..id_len] {
-                return controller::is_operation_done(data, i);
-            }
-        }
-    }
-    false
+    (0..op_count)
+        .filter_map(|i| TimelockOp::load(data, i))
+        .find(|op| op.id == *predecessor_op_id)
+        .is_some_and(|op| op.state == OP_STATE_DONE)
 }
 
 /// Get the predecessor operation ID for a given operation.
-/// Returns 0 if no predecessor.
-pub fn get_predecessor(data: &[u8], operation_index: u8) -> u32 {
-    let mut key_buf = [0u8; 32];
-    let klen = build_prop_key(b"op_", operation_index, b"_predecessor", &mut key_buf);
-    find_value(data, &key_buf[..klen])
-        .and_then(|v| parse_u32(v))
-        .unwrap_or(0)
+/// Returns all zeros if no predecessor.
+pub fn get_predecessor(data: &[u8], operation_index: u16) -> [u8; HASH_SIZE] {
+    TimelockOp::load(data, operation_index).map_or([0u8; HASH_SIZE], |op| op.predecessor)
 }
@@ -7,32 +7,73 @@
 //! 2. Issued tokens (IOUs) on the XRPL DEX
 //! 3. Snapshot-based voting (balance at proposal creation time)
 //!
+//! ## Snapshots
+//!
+//! Votes are weighed as of proposal creation, but nothing is written when
+//! a proposal is created. Snapshots are copy-on-write: just before an
+//! account's effective votes change (delegation, power grant, member
+//! removal), `preserve_snapshots` records its current value for every
+//! proposal still Pending or Active that has no snapshot of it yet. An
+//! account without a snapshot has not changed since the proposal was
+//! created, so its live votes are its snapshot votes. The blob only grows
+//! by one entry per (open proposal, changed account) pair.
+//!
+//! ## Checkpoints
+//!
+//! Like ERC20Votes, every account keeps a history of its effective votes,
+//! and the DAO one of its total voting power, appended to whenever a
+//! delegation or member change moves them. Each checkpoint is
+//! `<timepoint>:<power>`, meaning "from this ledger time on"; a second
+//! change at the same timepoint overwrites the last one. `get_past_votes`
+//! and `get_past_total_supply` binary-search the history.
+//!
+//! Unlike ERC20Votes the history is bounded. A push keeps the last
+//! MAX_CHECKPOINTS entries, and `prune` drops those superseded before the
+//! horizon: the `vote_start` (OZ's proposalSnapshot) of the oldest Pending
+//! or Active proposal, or the current time if none is open. The entry in
+//! force at the horizon is kept; lookups before the oldest kept entry
+//! return 0. Votes themselves are weighed from snapshots, not from here.
+//!
 //! ## Delegation
 //!
 //! Like ERC20Votes, members can delegate their voting power to another
 //! account. Self-delegation is implicit (if no delegate set, votes count
-//! as self-delegated).
+//! as self-delegated). A delegation that grows the state must leave room
+//! for every vote still to come on open proposals.
 //!
 //! Data format:
 //!   delegate_<voter_hex>=<delegate_hex>
-//!   snapshot_<prop_id>_<account_hex>=<power_at_snapshot>
+//!   snap_<prop_id hex64>_<account_hex>=<power_at_snapshot>
+//!   ckpt_<account_hex>=<time>:<power>,<time>:<power>,...
+//!   ckpt_total=<time>:<power>,...
 
 use crate::foundation::config::*;
 use crate::foundation::data::*;
-use crate::foundation::parse::*;
 use crate::crypto::hex::encode_hex;
-use crate::governance::governor::{parse_u64, format_u64};
-use crate::governance::votes;
+use crate::foundation::parse::{format_u32, parse_u32};
+use crate::governance::governor::{format_u64, get_proposal_state, parse_u64, read_count};
+use crate::governance::{counting, votes};
+use crate::foundation::types::{Member, Proposal};
 
 /// Delegate voting power to another account. Mirrors ERC20Votes.delegate().
 ///
 /// If delegate == voter (self-delegation), clears any existing delegation.
+/// Power moves from the current delegate to `delegate_to`; both are
+/// snapshotted first for open proposals.
+///
+/// Only members may delegate, and only to a member: ERR_WRONG_ACCOUNT
+/// otherwise, so outsiders cannot fill the Data field with `delegate_`
+/// and `ckpt_` entries. A change that grows the state fails with
+/// ERR_DATA_FULL unless every vote still to come on open proposals would
+/// fit after it, so a member cannot crowd out votes by delegating back
+/// and forth.
 pub fn delegate(
     data: &[u8],
     data_len: usize,
     voter: &[u8; ACCOUNT_ID_SIZE],
     delegate_to: &[u8; ACCOUNT_ID_SIZE],
-) -> Result<([u8; 4096], usize), i32> {
+    current_time: u32,
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
     let mut voter_hex = [0u8; 40];
     encode_hex(voter, &mut voter_hex);
 
@@ -46,56 +87,52 @@ pub fn delegate(
     key_buf[prefix.len()..prefix.len() + 40].copy_from_slice(&voter_hex);
     let key_len = prefix.len() + 40;
 
-    // Check if delegation already exists
-    let _existing = find_value(data, &key_buf[..key_len]);
+    if Member::find(data, voter).is_none()
+        || (delegate_to != voter && Member::find(data, delegate_to).is_none())
+    {
+        return Err(ERR_WRONG_ACCOUNT);
+    }
 
-    // Self-delegation: if voter == delegate, remove delegation entry
-    let is_self_delegate = voter == delegate_to;
+    let mut store = Store::load(&data[..data_len]);
 
-    let mut new_data = [0u8; 4096];
-    let mut pos = 0;
-    let mut scan = 0;
-    let mut found = false;
-
-    while scan < data_len {
-        let entry_end = data[scan..data_len].iter()
-            .position(|&b| b == b';')
-            .map(|p| scan + p)
-            .unwrap_or(data_len);
-
-        let entry = &data[scan..entry_end];
-
-        let is_target = if let Some(eq) = entry.iter().position(|&b| b == b'=') {
-            &entry[..eq] == &key_buf[..key_len]
-        } else { false };
-
-        if is_target {
-            found = true;
-            if !is_self_delegate {
-                // Update delegation
-                if pos > 0 { pos = write_separator(&mut new_data, pos); }
-                pos = write_entry(&mut new_data, pos, &key_buf[..key_len], &delegate_hex);
-            }
-            // If self-delegate, skip (removes delegation)
-        } else if !entry.is_empty() {
-            if pos > 0 { pos = write_separator(&mut new_data, pos); }
-            let elen = entry.len();
-            if pos + elen <= new_data.len() {
-                new_data[pos..pos + elen].copy_from_slice(entry);
-                pos += elen;
-            }
-        }
+    let previous = get_delegate(data, voter);
+    if previous != *delegate_to {
+        preserve_snapshots(data, &mut store, &previous, current_time)?;
+        preserve_snapshots(data, &mut store, delegate_to, current_time)?;
+    }
+
+    // Self-delegation: if voter == delegate, remove delegation entry
+    if voter == delegate_to {
+        clear_delegation(&mut store, voter);
+    } else {
+        store.set(&key_buf[..key_len], &delegate_hex)?;
+    }
 
-        scan = entry_end + 1;
+    if previous != *delegate_to {
+        checkpoint_votes(&mut store, &previous, current_time)?;
+        checkpoint_votes(&mut store, delegate_to, current_time)?;
     }
 
-    // New delegation (not found previously)
-    if !found && !is_self_delegate {
-        if pos > 0 { pos = write_separator(&mut new_data, pos); }
-        pos = write_entry(&mut new_data, pos, &key_buf[..key_len], &delegate_hex);
+    if store.len() > data_len {
+        check_vote_headroom(store.as_bytes(), current_time)?;
     }
+    Ok(store.commit())
+}
 
-    Ok((new_data, pos))
+/// ERR_DATA_FULL unless `data` still fits the Data field once every
+/// member has voted on every open proposal.
+fn check_vote_headroom(data: &[u8], current_time: u32) -> Result<(), i32> {
+    let mut reserved = Store::load(data);
+    counting::reserve_remaining_votes(&mut reserved, current_time)?;
+    check_fits(reserved.as_bytes())
+}
+
+/// Drop `voter`'s delegation entry, reverting to self-delegation.
+pub fn clear_delegation(store: &mut Store, voter: &[u8; ACCOUNT_ID_SIZE]) {
+    let mut key_buf = [0u8; 49]; // "delegate_" + 40 hex
+    key_buf[..9].copy_from_slice(b"delegate_");
+    encode_hex(voter, &mut key_buf[9..]);
+    store.remove(&key_buf);
 }
 
 /// Get the delegate for a voter. Returns the delegate's AccountID.
@@ -132,46 +169,24 @@ pub fn get_effective_votes(
     data: &[u8],
     account: &[u8; ACCOUNT_ID_SIZE],
 ) -> u64 {
-    let mut account_hex = [0u8; 40];
-    encode_hex(account, &mut account_hex);
-
     let mut total_power: u64 = 0;
 
     // Self-power (if self-delegated or no delegation)
     let self_delegate = get_delegate(data, account);
     if self_delegate == *account {
-        total_power += votes::get_votes(data, account);
+        total_power = total_power.saturating_add(votes::get_votes(data, account));
     }
 
     // Scan all members for those who delegated to this account
     let member_count = votes::get_member_count(data);
-    let mut key_buf = [0u8; 16];
-
-    for i in 0..member_count {
-        // Build multi-digit member key
-        let prefix = b"member_";
-        let plen = prefix.len();
-        key_buf[..plen].copy_from_slice(prefix);
-        let idx_len = crate::governance::governor::format_u8(i, &mut key_buf[plen..]);
-        let klen = plen + idx_len;
-
-        if let Some(val) = find_value(data, &key_buf[..klen]) {
-            if val.len() >= 40 {
-                let member_hex = &val[..40];
-                // Skip self (already counted)
-                if member_hex == &account_hex[..] {
-                    continue;
-                }
-
-                // Decode this member's AccountID
-                let mut member_id = [0u8; ACCOUNT_ID_SIZE];
-                if crate::crypto::hex::decode_hex(member_hex, &mut member_id) {
-                    let their_delegate = get_delegate(data, &member_id);
-                    if their_delegate == *account {
-                        total_power += votes::get_votes(data, &member_id);
-                    }
-                }
-            }
+
+    for member in (0..member_count).filter_map(|i| Member::load(data, i)) {
+        // Skip self (already counted)
+        if member.account == *account {
+            continue;
+        }
+        if get_delegate(data, &member.account) == *account {
+            total_power = total_power.saturating_add(member.voting_power);
         }
     }
 
@@ -179,80 +194,280 @@ pub fn get_effective_votes(
 }
 
 /// Take a snapshot of voting power at proposal creation time.
-/// Stored as: snapshot_<prop_id>_<account_hex>=<power>
+/// Stored as: snap_<prop_id hex64>_<account_hex>=<power>
 pub fn snapshot_voting_power(
     data: &[u8],
     data_len: usize,
-    proposal_id: u32,
+    proposal_id: &[u8; HASH_SIZE],
     account: &[u8; ACCOUNT_ID_SIZE],
-) -> Result<([u8; 4096], usize), i32> {
+) -> Result<([u8; DATA_CAPACITY], usize), i32> {
     let power = get_effective_votes(data, account);
 
-    let mut account_hex = [0u8; 40];
-    encode_hex(account, &mut account_hex);
+    let mut key_buf = [0u8; SNAPSHOT_KEY_LEN];
+    build_snapshot_key(proposal_id, account, &mut key_buf);
 
-    // Build key: "snap_<prop_id>_<hex>"
-    let mut key_buf = [0u8; 64];
-    let prefix = b"snap_";
-    let mut kpos = prefix.len();
-    key_buf[..kpos].copy_from_slice(prefix);
+    // First snapshot wins — later calls leave it untouched
+    let mut store = Store::load(&data[..data_len]);
+    insert_snapshot(&mut store, &key_buf, power)?;
 
-    let mut id_buf = [0u8; 10];
-    let id_len = format_u32(proposal_id, &mut id_buf);
-    key_buf[kpos..kpos + id_len].copy_from_slice(&id_buf[..id_len]);
-    kpos += id_len;
+    Ok(store.commit())
+}
 
-    key_buf[kpos] = b'_';
-    kpos += 1;
+/// Copy-on-write: record `account`'s effective votes in `data` (the state
+/// before a change) for each proposal open at `current_time` that has no
+/// snapshot of it yet. Call before any write that changes its votes.
+pub fn preserve_snapshots(
+    data: &[u8],
+    store: &mut Store,
+    account: &[u8; ACCOUNT_ID_SIZE],
+    current_time: u32,
+) -> Result<(), i32> {
+    let mut power = None;
+    let mut key_buf = [0u8; SNAPSHOT_KEY_LEN];
+
+    for i in 0..read_count(data, b"proposal_count") {
+        let state = get_proposal_state(data, i, current_time);
+        if state != PROPOSAL_STATE_PENDING && state != PROPOSAL_STATE_ACTIVE {
+            continue;
+        }
+        let Some(proposal) = Proposal::load(data, i) else { continue };
+        build_snapshot_key(&proposal.id, account, &mut key_buf);
+        let power = *power.get_or_insert_with(|| get_effective_votes(data, account));
+        insert_snapshot(store, &key_buf, power)?;
+    }
+    Ok(())
+}
+
+/// Get snapshotted voting power for an account at a specific proposal.
+pub fn get_snapshot_votes(
+    data: &[u8],
+    proposal_id: &[u8; HASH_SIZE],
+    account: &[u8; ACCOUNT_ID_SIZE],
+) -> u64 {
+    let mut key_buf = [0u8; SNAPSHOT_KEY_LEN];
+    build_snapshot_key(proposal_id, account, &mut key_buf);
 
-    key_buf[kpos..kpos + 40].copy_from_slice(&account_hex);
-    kpos += 40;
+    find_value(data, &key_buf)
+        .and_then(parse_u64)
+        .unwrap_or(0)
+}
 
-    // Append snapshot entry
-    let mut new_data = [0u8; 4096];
-    let mut pos = 0;
+/// Voting power `account` votes with on proposal `proposal_id`: its
+/// snapshot if its votes changed since the proposal was created, else its
+/// live effective votes.
+pub fn get_proposal_votes(
+    data: &[u8],
+    proposal_id: &[u8; HASH_SIZE],
+    account: &[u8; ACCOUNT_ID_SIZE],
+) -> u64 {
+    let mut key_buf = [0u8; SNAPSHOT_KEY_LEN];
+    build_snapshot_key(proposal_id, account, &mut key_buf);
 
-    // Copy existing data
-    if data_len > 0 {
-        new_data[..data_len].copy_from_slice(&data[..data_len]);
-        pos = data_len;
+    match find_value(data, &key_buf).and_then(parse_u64) {
+        Some(power) => power,
+        None => get_effective_votes(data, account),
     }
+}
 
-    let mut val_buf = [0u8; 20];
-    let vlen = format_u64(power, &mut val_buf);
+/// Append `account`'s effective votes in `store` to its checkpoint
+/// history at `current_time`. Call after any write that changes them.
+pub fn checkpoint_votes(
+    store: &mut Store,
+    account: &[u8; ACCOUNT_ID_SIZE],
+    current_time: u32,
+) -> Result<(), i32> {
+    let power = get_effective_votes(store.as_bytes(), account);
+    let mut key_buf = [0u8; CHECKPOINT_KEY_LEN];
+    build_checkpoint_key(account, &mut key_buf);
+    push_checkpoint(store, &key_buf, current_time, power)
+}
 
-    if pos > 0 { pos = write_separator(&mut new_data, pos); }
-    pos = write_entry(&mut new_data, pos, &key_buf[..kpos], &val_buf[..vlen]);
+/// Append the total voting power in `store` to `ckpt_total`.
+pub fn checkpoint_total_supply(store: &mut Store, current_time: u32) -> Result<(), i32> {
+    let power = votes::get_total_voting_power(store.as_bytes());
+    push_checkpoint(store, TOTAL_CHECKPOINT_KEY, current_time, power)
+}
 
-    Ok((new_data, pos))
+/// Drop checkpoints superseded before the horizon from every history.
+/// `prune` calls this to reclaim history kept for proposals since closed.
+pub fn compact_checkpoints(store: &mut Store, current_time: u32) -> Result<(), i32> {
+    let mut data = [0u8; DATA_CAPACITY];
+    let len = store.len();
+    data[..len].copy_from_slice(store.as_bytes());
+    let horizon = checkpoint_horizon(&data[..len], current_time);
+    for (key, history) in iter_prefix(&data[..len], b"ckpt_") {
+        let start = retained_start(history, horizon);
+        if start > 0 {
+            store.set(key, &history[start..])?;
+        }
+    }
+    Ok(())
 }
 
-/// Get snapshotted voting power for an account at a specific proposal.
-pub fn get_snapshot_votes(
+/// Effective votes of `account` as of `timepoint`: the power of its last
+/// checkpoint at or before it, or 0 if there is none.
+/// Mirrors ERC20Votes.getPastVotes().
+pub fn get_past_votes(
     data: &[u8],
-    proposal_id: u32,
     account: &[u8; ACCOUNT_ID_SIZE],
+    timepoint: u32,
 ) -> u64 {
-    let mut account_hex = [0u8; 40];
-    encode_hex(account, &mut account_hex);
+    let mut key_buf = [0u8; CHECKPOINT_KEY_LEN];
+    build_checkpoint_key(account, &mut key_buf);
+    find_value(data, &key_buf).map_or(0, |history| lookup_checkpoint(history, timepoint))
+}
 
-    let mut key_buf = [0u8; 64];
-    let prefix = b"snap_";
-    let mut kpos = prefix.len();
-    key_buf[..kpos].copy_from_slice(prefix);
+/// Total voting power as of `timepoint`.
+/// Mirrors ERC20Votes.getPastTotalSupply().
+pub fn get_past_total_supply(data: &[u8], timepoint: u32) -> u64 {
+    find_value(data, TOTAL_CHECKPOINT_KEY).map_or(0, |history| lookup_checkpoint(history, timepoint))
+}
 
-    let mut id_buf = [0u8; 10];
-    let id_len = format_u32(proposal_id, &mut id_buf);
-    key_buf[kpos..kpos + id_len].copy_from_slice(&id_buf[..id_len]);
-    kpos += id_len;
+// ——— Internal helpers ———
 
-    key_buf[kpos] = b'_';
-    kpos += 1;
+/// Key of the total voting power history.
+const TOTAL_CHECKPOINT_KEY: &[u8] = b"ckpt_total";
 
-    key_buf[kpos..kpos + 40].copy_from_slice(&account_hex);
-    kpos += 40;
+/// Length of "ckpt_<hex40>".
+const CHECKPOINT_KEY_LEN: usize = 5 + ACCOUNT_ID_SIZE * 2;
 
-    find_value(data, &key_buf[..kpos])
-        .and_then(|v| parse_u64(v))
-        .unwrap_or(0)
+/// Longest "<time>:<power>" checkpoint, with its leading comma.
+const CHECKPOINT_TEXT_LEN: usize = 1 + 10 + 1 + 20;
+
+/// Build "ckpt_<account hex40>".
+fn build_checkpoint_key(account: &[u8; ACCOUNT_ID_SIZE], out: &mut [u8; CHECKPOINT_KEY_LEN]) {
+    out[..5].copy_from_slice(b"ckpt_");
+    encode_hex(account, &mut out[5..]);
+}
+
+/// Parse one "<time>:<power>" checkpoint.
+fn parse_checkpoint(text: &[u8]) -> Option<(u32, u64)> {
+    let colon = text.iter().position(|&b| b == b':')?;
+    Some((parse_u32(&text[..colon])?, parse_u64(&text[colon + 1..])?))
+}
+
+/// Append `time:power` to the history at `key`, unless the power is
+/// unchanged. A checkpoint at the same time replaces the last one.
+fn push_checkpoint(store: &mut Store, key: &[u8], time: u32, power: u64) -> Result<(), i32> {
+    let mut history = [0u8; DATA_CAPACITY];
+    let mut len = 0;
+    if let Some(old) = store.get(key) {
+        history[..old.len()].copy_from_slice(old);
+        len = old.len();
+    }
+
+    let (last_start, mut last) = last_checkpoint(&history[..len]);
+    if last.map_or(0, |(_, p)| p) == power {
+        return Ok(());
+    }
+    if last.is_some_and(|(t, _)| t == time) {
+        len = last_start.saturating_sub(1);
+        last = last_checkpoint(&history[..len]).1;
+    }
+
+    if last.map_or(0, |(_, p)| p) != power {
+        if len + CHECKPOINT_TEXT_LEN > history.len() {
+            return Err(ERR_DATA_FULL);
+        }
+        if len > 0 {
+            history[len] = b',';
+            len += 1;
+        }
+        len += format_u32(time, &mut history[len..]);
+        history[len] = b':';
+        len += 1;
+        len += format_u64(power, &mut history[len..]);
+    }
+
+    if len == 0 {
+        store.remove(key);
+        return Ok(());
+    }
+    let start = capped_start(&history[..len]);
+    store.set(key, &history[start..len])
+}
+
+/// Offset of the oldest of the last MAX_CHECKPOINTS checkpoints.
+fn capped_start(history: &[u8]) -> usize {
+    let mut commas = history.iter().enumerate().rev().filter(|&(_, &b)| b == b',');
+    commas.nth(MAX_CHECKPOINTS - 1).map_or(0, |(i, _)| i + 1)
+}
+
+/// Earliest timepoint lookups must still answer: the `vote_start` of the
+/// oldest Pending or Active proposal, or `current_time` if none is open.
+fn checkpoint_horizon(data: &[u8], current_time: u32) -> u32 {
+    (0..read_count(data, b"proposal_count"))
+        .filter(|&i| matches!(
+            get_proposal_state(data, i, current_time),
+            PROPOSAL_STATE_PENDING | PROPOSAL_STATE_ACTIVE
+        ))
+        .filter_map(|i| Proposal::load(data, i))
+        .fold(current_time, |horizon, proposal| horizon.min(proposal.vote_start))
+}
+
+/// Offset of the first checkpoint to keep: the last one at or before
+/// `horizon`, which is still in force there. Earlier ones are superseded.
+fn retained_start(history: &[u8], horizon: u32) -> usize {
+    let mut start = 0;
+    let mut pos = 0;
+    for checkpoint in history.split(|&b| b == b',') {
+        match parse_checkpoint(checkpoint) {
+            Some((time, _)) if time <= horizon => start = pos,
+            _ => break,
+        }
+        pos += checkpoint.len() + 1;
+    }
+    start
+}
+
+/// Start and value of the last checkpoint in a history.
+fn last_checkpoint(history: &[u8]) -> (usize, Option<(u32, u64)>) {
+    let start = history.iter().rposition(|&b| b == b',').map_or(0, |i| i + 1);
+    (start, parse_checkpoint(&history[start..]))
+}
+
+/// Binary search a `<time>:<power>,...` history (ascending times) for the
+/// power in force at `timepoint`. Probes land mid-byte and widen to the
+/// surrounding checkpoint, so no index is needed.
+fn lookup_checkpoint(history: &[u8], timepoint: u32) -> u64 {
+    let (mut lo, mut hi) = (0, history.len());
+    let mut power = 0;
+    while lo < hi {
+        let mid = lo + (hi - lo) / 2;
+        let start = history[lo..mid].iter().rposition(|&b| b == b',').map_or(lo, |i| lo + i + 1);
+        let end = history[mid..hi].iter().position(|&b| b == b',').map_or(hi, |i| mid + i);
+        let Some((time, votes)) = parse_checkpoint(&history[start..end]) else { return 0 };
+        if time <= timepoint {
+            power = votes;
+            lo = end + 1;
+        } else {
+            hi = start;
+        }
+    }
+    power
+}
+
+/// Insert `snap_* = power` unless a snapshot already exists.
+fn insert_snapshot(store: &mut Store, key: &[u8], power: u64) -> Result<(), i32> {
+    if store.get(key).is_none() {
+        let mut val_buf = [0u8; 20];
+        let vlen = format_u64(power, &mut val_buf);
+        store.insert(key, &val_buf[..vlen])?;
+    }
+    Ok(())
+}
+
+/// Length of "snap_<hex64>_<hex40>".
+const SNAPSHOT_KEY_LEN: usize = 5 + HASH_SIZE * 2 + 1 + ACCOUNT_ID_SIZE * 2;
+
+/// Build "snap_<prop_id hex64>_<account hex40>".
+fn build_snapshot_key(
+    proposal_id: &[u8; HASH_SIZE],
+    account: &[u8; ACCOUNT_ID_SIZE],
+    out: &mut [u8; SNAPSHOT_KEY_LEN],
+) {
+    out[..5].copy_from_slice(b"snap_");
+    encode_hex(proposal_id, &mut out[5..5 + HASH_SIZE * 2]);
+    out[5 + HASH_SIZE * 2] = b'_';
+    encode_hex(account, &mut out[5 + HASH_SIZE * 2 + 1..]);
 }